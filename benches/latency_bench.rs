@@ -111,6 +111,12 @@ fn bench_end_to_end(c: &mut Criterion) {
 
 fn bench_timestamp(c: &mut Criterion) {
     c.bench_function("timestamp_rdtsc", |b| {
+        b.iter(|| {
+            black_box(hedging_engine::utils::read_cycles());
+        });
+    });
+
+    c.bench_function("timestamp_calibrated_ns", |b| {
         b.iter(|| {
             black_box(get_timestamp_ns());
         });
@@ -157,6 +163,19 @@ fn bench_mvhr(c: &mut Criterion) {
             black_box(mvhr.calculate_optimal_ratio());
         });
     });
+
+    // Full window: every observation evicts one
+    for i in 0..720 {
+        mvhr.add_observation(45.0 + (i % 13) as f64 * 0.1, 50.0 + (i % 11) as f64 * 0.12);
+    }
+    let mut i = 0u64;
+    c.bench_function("mvhr_observe_and_refresh", |b| {
+        b.iter(|| {
+            i += 1;
+            mvhr.add_observation(45.0 + (i % 13) as f64 * 0.1, 50.0 + (i % 11) as f64 * 0.12);
+            black_box(mvhr.calculate_optimal_ratio());
+        });
+    });
 }
 
 fn bench_mean_reversion(c: &mut Criterion) {
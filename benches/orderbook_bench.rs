@@ -34,6 +34,24 @@ fn bench_orderbook_read(c: &mut Criterion) {
             black_box(ob.mid_price());
         });
     });
+
+    c.bench_function("orderbook_snapshot_10", |b| {
+        b.iter(|| {
+            black_box(ob.snapshot(black_box(10)));
+        });
+    });
+
+    c.bench_function("orderbook_microprice", |b| {
+        b.iter(|| {
+            black_box(ob.microprice());
+        });
+    });
+
+    c.bench_function("orderbook_imbalance_5", |b| {
+        b.iter(|| {
+            black_box(ob.imbalance(black_box(5)));
+        });
+    });
 }
 
 fn bench_orderbook_throughput(c: &mut Criterion) {
@@ -1,8 +1,8 @@
 //! Backtesting example with historical data
 
+use hedging_engine::market_data::TickCsvReader;
+use hedging_engine::utils::SimulatedClock;
 use hedging_engine::*;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::time::{Duration, Instant};
 
 fn main() -> Result<()> {
@@ -22,7 +22,10 @@ fn main() -> Result<()> {
         ..Default::default()
     };
 
-    let engine: HedgeEngine = HedgeEngine::new(config)?;
+    // Time follows the tick timestamps, so reruns are reproducible
+    let clock = SimulatedClock::shared(0);
+    let engine: HedgeEngine =
+        HedgeEngine::with_clock(config, InstrumentRegistry::default(), clock)?;
 
     println!("Configuration:");
     println!("  Initial Position: -10,000 MWh");
@@ -39,26 +42,27 @@ fn main() -> Result<()> {
     // Process ticks
     for (i, tick) in ticks.iter().enumerate() {
         engine.on_tick(*tick);
+        engine.recalibrate();
 
         // Check for hedge every 100 ticks
-        if i % 100 == 0 {
-            if let Some(rec) = engine.get_hedge_recommendation()? {
-                hedge_count += 1;
-                total_hedge_volume += rec.quantity;
-                engine.execute_hedge(&rec)?;
-
-                if hedge_count <= 5 {
-                    println!(
-                        "Hedge #{}: {} {:.0} MWh @ €{:.2}",
-                        hedge_count,
-                        match rec.side {
-                            Side::Bid => "SELL",
-                            Side::Ask => "BUY",
-                        },
-                        rec.quantity,
-                        rec.price
-                    );
-                }
+        if i % 100 == 0
+            && let Some(rec) = engine.get_hedge_recommendation()?
+        {
+            hedge_count += 1;
+            total_hedge_volume += rec.quantity;
+            engine.execute_hedge(&rec)?;
+
+            if hedge_count <= 5 {
+                println!(
+                    "Hedge #{}: {} {:.0} MWh @ €{:.2}",
+                    hedge_count,
+                    match rec.side {
+                        Side::Bid => "SELL",
+                        Side::Ask => "BUY",
+                    },
+                    rec.quantity,
+                    rec.price
+                );
             }
         }
 
@@ -128,35 +132,7 @@ fn load_sample_data() -> Result<Vec<MarketTick>> {
 }
 
 fn load_from_csv(path: &str) -> Result<Vec<MarketTick>> {
-    let file: File =
-        File::open(path).map_err(|e| Error::MarketData(format!("Failed to open file: {}", e)))?;
-
-    let reader: BufReader<File> = BufReader::new(file);
-    let mut ticks: Vec<MarketTick> = Vec::new();
-
-    for line in reader.lines().skip(1) {
-        let line = line.map_err(|e| Error::MarketData(format!("Failed to read line: {}", e)))?;
-        let parts: Vec<&str> = line.split(',').collect();
-
-        if parts.len() < 5 {
-            continue;
-        }
-
-        let timestamp: u64 = parts[0].parse().unwrap_or(0);
-        let symbol_id: u8 = parts[1].parse().unwrap_or(1);
-        let price: f64 = parts[2].parse().unwrap_or(45.0);
-        let quantity: u32 = parts[3].parse().unwrap_or(100);
-
-        let tick: MarketTick = if parts[4] == "bid" {
-            MarketTick::bid(timestamp, price, quantity, symbol_id)
-        } else {
-            MarketTick::ask(timestamp, price, quantity, symbol_id)
-        };
-
-        ticks.push(tick);
-    }
-
-    Ok(ticks)
+    TickCsvReader::open(path)?.collect()
 }
 
 fn generate_synthetic_data(count: usize) -> Vec<MarketTick> {
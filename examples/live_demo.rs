@@ -23,6 +23,7 @@ fn main() -> Result<()> {
     };
 
     let engine: HedgeEngine = HedgeEngine::new(config)?;
+    engine.start_recalibration()?;
 
     println!("Configuration:");
     println!("  Position: -10,000 MWh (SHORT)");
@@ -45,8 +46,8 @@ fn main() -> Result<()> {
         let spot_delta: f64 = simulate_price_change(iteration, 0.15);
         let futures_delta: f64 = simulate_price_change(iteration + 1, 0.18);
 
-        spot_price = (spot_price + spot_delta).max(30.0).min(70.0);
-        futures_price = (futures_price + futures_delta).max(35.0).min(75.0);
+        spot_price = (spot_price + spot_delta).clamp(30.0, 70.0);
+        futures_price = (futures_price + futures_delta).clamp(35.0, 75.0);
 
         // Send market data
         let ts: u64 = get_timestamp_ns();
@@ -62,7 +63,7 @@ fn main() -> Result<()> {
         engine.on_tick(MarketTick::ask(ts, futures_price + 0.05, 130, 2));
 
         // Display every 10 iterations
-        if iteration % 10 == 0 {
+        if iteration.is_multiple_of(10) {
             clear_screen();
             display_dashboard(&engine, iteration, spot_price, futures_price)?;
 
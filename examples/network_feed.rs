@@ -19,16 +19,31 @@ fn main() -> Result<()> {
     match TcpMarketDataFeed::connect("127.0.0.1:5555") {
         Ok(mut feed) => {
             println!("✓ Connected!\n");
+
+            // Mark books stale on sequence gaps
+            for symbol_id in [1, 2] {
+                if let Some(orderbook) = engine.shared_orderbook(symbol_id) {
+                    feed.attach_orderbook(orderbook);
+                }
+            }
+
             println!("Receiving market data...\n");
 
             let mut tick_count = 0;
 
             loop {
                 match feed.read_tick()? {
-                    Some(tick) => {
-                        engine.on_tick(tick);
+                    Some(received) => {
+                        engine.on_received_tick(received);
                         tick_count += 1;
 
+                        for gap in feed.take_gaps() {
+                            println!(
+                                "  GAP: channel {} missing {}..={}",
+                                gap.channel, gap.first, gap.last
+                            );
+                        }
+
                         if tick_count % 100 == 0 {
                             println!("Processed {} ticks", tick_count);
 
@@ -55,7 +55,7 @@ fn main() {
                 local_count += 1;
 
                 // Report every 10k ticks
-                if local_count % 10_000 == 0 {
+                if local_count.is_multiple_of(10_000) {
                     counter.fetch_add(10_000, Ordering::Relaxed);
                 }
             }
@@ -89,7 +89,7 @@ fn main() {
                 engine.on_tick(tick);
                 local_count += 1;
 
-                if local_count % 10_000 == 0 {
+                if local_count.is_multiple_of(10_000) {
                     counter.fetch_add(10_000, Ordering::Relaxed);
                 }
             }
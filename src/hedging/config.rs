@@ -1,4 +1,8 @@
-use crate::market_data::Side;
+use crate::hedging::{
+    CrossHedgeStrategy, HedgeConstraints, PriceChangeMode, RatioAcceptance, RatioEstimator,
+    RecalibrationConfig, SamplingConfig,
+};
+use crate::market_data::{FillEstimate, Instrument, InstrumentRegistry, ReferencePrice, Side};
 use serde::{Deserialize, Serialize};
 
 /// Hedge urgency level
@@ -32,6 +36,40 @@ pub struct HedgeRecommendation {
 
     /// Timestamp when recommendation made
     pub timestamp_ns: u64,
+
+    /// Symbol ID of the hedge instrument (0 if unresolved)
+    #[serde(default)]
+    pub symbol_id: u8,
+
+    /// Quantity expressed in contract lots
+    #[serde(default)]
+    pub lots: f64,
+
+    /// Index of the exposure this recommendation hedges
+    /// (unused when `cross_hedge_id` is set)
+    #[serde(default)]
+    pub exposure_id: usize,
+
+    /// Index of the cross hedge this recommendation hedges (None for
+    /// single-instrument exposures)
+    #[serde(default)]
+    pub cross_hedge_id: Option<usize>,
+
+    /// Hedge leg of a cross hedge (None for single-instrument exposures)
+    #[serde(default)]
+    pub leg: Option<usize>,
+
+    /// Expected average execution price from visible depth (€/MWh)
+    #[serde(default)]
+    pub expected_price: f64,
+
+    /// Expected cost vs. mid in basis points (positive = worse than mid)
+    #[serde(default)]
+    pub slippage_bps: f64,
+
+    /// Fraction of the quantity covered by visible depth (0.0 - 1.0)
+    #[serde(default)]
+    pub liquidity_coverage: f64,
 }
 
 impl HedgeRecommendation {
@@ -51,8 +89,170 @@ impl HedgeRecommendation {
             urgency,
             reason,
             timestamp_ns,
+            symbol_id: 0,
+            lots: quantity,
+            exposure_id: 0,
+            cross_hedge_id: None,
+            leg: None,
+            expected_price: price,
+            slippage_bps: 0.0,
+            liquidity_coverage: 1.0,
         }
     }
+
+    /// Apply a depth-aware execution estimate
+    ///
+    /// `reference` is the price slippage is measured against, normally the
+    /// mid. Without any fillable depth the expected price stays at `price`.
+    pub fn with_fill(mut self, fill: &FillEstimate, reference: f64) -> Self {
+        self.expected_price = fill.vwap.unwrap_or(self.price);
+        self.liquidity_coverage = fill.coverage();
+
+        self.slippage_bps = if reference != 0.0 {
+            let cost = match self.side {
+                Side::Ask => self.expected_price - reference,
+                Side::Bid => reference - self.expected_price,
+            };
+            cost / reference.abs() * 10000.0
+        } else {
+            0.0
+        };
+
+        self
+    }
+
+    /// Resolve the recommendation against an instrument
+    ///
+    /// Sets the symbol ID, converts the quantity to lots and rounds the
+    /// price to the instrument's tick size.
+    pub fn with_instrument(mut self, instrument: &Instrument) -> Self {
+        self.symbol_id = instrument.symbol_id;
+        self.lots = instrument.quantity_to_lots(self.quantity);
+        self.price = instrument.round_to_tick(self.price);
+        self
+    }
+}
+
+/// Price series feeding MVHR and mean reversion statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PriceSource {
+    /// Quote updates (orderbook mid / quoted price)
+    #[default]
+    Quotes,
+    /// Last-trade prints
+    Trades,
+}
+
+/// Action taken when a book fails the health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UnhealthyBookAction {
+    /// Return no recommendation
+    #[default]
+    Suppress,
+    /// Return `Error::UnhealthyBook`
+    Reject,
+}
+
+/// A physical exposure and the instrument used to hedge it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureConfig {
+    /// Exposure name (e.g., "DE Base Q1")
+    pub name: String,
+
+    /// Symbol ID of the exposure (spot) instrument
+    pub symbol_id: u8,
+
+    /// Symbol ID of the hedge (futures) instrument
+    pub hedge_symbol_id: u8,
+
+    /// Initial position (MWh, negative = short)
+    pub position: f64,
+
+    /// Hedge ratio (e.g., 1.125)
+    pub hedge_ratio: f64,
+}
+
+impl ExposureConfig {
+    /// Create a new exposure
+    pub fn new(name: &str, symbol_id: u8, hedge_symbol_id: u8, position: f64, ratio: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol_id,
+            hedge_symbol_id,
+            position,
+            hedge_ratio: ratio,
+        }
+    }
+
+    /// Validate exposure
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.hedge_ratio <= 0.0 {
+            return Err(crate::Error::Config(format!(
+                "Exposure {}: hedge ratio must be positive",
+                self.name
+            )));
+        }
+
+        if self.symbol_id == self.hedge_symbol_id {
+            return Err(crate::Error::Config(format!(
+                "Exposure {}: exposure and hedge symbols must differ",
+                self.name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// An exposure hedged with a minimum-variance mix of several instruments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossHedgeConfig {
+    /// Exposure name (e.g., "NL Base Cal-26")
+    pub name: String,
+
+    /// Symbol ID of the exposure (spot) instrument
+    pub symbol_id: u8,
+
+    /// Symbol IDs of the hedge instruments, one per leg
+    pub hedge_symbol_ids: Vec<u8>,
+
+    /// Initial position (MWh, negative = short)
+    pub position: f64,
+}
+
+impl CrossHedgeConfig {
+    /// Create a new cross hedge
+    pub fn new(name: &str, symbol_id: u8, hedge_symbol_ids: &[u8], position: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol_id,
+            hedge_symbol_ids: hedge_symbol_ids.to_vec(),
+            position,
+        }
+    }
+
+    /// Validate cross hedge
+    pub fn validate(&self) -> crate::Result<()> {
+        let legs = self.hedge_symbol_ids.len();
+        if legs == 0 || legs > CrossHedgeStrategy::MAX_LEGS {
+            return Err(crate::Error::Config(format!(
+                "Cross hedge {}: needs 1 to {} hedge instruments",
+                self.name,
+                CrossHedgeStrategy::MAX_LEGS
+            )));
+        }
+
+        for (i, &symbol_id) in self.hedge_symbol_ids.iter().enumerate() {
+            if symbol_id == self.symbol_id || self.hedge_symbol_ids[..i].contains(&symbol_id) {
+                return Err(crate::Error::Config(format!(
+                    "Cross hedge {}: exposure and hedge symbols must all differ",
+                    self.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Hedge engine configuration
@@ -77,8 +277,78 @@ pub struct HedgeConfig {
     /// Enable mean reversion
     pub enable_mean_reversion: bool,
 
-    /// Look back window for statistics (hours)
+    /// Look back window for statistics (wall-clock hours)
     pub statistics_window_hours: usize,
+
+    /// Sampling of prices into MVHR and mean reversion observations
+    /// Defaults to hourly closes
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+
+    /// Price change measure for MVHR
+    /// Use `Absolute` for markets that clear at zero or negative prices
+    #[serde(default)]
+    pub mvhr_change_mode: PriceChangeMode,
+
+    /// Covariance estimator behind the MVHR ratio
+    /// Defaults to the equal-weighted window (OLS)
+    #[serde(default)]
+    pub ratio_estimator: RatioEstimator,
+
+    /// Checks a new MVHR ratio must pass before it is applied
+    /// None = apply every estimate
+    #[serde(default)]
+    pub ratio_acceptance: Option<RatioAcceptance>,
+
+    /// Policy limits on hedge ratios, lots and notional
+    /// Defaults to unconstrained
+    #[serde(default)]
+    pub constraints: HedgeConstraints,
+
+    /// Recalibration schedule of MVHR and mean reversion
+    #[serde(default)]
+    pub recalibration: RecalibrationConfig,
+
+    /// Price series feeding MVHR and mean reversion
+    #[serde(default)]
+    pub price_source: PriceSource,
+
+    /// Book fair value feeding MVHR and mean reversion from quotes
+    /// If None, mean reversion gets the quoted price and MVHR the mid
+    #[serde(default)]
+    pub reference_price: Option<ReferencePrice>,
+
+    /// Handling of recommendations priced off unhealthy books
+    /// Limits are set per instrument (`Instrument::health`)
+    #[serde(default)]
+    pub unhealthy_book_action: UnhealthyBookAction,
+
+    /// Symbol ID of the exposure (spot) instrument
+    #[serde(default = "default_spot_symbol_id")]
+    pub spot_symbol_id: u8,
+
+    /// Symbol ID of the hedge (futures) instrument
+    #[serde(default = "default_futures_symbol_id")]
+    pub futures_symbol_id: u8,
+
+    /// Exposures to hedge
+    /// If empty, a single exposure is built from `initial_position`,
+    /// `default_hedge_ratio`, `spot_symbol_id` and `futures_symbol_id`
+    #[serde(default)]
+    pub exposures: Vec<ExposureConfig>,
+
+    /// Exposures hedged across several instruments
+    /// Ratios come from the cross-hedge estimator, recalibrated on the MVHR schedule
+    #[serde(default)]
+    pub cross_hedges: Vec<CrossHedgeConfig>,
+}
+
+fn default_spot_symbol_id() -> u8 {
+    InstrumentRegistry::DEFAULT_SPOT_ID
+}
+
+fn default_futures_symbol_id() -> u8 {
+    InstrumentRegistry::DEFAULT_FUTURES_ID
 }
 
 impl Default for HedgeConfig {
@@ -91,6 +361,19 @@ impl Default for HedgeConfig {
             enable_mvhr: true,
             enable_mean_reversion: false,
             statistics_window_hours: 720, // 30 days
+            sampling: SamplingConfig::hourly(),
+            mvhr_change_mode: PriceChangeMode::Relative,
+            ratio_estimator: RatioEstimator::Ols,
+            ratio_acceptance: None,
+            constraints: HedgeConstraints::default(),
+            recalibration: RecalibrationConfig::default(),
+            price_source: PriceSource::Quotes,
+            reference_price: None,
+            unhealthy_book_action: UnhealthyBookAction::Suppress,
+            spot_symbol_id: InstrumentRegistry::DEFAULT_SPOT_ID,
+            futures_symbol_id: InstrumentRegistry::DEFAULT_FUTURES_ID,
+            exposures: Vec::new(),
+            cross_hedges: Vec::new(),
         }
     }
 }
@@ -105,6 +388,24 @@ impl HedgeConfig {
         }
     }
 
+    /// Get the exposures the engine will manage
+    ///
+    /// The first exposure is the primary one served by the single-exposure
+    /// engine API.
+    pub fn resolved_exposures(&self) -> Vec<ExposureConfig> {
+        if self.exposures.is_empty() {
+            vec![ExposureConfig::new(
+                "default",
+                self.spot_symbol_id,
+                self.futures_symbol_id,
+                self.initial_position,
+                self.default_hedge_ratio,
+            )]
+        } else {
+            self.exposures.clone()
+        }
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> crate::Result<()> {
         if self.default_hedge_ratio <= 0.0 {
@@ -125,6 +426,40 @@ impl HedgeConfig {
             ));
         }
 
+        if self.statistics_window_hours == 0
+            || self.sampling.interval_secs > self.statistics_window_hours as u64 * 3600
+        {
+            return Err(crate::Error::Config(
+                "Statistics window must cover at least one sampling interval".to_string(),
+            ));
+        }
+
+        self.ratio_estimator.validate()?;
+        self.constraints.validate()?;
+        if let Some(ref acceptance) = self.ratio_acceptance {
+            acceptance.validate()?;
+        }
+
+        if self.recalibration.poll_interval_ms == 0 {
+            return Err(crate::Error::Config(
+                "Recalibration poll interval must be positive".to_string(),
+            ));
+        }
+
+        if self.spot_symbol_id == self.futures_symbol_id {
+            return Err(crate::Error::Config(
+                "Spot and futures symbols must differ".to_string(),
+            ));
+        }
+
+        for exposure in &self.exposures {
+            exposure.validate()?;
+        }
+
+        for cross_hedge in &self.cross_hedges {
+            cross_hedge.validate()?;
+        }
+
         Ok(())
     }
 }
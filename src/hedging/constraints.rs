@@ -0,0 +1,594 @@
+//! Minimum-variance hedge allocation under policy constraints
+
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Covariance of spot and hedge leg changes
+///
+/// For ratios h the variance of hedged changes is
+/// Var(ΔS) - 2 σ_FS'h + h'Σ_FF h.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeCovariance {
+    /// Σ_FF: covariance matrix of the leg changes
+    pub futures: DMatrix<f64>,
+
+    /// σ_FS: covariance of each leg's changes with the spot changes
+    pub cross: DVector<f64>,
+
+    /// Var(ΔS)
+    pub spot_variance: f64,
+}
+
+impl Default for HedgeCovariance {
+    fn default() -> Self {
+        Self {
+            futures: DMatrix::zeros(0, 0),
+            cross: DVector::zeros(0),
+            spot_variance: 0.0,
+        }
+    }
+}
+
+impl HedgeCovariance {
+    /// Create from Σ_FF, σ_FS and Var(ΔS)
+    pub fn new(futures: DMatrix<f64>, cross: DVector<f64>, spot_variance: f64) -> Self {
+        Self {
+            futures,
+            cross,
+            spot_variance,
+        }
+    }
+
+    /// One leg whose minimum-variance ratio is `ratio`
+    pub fn single(futures_variance: f64, spot_variance: f64, ratio: f64) -> Self {
+        Self::new(
+            DMatrix::from_element(1, 1, futures_variance),
+            DVector::from_element(1, ratio * futures_variance),
+            spot_variance,
+        )
+    }
+
+    /// Unit covariance centred on `ratios`
+    ///
+    /// Without estimates, the constrained allocation is then the feasible
+    /// point nearest to `ratios`.
+    pub fn target(ratios: &[f64]) -> Self {
+        let cross = DVector::from_column_slice(ratios);
+        let spot_variance = cross.norm_squared();
+        Self::new(
+            DMatrix::identity(ratios.len(), ratios.len()),
+            cross,
+            spot_variance,
+        )
+    }
+
+    /// Number of hedge legs
+    pub fn legs(&self) -> usize {
+        self.cross.len()
+    }
+
+    /// Variance of hedged changes for `ratios`
+    pub fn hedged_variance(&self, ratios: &DVector<f64>) -> f64 {
+        let variance = self.spot_variance - 2.0 * self.cross.dot(ratios)
+            + ratios.dot(&(&self.futures * ratios));
+        variance.max(0.0)
+    }
+}
+
+/// Hedge policy limits
+///
+/// The default is unconstrained.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HedgeConstraints {
+    /// Lowest allowed hedge ratio per leg (None = unbounded)
+    #[serde(default)]
+    pub min_ratio: Option<f64>,
+
+    /// Highest allowed hedge ratio per leg (None = unbounded)
+    #[serde(default)]
+    pub max_ratio: Option<f64>,
+
+    /// Trade whole contract lots only
+    #[serde(default)]
+    pub whole_lots: bool,
+
+    /// Maximum hedge notional per instrument (currency), by symbol ID
+    #[serde(default)]
+    pub max_notional: HashMap<u8, f64>,
+}
+
+impl HedgeConstraints {
+    /// Keep hedge ratios within [min, max]
+    pub fn corridor(min_ratio: f64, max_ratio: f64) -> Self {
+        Self {
+            min_ratio: Some(min_ratio),
+            max_ratio: Some(max_ratio),
+            ..Default::default()
+        }
+    }
+
+    /// Round quantities to whole contract lots
+    pub fn with_whole_lots(mut self) -> Self {
+        self.whole_lots = true;
+        self
+    }
+
+    /// Cap the hedge notional in one instrument
+    pub fn with_max_notional(mut self, symbol_id: u8, notional: f64) -> Self {
+        self.max_notional.insert(symbol_id, notional);
+        self
+    }
+
+    /// Check whether no constraint is set
+    pub fn is_unconstrained(&self) -> bool {
+        self.min_ratio.is_none()
+            && self.max_ratio.is_none()
+            && !self.whole_lots
+            && self.max_notional.is_empty()
+    }
+
+    /// Validate constraints
+    pub fn validate(&self) -> crate::Result<()> {
+        let min = self.min_ratio.unwrap_or(f64::NEG_INFINITY);
+        let max = self.max_ratio.unwrap_or(f64::INFINITY);
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(crate::Error::Config(
+                "Hedge ratio corridor must satisfy min <= max".to_string(),
+            ));
+        }
+
+        if self
+            .max_notional
+            .values()
+            .any(|&notional| notional.is_nan() || notional < 0.0)
+        {
+            return Err(crate::Error::Config(
+                "Maximum notional must be non-negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Minimum-variance ratios for `position` within the constraints
+    ///
+    /// `legs` gives the market terms of each hedge leg, in the order of
+    /// the covariance. The continuous optimum over the ratio box is found
+    /// by projected coordinate descent; with `whole_lots` it is then moved
+    /// to the best neighbouring lot grid point by local search.
+    ///
+    /// Fails if the covariance is singular or the constraints leave no
+    /// feasible allocation.
+    ///
+    /// # Performance
+    /// Cold path: O(N³) solve plus O(N²) per sweep.
+    pub fn allocate(
+        &self,
+        covariance: &HedgeCovariance,
+        position: f64,
+        legs: &[LegTerms],
+    ) -> crate::Result<ConstrainedAllocation> {
+        let n = legs.len();
+        if covariance.legs() != n || covariance.futures.shape() != (n, n) {
+            return Err(crate::Error::Config(format!(
+                "Covariance has {} legs, expected {}",
+                covariance.legs(),
+                n
+            )));
+        }
+
+        let unconstrained = covariance
+            .futures
+            .clone()
+            .cholesky()
+            .map(|cholesky| cholesky.solve(&covariance.cross))
+            .filter(|ratios| ratios.iter().all(|r| r.is_finite()))
+            .ok_or_else(|| crate::Error::Calculation("Hedge covariance is singular".to_string()))?;
+
+        let bounds = legs
+            .iter()
+            .map(|leg| self.bounds(leg, position))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut ratios = DVector::from_iterator(
+            n,
+            unconstrained
+                .iter()
+                .zip(&bounds)
+                .map(|(&ratio, bounds)| bounds.clamp(ratio)),
+        );
+        Self::descend(covariance, &bounds, &mut ratios);
+
+        let continuous = ratios.clone();
+        if self.whole_lots && position != 0.0 {
+            let steps: Vec<f64> = legs
+                .iter()
+                .map(|leg| leg.contract_size / position.abs())
+                .collect();
+            Self::round_to_lots(covariance, &bounds, &steps, legs, &mut ratios)?;
+        }
+
+        // Bound sources whose constraint holds the optimum back (KKT sign)
+        let gradient = &covariance.futures * &continuous - &covariance.cross;
+        let allocations = (0..n)
+            .map(|i| {
+                let tolerance = 1e-9 * continuous[i].abs().max(1.0);
+                let binding = if (continuous[i] - bounds[i].max).abs() <= tolerance
+                    && gradient[i] < -1e-12
+                {
+                    bounds[i].max_source
+                } else if (continuous[i] - bounds[i].min).abs() <= tolerance && gradient[i] > 1e-12
+                {
+                    bounds[i].min_source
+                } else if (ratios[i] - continuous[i]).abs() > tolerance {
+                    Binding::LotSize
+                } else {
+                    Binding::Free
+                };
+
+                let quantity = ratios[i] * position.abs();
+                LegAllocation {
+                    symbol_id: legs[i].symbol_id,
+                    ratio: ratios[i],
+                    unconstrained_ratio: unconstrained[i],
+                    quantity,
+                    lots: quantity / legs[i].contract_size,
+                    notional: (quantity * legs[i].price).abs(),
+                    binding,
+                }
+            })
+            .collect();
+
+        Ok(ConstrainedAllocation {
+            legs: allocations,
+            hedged_variance: covariance.hedged_variance(&ratios),
+            unconstrained_variance: covariance.hedged_variance(&unconstrained),
+        })
+    }
+
+    /// Ratio bounds of one leg
+    fn bounds(&self, leg: &LegTerms, position: f64) -> crate::Result<RatioBounds> {
+        let mut bounds = RatioBounds {
+            min: self.min_ratio.unwrap_or(f64::NEG_INFINITY),
+            max: self.max_ratio.unwrap_or(f64::INFINITY),
+            min_source: Binding::MinRatio,
+            max_source: Binding::MaxRatio,
+        };
+
+        let exposure = (position * leg.price).abs();
+        if let Some(&notional) = self.max_notional.get(&leg.symbol_id)
+            && exposure > 0.0
+        {
+            let cap = notional / exposure;
+            if cap < bounds.max {
+                bounds.max = cap;
+                bounds.max_source = Binding::MaxNotional;
+            }
+            if -cap > bounds.min {
+                bounds.min = -cap;
+                bounds.min_source = Binding::MaxNotional;
+            }
+        }
+
+        if bounds.min > bounds.max {
+            return Err(crate::Error::Calculation(format!(
+                "No hedge ratio of symbol {} satisfies both the corridor and the notional cap",
+                leg.symbol_id
+            )));
+        }
+
+        Ok(bounds)
+    }
+
+    /// Projected Gauss-Seidel on the ratio box (exact for this convex QP)
+    fn descend(covariance: &HedgeCovariance, bounds: &[RatioBounds], ratios: &mut DVector<f64>) {
+        const MAX_SWEEPS: usize = 1000;
+
+        let sigma = &covariance.futures;
+        for _ in 0..MAX_SWEEPS {
+            let mut max_step: f64 = 0.0;
+            for i in 0..ratios.len() {
+                let others = sigma.row(i).dot(&ratios.transpose()) - sigma[(i, i)] * ratios[i];
+                let ratio = bounds[i].clamp((covariance.cross[i] - others) / sigma[(i, i)]);
+                max_step = max_step.max((ratio - ratios[i]).abs());
+                ratios[i] = ratio;
+            }
+            if max_step < 1e-12 {
+                break;
+            }
+        }
+    }
+
+    /// Move ratios to whole lots, then improve one lot at a time
+    fn round_to_lots(
+        covariance: &HedgeCovariance,
+        bounds: &[RatioBounds],
+        steps: &[f64],
+        legs: &[LegTerms],
+        ratios: &mut DVector<f64>,
+    ) -> crate::Result<()> {
+        const MAX_PASSES: usize = 100;
+
+        let mut ranges = Vec::with_capacity(ratios.len());
+        let mut lots = Vec::with_capacity(ratios.len());
+        for i in 0..ratios.len() {
+            // Saturating casts keep unbounded sides at i64::MIN / i64::MAX
+            let min = (bounds[i].min / steps[i] - 1e-9).ceil() as i64;
+            let max = (bounds[i].max / steps[i] + 1e-9).floor() as i64;
+            if min > max {
+                return Err(crate::Error::Calculation(format!(
+                    "No whole-lot quantity of symbol {} within its ratio bounds",
+                    legs[i].symbol_id
+                )));
+            }
+            ranges.push((min, max));
+            lots.push(((ratios[i] / steps[i]).round() as i64).clamp(min, max));
+            ratios[i] = lots[i] as f64 * steps[i];
+        }
+
+        let mut variance = covariance.hedged_variance(ratios);
+        for _ in 0..MAX_PASSES {
+            let mut improved = false;
+            for i in 0..ratios.len() {
+                for next in [lots[i].saturating_sub(1), lots[i].saturating_add(1)] {
+                    if next < ranges[i].0 || next > ranges[i].1 || next == lots[i] {
+                        continue;
+                    }
+
+                    let current = ratios[i];
+                    ratios[i] = next as f64 * steps[i];
+                    let candidate = covariance.hedged_variance(ratios);
+                    if candidate < variance - 1e-15 * variance.max(1e-300) {
+                        variance = candidate;
+                        lots[i] = next;
+                        improved = true;
+                    } else {
+                        ratios[i] = current;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Market terms of one hedge leg
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LegTerms {
+    /// Symbol ID of the hedge instrument
+    pub symbol_id: u8,
+
+    /// Price used for the notional (currency per unit)
+    pub price: f64,
+
+    /// Quantity per lot (units per contract)
+    pub contract_size: f64,
+}
+
+/// Ratio bounds of one leg and the constraint behind each side
+#[derive(Debug, Clone, Copy)]
+struct RatioBounds {
+    min: f64,
+    max: f64,
+    min_source: Binding,
+    max_source: Binding,
+}
+
+impl RatioBounds {
+    fn clamp(&self, ratio: f64) -> f64 {
+        ratio.clamp(self.min, self.max)
+    }
+}
+
+/// Constraint holding a leg away from its unconstrained optimum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    /// No constraint binds
+    Free,
+    /// At the bottom of the ratio corridor
+    MinRatio,
+    /// At the top of the ratio corridor
+    MaxRatio,
+    /// At the instrument's notional cap
+    MaxNotional,
+    /// Moved to a whole number of lots
+    LotSize,
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Binding::Free => "free",
+            Binding::MinRatio => "min ratio",
+            Binding::MaxRatio => "max ratio",
+            Binding::MaxNotional => "max notional",
+            Binding::LotSize => "lot size",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Constrained allocation of one hedge leg
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegAllocation {
+    /// Symbol ID of the hedge instrument
+    pub symbol_id: u8,
+
+    /// Constrained hedge ratio
+    pub ratio: f64,
+
+    /// Minimum-variance ratio without constraints
+    pub unconstrained_ratio: f64,
+
+    /// Hedge quantity (ratio × |position|)
+    pub quantity: f64,
+
+    /// Hedge quantity in contract lots
+    pub lots: f64,
+
+    /// Absolute hedge notional (currency)
+    pub notional: f64,
+
+    /// Binding constraint
+    pub binding: Binding,
+}
+
+/// Feasible minimum-variance allocation across hedge legs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstrainedAllocation {
+    /// One allocation per leg, in leg order
+    pub legs: Vec<LegAllocation>,
+
+    /// Variance of hedged changes with the constrained ratios
+    pub hedged_variance: f64,
+
+    /// Variance of hedged changes with the unconstrained ratios
+    pub unconstrained_variance: f64,
+}
+
+impl ConstrainedAllocation {
+    /// Constrained ratio of every leg
+    pub fn ratios(&self) -> Vec<f64> {
+        self.legs.iter().map(|leg| leg.ratio).collect()
+    }
+
+    /// Check whether any constraint binds
+    pub fn is_constrained(&self) -> bool {
+        self.legs.iter().any(|leg| leg.binding != Binding::Free)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(symbol_id: u8) -> LegTerms {
+        LegTerms {
+            symbol_id,
+            price: 50.0,
+            contract_size: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_unconstrained_matches_mvhr() {
+        let covariance = HedgeCovariance::single(4.0, 5.0, 0.9);
+        let allocation = HedgeConstraints::default()
+            .allocate(&covariance, -10_000.0, &[leg(2)])
+            .unwrap();
+
+        let leg = &allocation.legs[0];
+        assert!((leg.ratio - 0.9).abs() < 1e-12);
+        assert_eq!(leg.binding, Binding::Free);
+        assert!((leg.quantity - 9_000.0).abs() < 1e-6);
+        assert!((leg.notional - 450_000.0).abs() < 1e-3);
+        assert!(!allocation.is_constrained());
+        // 5 - 2·0.9·3.6 + 0.81·4
+        assert!((allocation.hedged_variance - 1.76).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_corridor_binds() {
+        let constraints = HedgeConstraints::corridor(0.6, 1.1);
+
+        let high = HedgeCovariance::single(1.0, 2.0, 1.3);
+        let allocation = constraints.allocate(&high, 1_000.0, &[leg(2)]).unwrap();
+        assert_eq!(allocation.legs[0].ratio, 1.1);
+        assert_eq!(allocation.legs[0].binding, Binding::MaxRatio);
+        assert!(allocation.hedged_variance > allocation.unconstrained_variance);
+
+        let low = HedgeCovariance::single(1.0, 2.0, 0.2);
+        let allocation = constraints.allocate(&low, 1_000.0, &[leg(2)]).unwrap();
+        assert_eq!(allocation.legs[0].ratio, 0.6);
+        assert_eq!(allocation.legs[0].binding, Binding::MinRatio);
+    }
+
+    #[test]
+    fn test_notional_cap_and_infeasible_corridor() {
+        // 10,000 MWh at 50 €/MWh: a 300k cap allows a ratio of 0.6
+        let constraints = HedgeConstraints::default().with_max_notional(2, 300_000.0);
+        let covariance = HedgeCovariance::single(1.0, 1.0, 0.9);
+        let allocation = constraints
+            .allocate(&covariance, -10_000.0, &[leg(2)])
+            .unwrap();
+        assert!((allocation.legs[0].ratio - 0.6).abs() < 1e-12);
+        assert_eq!(allocation.legs[0].binding, Binding::MaxNotional);
+        assert!((allocation.legs[0].notional - 300_000.0).abs() < 1e-6);
+
+        let corridor = HedgeConstraints {
+            min_ratio: Some(0.8),
+            ..constraints
+        };
+        assert!(matches!(
+            corridor.allocate(&covariance, -10_000.0, &[leg(2)]),
+            Err(crate::Error::Calculation(_))
+        ));
+    }
+
+    #[test]
+    fn test_whole_lots() {
+        let constraints = HedgeConstraints::corridor(0.0, 1.1).with_whole_lots();
+        let legs = [LegTerms {
+            contract_size: 744.0,
+            ..leg(2)
+        }];
+
+        // 0.93 × 10,000 = 9,300 MWh = 12.5 lots; 1.1 caps at 14.78 lots
+        let covariance = HedgeCovariance::single(1.0, 1.0, 0.93);
+        let allocation = constraints.allocate(&covariance, 10_000.0, &legs).unwrap();
+        let leg = &allocation.legs[0];
+        assert_eq!(leg.lots.round(), leg.lots);
+        assert!((leg.lots - 12.5).abs() <= 0.5 + 1e-9);
+        assert_eq!(leg.binding, Binding::LotSize);
+
+        // The top of the corridor rounds down, not beyond the bound
+        let covariance = HedgeCovariance::single(1.0, 1.0, 2.0);
+        let allocation = constraints.allocate(&covariance, 10_000.0, &legs).unwrap();
+        assert!((allocation.legs[0].lots - 14.0).abs() < 1e-9);
+        assert_eq!(allocation.legs[0].binding, Binding::MaxRatio);
+    }
+
+    #[test]
+    fn test_correlated_legs_rebalance() {
+        // Two correlated legs; capping the first shifts weight onto the second
+        let covariance = HedgeCovariance::new(
+            DMatrix::from_row_slice(2, 2, &[1.0, 0.8, 0.8, 1.0]),
+            DVector::from_column_slice(&[0.9, 0.8]),
+            1.0,
+        );
+        let free = HedgeConstraints::default()
+            .allocate(&covariance, 1_000.0, &[leg(2), leg(3)])
+            .unwrap();
+
+        let capped = HedgeConstraints::default()
+            .with_max_notional(2, 0.2 * 1_000.0 * 50.0)
+            .allocate(&covariance, 1_000.0, &[leg(2), leg(3)])
+            .unwrap();
+        assert!((capped.legs[0].ratio - 0.2).abs() < 1e-9);
+        assert_eq!(capped.legs[0].binding, Binding::MaxNotional);
+        assert_eq!(capped.legs[1].binding, Binding::Free);
+        // Optimum of the second leg given the first: 0.8 - 0.8 · 0.2
+        assert!((capped.legs[1].ratio - 0.64).abs() < 1e-9);
+        assert!(capped.legs[1].ratio > free.legs[1].ratio);
+        assert!(capped.hedged_variance >= free.hedged_variance);
+    }
+
+    #[test]
+    fn test_constraint_validation() {
+        assert!(HedgeConstraints::default().is_unconstrained());
+        assert!(HedgeConstraints::corridor(0.6, 1.1).validate().is_ok());
+        assert!(HedgeConstraints::corridor(1.1, 0.6).validate().is_err());
+        assert!(
+            HedgeConstraints::default()
+                .with_max_notional(2, -1.0)
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,422 @@
+//! Minimum-variance cross hedge across several futures
+
+use crate::hedging::mvhr::MAX_RATIO;
+use crate::hedging::{HedgeCovariance, PriceChangeMode, SampledWindow, SamplingConfig};
+use crate::utils::{SharedClock, SystemClock};
+use nalgebra::{DMatrix, DVector};
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Spot price followed by up to `MAX_LEGS` futures prices
+type PriceVector = [f64; CrossHedgeStrategy::MAX_LEGS + 1];
+
+/// Minimum-variance hedge of one exposure with N futures
+///
+/// Regresses spot changes on the changes of all hedge legs at once:
+///
+/// h* = Σ_FF⁻¹ σ_FS
+///
+/// where Σ_FF is the covariance matrix of the futures changes and σ_FS
+/// their covariances with the spot change. Ratios are solved on the cold
+/// path and cached per leg for the hot path.
+pub struct CrossHedgeStrategy {
+    /// Historical (spot, futures...) price vectors
+    observations: RwLock<SampledWindow<PriceVector>>,
+
+    /// Number of hedge legs
+    legs: usize,
+
+    /// Cached ratio per leg (f64 bits)
+    ratios: [AtomicU64; Self::MAX_LEGS],
+
+    /// Result of the last successful calculation
+    statistics: RwLock<Option<CrossHedgeStatistics>>,
+
+    /// Last calculation timestamp (nanoseconds)
+    last_calc_ns: AtomicU64,
+
+    /// Window size (observations, or hours once sampled by time)
+    window_size: usize,
+
+    /// Recalculation interval (nanoseconds)
+    recalc_interval_ns: u64,
+
+    /// Price change measure
+    change_mode: PriceChangeMode,
+
+    /// Time source for the recalculation schedule
+    clock: SharedClock,
+}
+
+impl CrossHedgeStrategy {
+    /// Maximum number of hedge legs
+    pub const MAX_LEGS: usize = 8;
+
+    /// Smallest squared Cholesky pivot, relative to the largest variance
+    const CONDITION_LIMIT: f64 = 1e-10;
+
+    /// Create a cross hedge over `legs` futures using percentage returns
+    ///
+    /// Every observation is kept until `window_size` newer ones arrive;
+    /// use `with_sampling` for a window of `window_size` wall-clock hours.
+    /// Ratios are 0 (no hedge) until the first calculation.
+    pub fn new(legs: usize, window_size: usize) -> crate::Result<Self> {
+        if legs == 0 || legs > Self::MAX_LEGS {
+            return Err(crate::Error::Config(format!(
+                "Cross hedge needs 1 to {} legs, got {}",
+                Self::MAX_LEGS,
+                legs
+            )));
+        }
+
+        Ok(Self {
+            observations: RwLock::new(SampledWindow::with_capacity(window_size)),
+            legs,
+            ratios: Default::default(),
+            statistics: RwLock::new(None),
+            last_calc_ns: AtomicU64::new(0),
+            window_size,
+            recalc_interval_ns: 24 * 3600 * 1_000_000_000,
+            change_mode: PriceChangeMode::Relative,
+            clock: SystemClock::shared(),
+        })
+    }
+
+    /// Set the price change measure
+    pub fn with_change_mode(mut self, change_mode: PriceChangeMode) -> Self {
+        self.change_mode = change_mode;
+        self
+    }
+
+    /// Sample observations into time buckets over `window_size` hours
+    ///
+    /// Replaces any observations recorded so far.
+    pub fn with_sampling(self, sampling: SamplingConfig) -> Self {
+        let window_ns = self.window_size as u64 * 3600 * 1_000_000_000;
+        *self.observations.write() = SampledWindow::with_duration(window_ns, sampling);
+        self
+    }
+
+    /// Set the recalculation interval (default: 24 hours)
+    pub fn with_recalc_interval(mut self, interval: Duration) -> Self {
+        self.recalc_interval_ns = interval.as_nanos() as u64;
+        self
+    }
+
+    /// Use a custom time source (e.g. a simulated clock for replay)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Number of hedge legs
+    pub fn legs(&self) -> usize {
+        self.legs
+    }
+
+    /// Add an observation at the current clock time
+    pub fn add_observation(&self, spot_price: f64, futures_prices: &[f64]) {
+        self.add_observation_at(self.clock.now_ns(), spot_price, futures_prices);
+    }
+
+    /// Add an observation made at `timestamp_ns`
+    ///
+    /// `futures_prices` holds one price per leg, in leg order; other
+    /// lengths are ignored.
+    pub fn add_observation_at(&self, timestamp_ns: u64, spot_price: f64, futures_prices: &[f64]) {
+        if futures_prices.len() != self.legs {
+            return;
+        }
+
+        let mut prices = [0.0; Self::MAX_LEGS + 1];
+        prices[0] = spot_price;
+        prices[1..=self.legs].copy_from_slice(futures_prices);
+        self.observations.write().record(timestamp_ns, prices);
+    }
+
+    /// Check whether the window holds enough changes for a regression
+    pub fn has_sufficient_data(&self) -> bool {
+        self.observations.read().consecutive().count() > self.legs + 1
+    }
+
+    /// Solve the minimum-variance ratios over the window
+    ///
+    /// Fails with `Error::Calculation` when there are too few changes, the
+    /// futures legs are collinear or constant, or a ratio exceeds the
+    /// MVHR sanity limit (±5). The cached ratios are kept on failure.
+    ///
+    /// # Performance
+    /// O(n·N²) over the window plus an N×N Cholesky solve: cold path.
+    pub fn calculate_optimal_ratios(&self) -> crate::Result<CrossHedgeStatistics> {
+        let n = self.legs;
+        let (changes, observations) = {
+            let window = self.observations.read();
+            let changes: Vec<PriceVector> = window
+                .consecutive()
+                .filter_map(|(prev, curr)| self.change(&prev, &curr))
+                .collect();
+            (changes, window.len())
+        };
+
+        if changes.len() <= n + 1 {
+            return Err(crate::Error::Calculation(format!(
+                "Cross hedge needs more than {} price changes, got {}",
+                n + 1,
+                changes.len()
+            )));
+        }
+
+        // Sample covariance matrix of (spot, futures...) changes
+        let count = changes.len() as f64;
+        let mut mean = [0.0; Self::MAX_LEGS + 1];
+        for change in &changes {
+            for (m, c) in mean.iter_mut().zip(change).take(n + 1) {
+                *m += c / count;
+            }
+        }
+        let mut cov = DMatrix::<f64>::zeros(n + 1, n + 1);
+        for change in &changes {
+            for i in 0..=n {
+                for j in 0..=i {
+                    cov[(i, j)] += (change[i] - mean[i]) * (change[j] - mean[j]);
+                }
+            }
+        }
+        for i in 0..=n {
+            for j in 0..i {
+                cov[(j, i)] = cov[(i, j)];
+            }
+        }
+        cov /= count - 1.0;
+
+        let spot_variance = cov[(0, 0)];
+        let futures_cov = cov.view((1, 1), (n, n)).into_owned();
+        let spot_cov: DVector<f64> = cov.view((1, 0), (n, 1)).column(0).into_owned();
+
+        // Reject near-singular systems: a pivot that vanishes relative to
+        // the largest variance means a leg is (almost) a mix of the others
+        let max_variance = futures_cov.diagonal().max();
+        let ratios = futures_cov
+            .clone()
+            .cholesky()
+            .filter(|cholesky| {
+                let min_pivot = cholesky.l_dirty().diagonal().min();
+                min_pivot * min_pivot > Self::CONDITION_LIMIT * max_variance
+            })
+            .map(|cholesky| cholesky.solve(&spot_cov))
+            .filter(|ratios| ratios.iter().all(|r| r.is_finite()))
+            .ok_or_else(|| {
+                crate::Error::Calculation("Cross hedge legs are collinear or constant".to_string())
+            })?;
+
+        if let Some(ratio) = ratios.iter().find(|r| r.abs() > MAX_RATIO) {
+            return Err(crate::Error::Calculation(format!(
+                "Cross hedge ratio {:.4} exceeds ±{}",
+                ratio, MAX_RATIO
+            )));
+        }
+
+        // Variance left after hedging: Var(ΔS) - σ_FS' h
+        let residual_variance = (spot_variance - spot_cov.dot(&ratios)).max(0.0);
+        let r_squared = if spot_variance > 0.0 {
+            1.0 - residual_variance / spot_variance
+        } else {
+            0.0
+        };
+
+        for (cached, &ratio) in self.ratios.iter().zip(ratios.iter()) {
+            cached.store(ratio.to_bits(), Ordering::Release);
+        }
+        self.last_calc_ns
+            .store(self.clock.now_ns(), Ordering::Release);
+
+        let statistics = CrossHedgeStatistics {
+            ratios: ratios.iter().copied().collect(),
+            residual_variance,
+            r_squared,
+            spot_volatility: spot_variance.sqrt(),
+            observations,
+            covariance: HedgeCovariance::new(futures_cov, spot_cov, spot_variance),
+        };
+        *self.statistics.write() = Some(statistics.clone());
+        Ok(statistics)
+    }
+
+    /// Changes of a price vector, if all are defined
+    fn change(&self, prev: &PriceVector, curr: &PriceVector) -> Option<PriceVector> {
+        let mut change = [0.0; Self::MAX_LEGS + 1];
+        for i in 0..=self.legs {
+            change[i] = self.change_mode.change(prev[i], curr[i])?;
+        }
+        Some(change)
+    }
+
+    /// Get the cached ratio of one leg (fast)
+    #[inline(always)]
+    pub fn get_hedge_ratio(&self, leg: usize) -> f64 {
+        self.ratios
+            .get(leg)
+            .map_or(0.0, |r| f64::from_bits(r.load(Ordering::Acquire)))
+    }
+
+    /// Get the cached ratios of all legs
+    pub fn get_hedge_ratios(&self) -> Vec<f64> {
+        (0..self.legs)
+            .map(|leg| self.get_hedge_ratio(leg))
+            .collect()
+    }
+
+    /// Check if recalculation is needed
+    ///
+    /// True until the first successful calculation, then once per interval.
+    pub fn needs_recalculation(&self) -> bool {
+        let last_calc = self.last_calc_ns.load(Ordering::Relaxed);
+        last_calc == 0 || self.clock.now_ns().saturating_sub(last_calc) > self.recalc_interval_ns
+    }
+
+    /// Statistics of the last successful calculation
+    pub fn get_statistics(&self) -> Option<CrossHedgeStatistics> {
+        self.statistics.read().clone()
+    }
+}
+
+/// Cross hedge statistics for monitoring
+#[derive(Debug, Clone, Default)]
+pub struct CrossHedgeStatistics {
+    /// Ratio per leg (MWh of futures per MWh of exposure)
+    pub ratios: Vec<f64>,
+
+    /// Variance of spot changes left after hedging
+    pub residual_variance: f64,
+
+    /// Share of spot change variance removed by the hedge
+    pub r_squared: f64,
+
+    pub spot_volatility: f64,
+    pub observations: usize,
+
+    /// Estimated covariance, input to `HedgeConstraints::allocate`
+    pub covariance: HedgeCovariance,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two futures with independent moves; spot = 0.6 DE + 0.3 FR + noise
+    fn fill(strategy: &CrossHedgeStrategy, count: u64) {
+        let (mut de, mut fr, mut spot) = (50.0, 48.0, 45.0);
+        for i in 0..count {
+            let de_move = ((i * 37) % 11) as f64 * 0.1 - 0.5;
+            let fr_move = ((i * 53) % 13) as f64 * 0.1 - 0.6;
+            let noise = ((i * 17) % 5) as f64 * 0.01 - 0.02;
+            de += de_move;
+            fr += fr_move;
+            spot += 0.6 * de_move + 0.3 * fr_move + noise;
+            strategy.add_observation(spot, &[de, fr]);
+        }
+    }
+
+    #[test]
+    fn test_cross_hedge_recovers_ratios() {
+        let strategy = CrossHedgeStrategy::new(2, 500)
+            .unwrap()
+            .with_change_mode(PriceChangeMode::Absolute);
+        assert_eq!(strategy.get_hedge_ratios(), [0.0, 0.0]);
+
+        fill(&strategy, 300);
+        assert!(strategy.has_sufficient_data());
+
+        let stats = strategy.calculate_optimal_ratios().unwrap();
+        assert!((stats.ratios[0] - 0.6).abs() < 0.01);
+        assert!((stats.ratios[1] - 0.3).abs() < 0.01);
+        assert!(stats.r_squared > 0.99);
+        assert!(stats.residual_variance < 1e-3);
+        assert_eq!(stats.observations, 300);
+
+        assert!((strategy.get_hedge_ratio(0) - 0.6).abs() < 0.01);
+        assert_eq!(strategy.get_hedge_ratio(5), 0.0);
+        assert!(!strategy.needs_recalculation());
+    }
+
+    #[test]
+    fn test_single_leg_matches_mvhr() {
+        use crate::hedging::MVHRStrategy;
+
+        let strategy = CrossHedgeStrategy::new(1, 100).unwrap();
+        let mvhr = MVHRStrategy::new(100, 1);
+        for i in 0..80u64 {
+            let futures = 50.0 + ((i * 37) % 11) as f64 * 0.3;
+            let spot = 45.0 + ((i * 37) % 11) as f64 * 0.2 + ((i * 13) % 7) as f64 * 0.05;
+            strategy.add_observation(spot, &[futures]);
+            mvhr.add_observation(spot, futures);
+        }
+
+        let stats = strategy.calculate_optimal_ratios().unwrap();
+        let ratio = mvhr.calculate_optimal_ratio().unwrap();
+        assert!((stats.ratios[0] - ratio).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_collinear_legs_fail() {
+        let strategy = CrossHedgeStrategy::new(2, 100).unwrap();
+        for i in 0..50u64 {
+            let futures = 50.0 + ((i * 37) % 11) as f64 * 0.3;
+            strategy.add_observation(45.0 + (i % 3) as f64, &[futures, futures]);
+        }
+
+        assert!(matches!(
+            strategy.calculate_optimal_ratios(),
+            Err(crate::Error::Calculation(_))
+        ));
+        assert!(strategy.get_statistics().is_none());
+    }
+
+    #[test]
+    fn test_ratio_precision_and_limit() {
+        let strategy = CrossHedgeStrategy::new(1, 100)
+            .unwrap()
+            .with_change_mode(PriceChangeMode::Absolute);
+        let (mut futures, mut spot) = (50.0, 45.0);
+        for i in 0..50u64 {
+            let step = ((i * 37) % 11) as f64 * 0.1 - 0.5;
+            futures += step;
+            spot += 0.123456789 * step;
+            strategy.add_observation(spot, &[futures]);
+        }
+        let stats = strategy.calculate_optimal_ratios().unwrap();
+        // Cached at full precision, not rounded to 4 decimals
+        assert_eq!(strategy.get_hedge_ratio(0), stats.ratios[0]);
+        assert!((strategy.get_hedge_ratio(0) - 0.123456789).abs() < 1e-9);
+
+        let extreme = CrossHedgeStrategy::new(1, 100)
+            .unwrap()
+            .with_change_mode(PriceChangeMode::Absolute);
+        let (mut futures, mut spot) = (50.0, 45.0);
+        for i in 0..50u64 {
+            let step = ((i * 37) % 11) as f64 * 0.1 - 0.5;
+            futures += step;
+            spot += 8.0 * step;
+            extreme.add_observation(spot, &[futures]);
+        }
+        assert!(matches!(
+            extreme.calculate_optimal_ratios(),
+            Err(crate::Error::Calculation(_))
+        ));
+        assert_eq!(extreme.get_hedge_ratio(0), 0.0);
+        assert!(extreme.get_statistics().is_none());
+    }
+
+    #[test]
+    fn test_leg_limits() {
+        assert!(CrossHedgeStrategy::new(0, 100).is_err());
+        assert!(CrossHedgeStrategy::new(CrossHedgeStrategy::MAX_LEGS + 1, 100).is_err());
+
+        let strategy = CrossHedgeStrategy::new(2, 100).unwrap();
+        assert!(matches!(
+            strategy.calculate_optimal_ratios(),
+            Err(crate::Error::Calculation(_))
+        ));
+    }
+}
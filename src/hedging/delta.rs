@@ -1,6 +1,6 @@
 use crate::hedging::{HedgeRecommendation, Urgency};
 use crate::market_data::{OrderBook, Side};
-use crate::utils::get_timestamp_ns;
+use crate::utils::{SharedClock, SystemClock};
 use std::sync::atomic::{AtomicI64, Ordering};
 
 /// Simple delta hedging strategy
@@ -32,6 +32,9 @@ pub struct DeltaHedge {
 
     /// Rehedge threshold (basis points)
     threshold_bps: i64,
+
+    /// Time source for recommendation timestamps
+    clock: SharedClock,
 }
 
 impl DeltaHedge {
@@ -42,9 +45,16 @@ impl DeltaHedge {
             hedge_ratio: AtomicI64::new((hedge_ratio * 10000.0) as i64),
             hedge_position: AtomicI64::new(0),
             threshold_bps,
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Use a custom time source (e.g. a simulated clock for replay)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Update a position
     pub fn update_position(&self, new_position: f64) {
         self.position
@@ -141,7 +151,7 @@ impl DeltaHedge {
                 self.get_hedge_position(),
                 delta
             ),
-            get_timestamp_ns(),
+            self.clock.now_ns(),
         ))
     }
 
@@ -0,0 +1,498 @@
+//! Uncertainty and specification diagnostics of the hedge ratio
+
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal, StudentsT};
+use std::fmt;
+
+/// OLS fit of spot changes on futures changes (with intercept)
+#[derive(Debug, Clone, Copy)]
+struct Ols {
+    slope: f64,
+    standard_error: f64,
+    r_squared: f64,
+}
+
+impl Ols {
+    /// Fit ΔS = α + β ΔF + ε; None with fewer than 3 changes or flat futures
+    fn fit(changes: &[(f64, f64)]) -> Option<(Self, Vec<f64>)> {
+        let n = changes.len();
+        if n < 3 {
+            return None;
+        }
+
+        let count = n as f64;
+        let (mean_s, mean_f) = changes.iter().fold((0.0, 0.0), |(s, f), &(ds, df)| {
+            (s + ds / count, f + df / count)
+        });
+
+        let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+        for &(ds, df) in changes {
+            sxx += (df - mean_f) * (df - mean_f);
+            sxy += (df - mean_f) * (ds - mean_s);
+            syy += (ds - mean_s) * (ds - mean_s);
+        }
+        if sxx < 1e-300 {
+            return None;
+        }
+
+        let slope = sxy / sxx;
+        let intercept = mean_s - slope * mean_f;
+        let residuals: Vec<f64> = changes
+            .iter()
+            .map(|&(ds, df)| ds - intercept - slope * df)
+            .collect();
+        let sse: f64 = residuals.iter().map(|e| e * e).sum();
+
+        let ols = Self {
+            slope,
+            standard_error: (sse / (count - 2.0) / sxx).sqrt(),
+            r_squared: if syy > 0.0 { 1.0 - sse / syy } else { 0.0 },
+        };
+        Some((ols, residuals))
+    }
+}
+
+/// Homogeneity of the ratio across consecutive sub-windows
+///
+/// Wald test of equal ratios: Q = Σ w_i (β_i - β̄)², w_i = 1 / SE_i²,
+/// χ² with k - 1 degrees of freedom under stability.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilityTest {
+    /// Ratio per sub-window, oldest first
+    pub ratios: Vec<f64>,
+
+    /// Standard error per sub-window
+    pub standard_errors: Vec<f64>,
+
+    /// Wald statistic Q
+    pub statistic: f64,
+
+    /// Probability of a Q at least this large with a stable ratio
+    pub p_value: f64,
+}
+
+impl StabilityTest {
+    /// Smallest number of changes per sub-window
+    pub const MIN_CHANGES: usize = 10;
+
+    /// Test `windows` consecutive sub-windows of `changes`
+    ///
+    /// None with fewer than 2 windows, too few changes per window, or a
+    /// window with flat futures.
+    pub fn new(changes: &[(f64, f64)], windows: usize) -> Option<Self> {
+        if windows < 2 || changes.len() / windows < Self::MIN_CHANGES {
+            return None;
+        }
+
+        let size = changes.len() / windows;
+        let mut ratios = Vec::with_capacity(windows);
+        let mut standard_errors = Vec::with_capacity(windows);
+        for i in 0..windows {
+            // The last window takes the remainder
+            let end = if i + 1 == windows {
+                changes.len()
+            } else {
+                (i + 1) * size
+            };
+            let (ols, _) = Ols::fit(&changes[i * size..end])?;
+            ratios.push(ols.slope);
+            standard_errors.push(ols.standard_error.max(f64::MIN_POSITIVE));
+        }
+
+        let weights: Vec<f64> = standard_errors.iter().map(|se| 1.0 / (se * se)).collect();
+        let total: f64 = weights.iter().sum();
+        let pooled = ratios.iter().zip(&weights).map(|(r, w)| r * w).sum::<f64>() / total;
+        let statistic: f64 = ratios
+            .iter()
+            .zip(&weights)
+            .map(|(r, w)| w * (r - pooled) * (r - pooled))
+            .sum();
+
+        let p_value = ChiSquared::new((windows - 1) as f64)
+            .map(|chi| chi.sf(statistic))
+            .unwrap_or(0.0);
+
+        Some(Self {
+            ratios,
+            standard_errors,
+            statistic,
+            p_value,
+        })
+    }
+
+    /// Check whether stability is not rejected at `significance`
+    pub fn is_stable(&self, significance: f64) -> bool {
+        self.p_value >= significance
+    }
+}
+
+/// Uncertainty and specification diagnostics of a hedge ratio
+///
+/// From the OLS regression of spot changes on futures changes over the
+/// window. An estimator with its own uncertainty (Kalman) replaces the
+/// ratio, standard error and interval with `with_estimate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatioDiagnostics {
+    /// OLS ratio over the window
+    pub ratio: f64,
+
+    /// Standard error of the ratio
+    pub standard_error: f64,
+
+    /// Confidence interval of the ratio (Student t, n - 2 df)
+    pub confidence_interval: (f64, f64),
+
+    /// Confidence level of the interval (e.g. 0.95)
+    pub confidence_level: f64,
+
+    /// Hedge effectiveness: share of spot change variance removed
+    pub r_squared: f64,
+
+    /// Durbin-Watson statistic of the residuals (≈2 if uncorrelated)
+    pub durbin_watson: f64,
+
+    /// Lag-1 autocorrelation of the residuals
+    pub residual_autocorrelation: f64,
+
+    /// Sub-window stability test (None if too few changes)
+    pub stability: Option<StabilityTest>,
+
+    /// Changes in the regression
+    pub observations: usize,
+}
+
+impl RatioDiagnostics {
+    /// Diagnose the ratio of (spot change, futures change) pairs
+    ///
+    /// Autocorrelated residuals (Durbin-Watson far from 2) mean the
+    /// standard error understates the uncertainty.
+    ///
+    /// # Performance
+    /// O(n): cold path.
+    pub fn estimate(
+        changes: &[(f64, f64)],
+        confidence_level: f64,
+        stability_windows: usize,
+    ) -> Option<Self> {
+        let (ols, residuals) = Ols::fit(changes)?;
+
+        let df = (changes.len() - 2) as f64;
+        let t = StudentsT::new(0.0, 1.0, df)
+            .ok()?
+            .inverse_cdf(0.5 + confidence_level / 2.0);
+        let half_width = t * ols.standard_error;
+
+        let sse: f64 = residuals.iter().map(|e| e * e).sum();
+        let (durbin_watson, residual_autocorrelation) = if sse > 0.0 {
+            let (mut dw, mut lagged) = (0.0, 0.0);
+            for pair in residuals.windows(2) {
+                dw += (pair[1] - pair[0]) * (pair[1] - pair[0]);
+                lagged += pair[1] * pair[0];
+            }
+            (dw / sse, lagged / sse)
+        } else {
+            (2.0, 0.0)
+        };
+
+        Some(Self {
+            ratio: ols.slope,
+            standard_error: ols.standard_error,
+            confidence_interval: (ols.slope - half_width, ols.slope + half_width),
+            confidence_level,
+            r_squared: ols.r_squared,
+            durbin_watson,
+            residual_autocorrelation,
+            stability: StabilityTest::new(changes, stability_windows),
+            observations: changes.len(),
+        })
+    }
+
+    /// Use an estimator's ratio and variance instead of the OLS ones
+    ///
+    /// The interval becomes ratio ± z·√variance (normal quantile at the
+    /// same confidence level); R², residual and stability diagnostics stay
+    /// those of the window.
+    pub fn with_estimate(mut self, ratio: f64, variance: f64) -> Self {
+        let z = Normal::new(0.0, 1.0)
+            .map(|normal| normal.inverse_cdf(0.5 + self.confidence_level / 2.0))
+            .unwrap_or(f64::NAN);
+        let standard_error = variance.max(0.0).sqrt();
+
+        self.ratio = ratio;
+        self.standard_error = standard_error;
+        self.confidence_interval = (ratio - z * standard_error, ratio + z * standard_error);
+        self
+    }
+
+    /// Half width of the confidence interval
+    pub fn margin(&self) -> f64 {
+        (self.confidence_interval.1 - self.confidence_interval.0) / 2.0
+    }
+}
+
+/// Limits a new hedge ratio must pass before the engine applies it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RatioAcceptance {
+    /// Confidence level of the ratio interval
+    #[serde(default = "default_confidence_level")]
+    pub confidence_level: f64,
+
+    /// Largest accepted standard error (None = any)
+    #[serde(default)]
+    pub max_standard_error: Option<f64>,
+
+    /// Smallest accepted hedge effectiveness R² (None = any)
+    #[serde(default)]
+    pub min_r_squared: Option<f64>,
+
+    /// Reject ratios whose sub-windows disagree
+    /// Tested at 1 - `confidence_level`
+    #[serde(default)]
+    pub require_stability: bool,
+
+    /// Sub-windows of the stability test
+    #[serde(default = "default_stability_windows")]
+    pub stability_windows: usize,
+}
+
+fn default_confidence_level() -> f64 {
+    0.95
+}
+
+fn default_stability_windows() -> usize {
+    3
+}
+
+impl Default for RatioAcceptance {
+    fn default() -> Self {
+        Self {
+            confidence_level: default_confidence_level(),
+            max_standard_error: None,
+            min_r_squared: None,
+            require_stability: false,
+            stability_windows: default_stability_windows(),
+        }
+    }
+}
+
+impl RatioAcceptance {
+    /// Validate limits
+    pub fn validate(&self) -> crate::Result<()> {
+        let valid = self.confidence_level > 0.0 && self.confidence_level < 1.0;
+        if !valid {
+            return Err(crate::Error::Config(
+                "Ratio confidence level must be in (0, 1)".to_string(),
+            ));
+        }
+
+        if self.require_stability && self.stability_windows < 2 {
+            return Err(crate::Error::Config(
+                "Stability test needs at least 2 sub-windows".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Decide whether to replace `current` with `candidate`
+    ///
+    /// The candidate is rejected when the diagnostics are missing, too
+    /// noisy or unstable, and ignored when `current` lies within its
+    /// confidence margin.
+    pub fn evaluate(
+        &self,
+        current: f64,
+        candidate: f64,
+        diagnostics: Option<&RatioDiagnostics>,
+    ) -> RatioDecision {
+        let Some(diagnostics) = diagnostics else {
+            return RatioDecision::InsufficientData;
+        };
+
+        if self
+            .max_standard_error
+            .is_some_and(|max| diagnostics.standard_error > max)
+        {
+            RatioDecision::TooNoisy
+        } else if self
+            .min_r_squared
+            .is_some_and(|min| diagnostics.r_squared < min)
+        {
+            RatioDecision::LowEffectiveness
+        } else if self.require_stability
+            && !diagnostics
+                .stability
+                .as_ref()
+                .is_some_and(|test| test.is_stable(1.0 - self.confidence_level))
+        {
+            RatioDecision::Unstable
+        } else if (candidate - current).abs() <= diagnostics.margin() {
+            RatioDecision::Indistinguishable
+        } else {
+            RatioDecision::Accepted
+        }
+    }
+}
+
+/// Outcome of checking a new hedge ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatioDecision {
+    /// Applied
+    Accepted,
+    /// Within the confidence margin of the current ratio
+    Indistinguishable,
+    /// Standard error above the limit
+    TooNoisy,
+    /// Hedge effectiveness below the limit
+    LowEffectiveness,
+    /// Sub-window ratios disagree (or too few changes to test)
+    Unstable,
+    /// Too few changes for diagnostics
+    InsufficientData,
+}
+
+impl fmt::Display for RatioDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RatioDecision::Accepted => "accepted",
+            RatioDecision::Indistinguishable => "indistinguishable",
+            RatioDecision::TooNoisy => "too noisy",
+            RatioDecision::LowEffectiveness => "low effectiveness",
+            RatioDecision::Unstable => "unstable",
+            RatioDecision::InsufficientData => "insufficient data",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ΔS = β ΔF + noise, with a break to `beta_after` halfway
+    fn changes(n: u64, beta: f64, beta_after: f64, noise: f64) -> Vec<(f64, f64)> {
+        (0..n)
+            .map(|i| {
+                let df = ((i * 37) % 11) as f64 * 0.2 - 1.0;
+                let e = (((i * 13) % 7) as f64 - 3.0) * noise;
+                let b = if i < n / 2 { beta } else { beta_after };
+                (b * df + e, df)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ratio_confidence_interval() {
+        let diagnostics =
+            RatioDiagnostics::estimate(&changes(200, 0.8, 0.8, 0.05), 0.95, 4).unwrap();
+
+        let (low, high) = diagnostics.confidence_interval;
+        assert!(low < 0.8 && 0.8 < high);
+        assert!(diagnostics.standard_error > 0.0 && diagnostics.standard_error < 0.05);
+        assert!((diagnostics.margin() - 1.97 * diagnostics.standard_error).abs() < 1e-3);
+        assert!(diagnostics.r_squared > 0.9);
+        assert_eq!(diagnostics.observations, 200);
+        assert!(diagnostics.durbin_watson > 0.0 && diagnostics.durbin_watson < 4.0);
+        assert!(diagnostics.stability.as_ref().unwrap().is_stable(0.05));
+
+        // More noise, wider interval
+        let noisy = RatioDiagnostics::estimate(&changes(200, 0.8, 0.8, 0.5), 0.95, 4).unwrap();
+        assert!(noisy.margin() > diagnostics.margin());
+        assert!(noisy.r_squared < diagnostics.r_squared);
+    }
+
+    #[test]
+    fn test_estimator_interval() {
+        let diagnostics = RatioDiagnostics::estimate(&changes(200, 0.8, 0.8, 0.05), 0.95, 3)
+            .unwrap()
+            .with_estimate(0.75, 0.0004);
+
+        assert_eq!(diagnostics.ratio, 0.75);
+        assert!((diagnostics.standard_error - 0.02).abs() < 1e-12);
+        assert!((diagnostics.margin() - 1.96 * 0.02).abs() < 1e-3);
+        assert!(diagnostics.r_squared > 0.9);
+    }
+
+    #[test]
+    fn test_durbin_watson_detects_autocorrelation() {
+        // Residuals following a slow cycle are positively autocorrelated
+        let changes: Vec<(f64, f64)> = (0..200u64)
+            .map(|i| {
+                let df = ((i * 37) % 11) as f64 * 0.2 - 1.0;
+                (0.8 * df + (i as f64 * 0.1).sin() * 0.3, df)
+            })
+            .collect();
+        let diagnostics = RatioDiagnostics::estimate(&changes, 0.95, 3).unwrap();
+        assert!(diagnostics.durbin_watson < 0.5);
+        assert!(diagnostics.residual_autocorrelation > 0.7);
+    }
+
+    #[test]
+    fn test_stability_detects_break() {
+        let test = StabilityTest::new(&changes(200, 0.6, 1.2, 0.05), 2).unwrap();
+        assert!((test.ratios[0] - 0.6).abs() < 0.05);
+        assert!((test.ratios[1] - 1.2).abs() < 0.05);
+        assert!(test.p_value < 0.01);
+        assert!(!test.is_stable(0.05));
+
+        assert!(StabilityTest::new(&changes(15, 0.8, 0.8, 0.05), 2).is_none());
+    }
+
+    #[test]
+    fn test_acceptance() {
+        let diagnostics =
+            RatioDiagnostics::estimate(&changes(200, 0.8, 0.8, 0.05), 0.95, 3).unwrap();
+        let acceptance = RatioAcceptance::default();
+
+        let close = diagnostics.ratio + diagnostics.margin() / 2.0;
+        assert_eq!(
+            acceptance.evaluate(close, diagnostics.ratio, Some(&diagnostics)),
+            RatioDecision::Indistinguishable
+        );
+        assert_eq!(
+            acceptance.evaluate(1.0, diagnostics.ratio, Some(&diagnostics)),
+            RatioDecision::Accepted
+        );
+        assert_eq!(
+            acceptance.evaluate(1.0, 0.8, None),
+            RatioDecision::InsufficientData
+        );
+
+        let strict = RatioAcceptance {
+            max_standard_error: Some(diagnostics.standard_error / 2.0),
+            ..acceptance
+        };
+        assert_eq!(
+            strict.evaluate(1.0, 0.8, Some(&diagnostics)),
+            RatioDecision::TooNoisy
+        );
+
+        let effective = RatioAcceptance {
+            min_r_squared: Some(0.999),
+            ..acceptance
+        };
+        assert_eq!(
+            effective.evaluate(1.0, 0.8, Some(&diagnostics)),
+            RatioDecision::LowEffectiveness
+        );
+
+        let broken = RatioDiagnostics::estimate(&changes(200, 0.6, 1.2, 0.05), 0.95, 2).unwrap();
+        let stable = RatioAcceptance {
+            require_stability: true,
+            stability_windows: 2,
+            ..acceptance
+        };
+        assert_eq!(
+            stable.evaluate(0.5, broken.ratio, Some(&broken)),
+            RatioDecision::Unstable
+        );
+        assert!(
+            RatioAcceptance {
+                confidence_level: 1.0,
+                ..acceptance
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
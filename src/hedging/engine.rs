@@ -1,248 +1,1346 @@
+use crate::hedging::recalibration::{RecalibrationThread, Recalibrator};
 use crate::hedging::{
-    DeltaHedge, HedgeConfig, HedgeRecommendation, MVHRStrategy, MeanReversionHedge,
+    ConstrainedAllocation, CrossHedgeConfig, CrossHedgeStatistics, CrossHedgeStrategy, DeltaHedge,
+    ExposureConfig, HedgeConfig, HedgeConstraints, HedgeCovariance, HedgeRecommendation, LegTerms,
+    MVHRStrategy, MeanReversionHedge, MeanReversionStats, PriceSource, RatioAcceptance,
+    RatioDecision, RatioDiagnostics, RecalibrationFailure, StationarityReport, UnhealthyBookAction,
 };
-use crate::market_data::{MarketTick, OrderBook};
-use crate::utils::Metrics;
-use parking_lot::RwLock;
+use crate::market_data::{
+    BookHealthChecker, BookIssue, BookUpdate, Instrument, InstrumentRegistry, MarketTick,
+    OrderBook, ReceivedTick, ReferencePrice, Side, TickEvent, TickJournalWriter,
+};
+use crate::utils::{Metrics, SharedClock, SystemClock};
+use parking_lot::{Mutex, RwLock};
+use smallvec::SmallVec;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
-/// Main hedging engine
-///
-/// Coordinates multiple strategies and manages execution
-pub struct HedgeEngine {
-    /// Spot orderbook
-    spot_orderbook: Arc<OrderBook>,
+/// Per-exposure hedging state
+struct Exposure {
+    /// Exposure definition
+    config: ExposureConfig,
 
-    /// Futures orderbook
-    futures_orderbook: Arc<OrderBook>,
+    /// Exposure (spot) orderbook
+    orderbook: Arc<OrderBook>,
+
+    /// Hedge (futures) orderbook
+    hedge_orderbook: Arc<OrderBook>,
 
     /// Delta hedging strategy
-    delta_hedge: Arc<DeltaHedge>,
+    delta_hedge: DeltaHedge,
 
-    /// MVHR strategy (optional)
+    /// MVHR strategy (optional, shared with the recalibration thread)
     mvhr_strategy: Option<Arc<MVHRStrategy>>,
 
-    /// Mean reversion strategy (optional)
+    /// Mean reversion strategy (optional, shared with the recalibration thread)
     mean_reversion: Option<Arc<MeanReversionHedge>>,
 
+    /// Last MVHR ratio passing the acceptance checks (f64 bits)
+    accepted_ratio: AtomicU64,
+
+    /// Last choice and the MVHR ratio revision it was made for
+    ratio_choice: Mutex<Option<(u64, RatioChoice)>>,
+}
+
+/// MVHR ratio selected for an exposure
+#[derive(Clone, Copy)]
+struct RatioChoice {
+    /// Ratio to apply
+    ratio: f64,
+
+    /// Latest MVHR estimate
+    candidate: f64,
+
+    /// Outcome of the acceptance checks (None if not checked)
+    decision: Option<RatioDecision>,
+}
+
+/// Exposure hedged with several instruments
+struct CrossHedge {
+    /// Cross hedge definition
+    config: CrossHedgeConfig,
+
+    /// Exposure (spot) orderbook
+    orderbook: Arc<OrderBook>,
+
+    /// Orderbook per hedge leg
+    leg_orderbooks: Vec<Arc<OrderBook>>,
+
+    /// Delta hedge per leg (ratio = the leg's minimum-variance ratio)
+    legs: Vec<DeltaHedge>,
+
+    /// Multivariate ratio estimator (shared with the recalibration thread)
+    strategy: Arc<CrossHedgeStrategy>,
+}
+
+/// Tick routing for one symbol ID
+#[derive(Clone, Default)]
+struct SymbolRoute {
+    /// Orderbook for the symbol (None if not registered)
+    orderbook: Option<Arc<OrderBook>>,
+
+    /// Exposures priced off this symbol
+    exposures: SmallVec<[usize; 4]>,
+
+    /// Exposures hedged with this symbol
+    hedges: SmallVec<[usize; 4]>,
+
+    /// Cross hedges priced off or hedged with this symbol
+    cross_hedges: SmallVec<[usize; 2]>,
+}
+
+/// Main hedging engine
+///
+/// Coordinates multiple strategies and manages execution across any number
+/// of exposures, each hedged with its own instrument. The first exposure is
+/// the primary one served by the single-exposure API
+/// (`get_hedge_recommendation`, `get_position`, `spot_orderbook`, ...).
+pub struct HedgeEngine {
+    /// Instrument metadata
+    instruments: Arc<InstrumentRegistry>,
+
+    /// Orderbooks and exposure routing, indexed by symbol ID
+    routes: Vec<SymbolRoute>,
+
+    /// Managed exposures
+    exposures: Vec<Exposure>,
+
+    /// Exposures hedged across several instruments
+    cross_hedges: Vec<CrossHedge>,
+
+    /// Price series feeding MVHR and mean reversion
+    price_source: PriceSource,
+
+    /// Book fair value used with quote prices (None = quote price / mid)
+    reference_price: Option<ReferencePrice>,
+
+    /// Book health limits per instrument
+    health: BookHealthChecker,
+
+    /// Handling of unhealthy books in recommendations
+    unhealthy_book_action: UnhealthyBookAction,
+
+    /// Policy limits on hedge ratios, lots and notional
+    constraints: HedgeConstraints,
+
+    /// Checks a new MVHR ratio must pass (None = apply every estimate)
+    ratio_acceptance: Option<RatioAcceptance>,
+
+    /// Time source for recommendations, health checks and schedules
+    clock: SharedClock,
+
+    /// Journal recording every tick (optional)
+    journal: Option<Mutex<TickJournalWriter>>,
+
+    /// Cold-path recalibration tasks
+    recalibrator: Arc<Recalibrator>,
+
+    /// Background recalibration thread (None = not started)
+    recalibration_thread: Mutex<Option<RecalibrationThread>>,
+
     /// Performance metrics
     metrics: Arc<RwLock<Metrics>>,
 }
 
 impl HedgeEngine {
-    /// Create a new hedge engine
+    /// Create a new hedge engine with the default spot/futures instruments
     pub fn new(config: HedgeConfig) -> crate::Result<Self> {
+        Self::with_instruments(config, InstrumentRegistry::default())
+    }
+
+    /// Create a new hedge engine resolving symbols through a registry
+    ///
+    /// An orderbook is created for every registered instrument. Fails if an
+    /// exposure references a symbol that is not registered.
+    pub fn with_instruments(
+        config: HedgeConfig,
+        instruments: InstrumentRegistry,
+    ) -> crate::Result<Self> {
+        Self::with_clock(config, instruments, SystemClock::shared())
+    }
+
+    /// Create a new hedge engine reading time from `clock`
+    ///
+    /// With a `SimulatedClock`, time follows the tick timestamps seen by
+    /// `on_tick`, so replaying the same ticks yields identical
+    /// recommendations and recalculation schedules.
+    pub fn with_clock(
+        config: HedgeConfig,
+        instruments: InstrumentRegistry,
+        clock: SharedClock,
+    ) -> crate::Result<Self> {
         config.validate()?;
 
-        let delta_hedge = Arc::new(DeltaHedge::new(
-            config.initial_position,
-            config.default_hedge_ratio,
-            config.rehedge_threshold_bps,
-        ));
+        let mut routes: Vec<SymbolRoute> = vec![SymbolRoute::default(); 256];
+        for instrument in instruments.iter() {
+            routes[instrument.symbol_id as usize].orderbook =
+                Some(Arc::new(OrderBook::for_instrument(instrument)));
+        }
 
-        let mvhr_strategy: Option<Arc<MVHRStrategy>> = if config.enable_mvhr {
-            Some(Arc::new(MVHRStrategy::new(
-                config.statistics_window_hours,
-                24, // Recalculate every 24 hours
-            )))
-        } else {
-            None
+        let book = |symbol_id: u8, role: &str, name: &str| {
+            routes[symbol_id as usize].orderbook.clone().ok_or_else(|| {
+                crate::Error::Config(format!(
+                    "Exposure {}: {} symbol {} not in instrument registry",
+                    name, role, symbol_id
+                ))
+            })
         };
 
-        let mean_reversion = if config.enable_mean_reversion {
-            Some(Arc::new(MeanReversionHedge::new(
-                config.statistics_window_hours,
-                0.20, // Kappa for energy markets
-                2.0,  // Z-score threshold
-                0.70, // Hedge strength
-            )))
-        } else {
-            None
-        };
+        let metrics = Arc::new(RwLock::new(Metrics::new()));
+        let recalibrator = Arc::new(Recalibrator::new(
+            config.recalibration,
+            clock.clone(),
+            metrics.clone(),
+        ));
+        let mvhr_interval = Duration::from_secs(config.recalibration.mvhr_interval_secs);
+        let mr_interval = Duration::from_secs(config.recalibration.mean_reversion_interval_secs);
+
+        let mut exposures = Vec::new();
+        for exposure in config.resolved_exposures() {
+            let orderbook = book(exposure.symbol_id, "exposure", &exposure.name)?;
+            let hedge_orderbook = book(exposure.hedge_symbol_id, "hedge", &exposure.name)?;
+
+            let delta_hedge = DeltaHedge::new(
+                exposure.position,
+                exposure.hedge_ratio,
+                config.rehedge_threshold_bps,
+            )
+            .with_clock(clock.clone());
+
+            let mvhr_strategy = if config.enable_mvhr {
+                let mvhr = Arc::new(
+                    MVHRStrategy::with_change_mode(
+                        config.statistics_window_hours,
+                        24, // Overridden by the configured interval
+                        config.mvhr_change_mode,
+                    )
+                    .with_recalc_interval(mvhr_interval)
+                    .with_estimator(config.ratio_estimator)
+                    .with_sampling(config.sampling)
+                    .with_clock(clock.clone()),
+                );
+                recalibrator.add_mvhr(&exposure.name, mvhr.clone());
+                Some(mvhr)
+            } else {
+                None
+            };
+
+            let mean_reversion = if config.enable_mean_reversion {
+                let mr = Arc::new(
+                    MeanReversionHedge::new(
+                        config.statistics_window_hours,
+                        0.20, // Prior kappa for energy markets, until fitted
+                        2.0,  // Z-score threshold
+                        0.70, // Hedge strength
+                    )
+                    .with_recalc_interval(mr_interval)
+                    .with_sampling(config.sampling)
+                    .with_clock(clock.clone()),
+                );
+                recalibrator.add_mean_reversion(&exposure.name, mr.clone());
+                Some(mr)
+            } else {
+                None
+            };
+
+            exposures.push(Exposure {
+                accepted_ratio: AtomicU64::new(exposure.hedge_ratio.to_bits()),
+                ratio_choice: Mutex::new(None),
+                config: exposure,
+                orderbook,
+                hedge_orderbook,
+                delta_hedge,
+                mvhr_strategy,
+                mean_reversion,
+            });
+        }
+
+        let mut cross_hedges = Vec::new();
+        for cross_hedge in &config.cross_hedges {
+            let orderbook = book(cross_hedge.symbol_id, "exposure", &cross_hedge.name)?;
+            let leg_orderbooks = cross_hedge
+                .hedge_symbol_ids
+                .iter()
+                .map(|&symbol_id| book(symbol_id, "hedge", &cross_hedge.name))
+                .collect::<crate::Result<Vec<_>>>()?;
+
+            // No hedge until the first ratios are estimated
+            let legs = leg_orderbooks
+                .iter()
+                .map(|_| {
+                    DeltaHedge::new(cross_hedge.position, 0.0, config.rehedge_threshold_bps)
+                        .with_clock(clock.clone())
+                })
+                .collect();
+
+            let strategy = Arc::new(
+                CrossHedgeStrategy::new(leg_orderbooks.len(), config.statistics_window_hours)?
+                    .with_change_mode(config.mvhr_change_mode)
+                    .with_recalc_interval(mvhr_interval)
+                    .with_sampling(config.sampling)
+                    .with_clock(clock.clone()),
+            );
+            recalibrator.add_cross_hedge(&cross_hedge.name, strategy.clone());
+
+            cross_hedges.push(CrossHedge {
+                config: cross_hedge.clone(),
+                orderbook,
+                leg_orderbooks,
+                legs,
+                strategy,
+            });
+        }
+
+        for (id, exposure) in exposures.iter().enumerate() {
+            routes[exposure.config.symbol_id as usize]
+                .exposures
+                .push(id);
+            routes[exposure.config.hedge_symbol_id as usize]
+                .hedges
+                .push(id);
+        }
+
+        for (id, cross_hedge) in cross_hedges.iter().enumerate() {
+            let config = &cross_hedge.config;
+            for &symbol_id in std::iter::once(&config.symbol_id).chain(&config.hedge_symbol_ids) {
+                routes[symbol_id as usize].cross_hedges.push(id);
+            }
+        }
 
         Ok(Self {
-            spot_orderbook: Arc::new(OrderBook::new(1)),
-            futures_orderbook: Arc::new(OrderBook::new(2)),
-            delta_hedge,
-            mvhr_strategy,
-            mean_reversion,
-            metrics: Arc::new(RwLock::new(Metrics::new())),
+            health: BookHealthChecker::from_registry(&instruments),
+            unhealthy_book_action: config.unhealthy_book_action,
+            constraints: config.constraints.clone(),
+            ratio_acceptance: config.ratio_acceptance,
+            instruments: Arc::new(instruments),
+            routes,
+            exposures,
+            cross_hedges,
+            price_source: config.price_source,
+            reference_price: config.reference_price,
+            clock,
+            journal: None,
+            recalibrator,
+            recalibration_thread: Mutex::new(None),
+            metrics,
         })
     }
 
     /// Process incoming market data tick
     ///
+    /// Quotes replace the top of the book, dropping levels they cross or
+    /// make stale (size 0 deletes the level). Trades and settlements are
+    /// recorded on it, and clear/halt events empty it. A halted book
+    /// resumes on its next quote or trade. Every tick is appended to the
+    /// journal, if one is set.
+    ///
     /// # Performance
     /// Hot path: ~200-400ns
     pub fn on_tick(&self, tick: MarketTick) {
-        let start_ns = crate::utils::get_timestamp_ns();
-
-        // Update appropriate orderbook
-        match tick.symbol_id {
-            1 => {
-                // Spot market
-                if tick.is_bid() {
-                    self.spot_orderbook.update_bid(
-                        0,
-                        tick.price,
-                        tick.quantity as u64,
-                        tick.timestamp_ns,
-                    );
-                } else {
-                    self.spot_orderbook.update_ask(
-                        0,
-                        tick.price,
-                        tick.quantity as u64,
-                        tick.timestamp_ns,
-                    );
+        self.process_tick(tick, 0);
+    }
+
+    /// Process a tick stamped with its receive time by the feed
+    ///
+    /// Like `on_tick`, and also records the exchange-to-receive latency in
+    /// the feed latency metrics. The journal keeps the receive time.
+    pub fn on_received_tick(&self, received: ReceivedTick) {
+        self.process_tick(received.tick, received.receive_ns);
+    }
+
+    /// Apply a tick (`receive_ns` 0 = receive time unknown)
+    #[inline(always)]
+    fn process_tick(&self, tick: MarketTick, receive_ns: u64) {
+        let start_cycles = crate::utils::read_cycles();
+        self.clock.observe(tick.timestamp_ns);
+
+        if let Some(ref journal) = self.journal
+            && let Err(e) = journal
+                .lock()
+                .append_received(&ReceivedTick::new(tick, receive_ns))
+        {
+            log::error!("{}", e);
+        }
+
+        let route = &self.routes[tick.symbol_id as usize];
+        if let Some(ref orderbook) = route.orderbook {
+            match tick.event() {
+                Some(TickEvent::Quote) => {
+                    if orderbook.is_halted() {
+                        orderbook.set_halted(false, tick.timestamp_ns);
+                    }
+
+                    let side = if tick.is_bid() { Side::Bid } else { Side::Ask };
+                    orderbook.update_top(side, tick.price, tick.quantity as u64, tick.timestamp_ns);
+
+                    if self.price_source == PriceSource::Quotes {
+                        let price = match self.reference_price {
+                            Some(reference) => orderbook.reference_price(reference),
+                            None => Some(orderbook.price_f64(tick.price)),
+                        };
+
+                        if let Some(price) = price {
+                            self.update_statistics(route, price, tick.timestamp_ns);
+                        }
+                    }
                 }
+                Some(TickEvent::Trade) => {
+                    if orderbook.is_halted() {
+                        orderbook.set_halted(false, tick.timestamp_ns);
+                    }
 
-                // Update mean reversion if enabled
-                if let Some(ref mr) = self.mean_reversion {
-                    mr.add_price(tick.price_f64());
+                    orderbook.record_trade(tick.price, tick.quantity as u64, tick.timestamp_ns);
+
+                    if self.price_source == PriceSource::Trades {
+                        let price = orderbook.price_f64(tick.price);
+                        self.update_statistics(route, price, tick.timestamp_ns);
+                    }
                 }
-            }
-            2 => {
-                // Futures market
-                if tick.is_bid() {
-                    self.futures_orderbook.update_bid(
-                        0,
-                        tick.price,
-                        tick.quantity as u64,
-                        tick.timestamp_ns,
-                    );
-                } else {
-                    self.futures_orderbook.update_ask(
-                        0,
-                        tick.price,
-                        tick.quantity as u64,
-                        tick.timestamp_ns,
-                    );
+                Some(TickEvent::Settlement) => {
+                    orderbook.record_settlement(tick.price, tick.timestamp_ns);
                 }
+                Some(TickEvent::BookClear) => orderbook.clear(tick.timestamp_ns),
+                Some(TickEvent::Halt) => orderbook.set_halted(true, tick.timestamp_ns),
+                None => {}
+            }
+        }
+
+        // Record latency
+        let latency_ns =
+            crate::utils::cycles_to_ns(crate::utils::read_cycles().saturating_sub(start_cycles));
+        let mut metrics = self.metrics.write();
+        metrics.record_tick_latency(latency_ns);
+        if receive_ns != 0 {
+            metrics.record_feed_latency(receive_ns.saturating_sub(tick.timestamp_ns));
+        }
+    }
+
+    /// Feed MVHR and mean reversion from a new price on a routed symbol
+    ///
+    /// Prices are sampled by tick timestamp into the configured buckets.
+    #[inline(always)]
+    fn update_statistics(&self, route: &SymbolRoute, price: f64, timestamp_ns: u64) {
+        // Update mean reversion of exposures priced off this symbol
+        for &id in &route.exposures {
+            if let Some(ref mr) = self.exposures[id].mean_reversion {
+                mr.add_price_at(timestamp_ns, price);
+            }
+        }
 
-                // Update MVHR if enabled
-                if let Some(ref mvhr) = self.mvhr_strategy {
-                    let spot_mid = self.spot_orderbook.mid_price();
-                    let futures_mid = self.futures_orderbook.mid_price();
-                    mvhr.add_observation(spot_mid, futures_mid);
+        // Update MVHR of exposures hedged with this symbol
+        for &id in &route.hedges {
+            let exposure = &self.exposures[id];
+            if let Some(ref mvhr) = exposure.mvhr_strategy {
+                let prices = self
+                    .book_price(&exposure.orderbook)
+                    .zip(self.book_price(&exposure.hedge_orderbook));
+
+                if let Some((spot, futures)) = prices {
+                    mvhr.add_observation_at(timestamp_ns, spot, futures);
                 }
             }
-            _ => {}
         }
 
-        // Record latency
-        let latency_ns = crate::utils::get_timestamp_ns() - start_ns;
+        // Update cross hedges on any of their books
+        for &id in &route.cross_hedges {
+            let cross_hedge = &self.cross_hedges[id];
+            let Some(spot) = self.book_price(&cross_hedge.orderbook) else {
+                continue;
+            };
+
+            let futures: Option<SmallVec<[f64; CrossHedgeStrategy::MAX_LEGS]>> = cross_hedge
+                .leg_orderbooks
+                .iter()
+                .map(|book| self.book_price(book))
+                .collect();
+
+            if let Some(futures) = futures {
+                cross_hedge
+                    .strategy
+                    .add_observation_at(timestamp_ns, spot, &futures);
+            }
+        }
+    }
+
+    /// Price of a book for pair statistics (MVHR, cross hedges)
+    ///
+    /// None while a side is empty: a one-sided book has no mid, and zero
+    /// is a valid price.
+    #[inline(always)]
+    fn book_price(&self, book: &OrderBook) -> Option<f64> {
+        match self.price_source {
+            PriceSource::Quotes => match self.reference_price {
+                Some(reference) => book.reference_price(reference),
+                None => book.snapshot(1).mid_price(),
+            },
+            PriceSource::Trades => book.last_trade().map(|(price, _)| price),
+        }
+    }
+
+    /// Process an incremental L2 book update from a depth feed
+    ///
+    /// Unlike `on_tick`, which only maintains the top of book, this keeps
+    /// the full stored depth of the target book in sync with the venue.
+    pub fn on_book_update(&self, symbol_id: u8, update: BookUpdate, timestamp_ns: u64) {
+        let start_cycles = crate::utils::read_cycles();
+        self.clock.observe(timestamp_ns);
+
+        let Some(ref orderbook) = self.routes[symbol_id as usize].orderbook else {
+            return;
+        };
+        orderbook.apply(update, timestamp_ns);
+
+        let latency_ns =
+            crate::utils::cycles_to_ns(crate::utils::read_cycles().saturating_sub(start_cycles));
         self.metrics.write().record_tick_latency(latency_ns);
     }
 
-    /// Get hedge recommendation
+    /// Get hedge recommendation for the primary exposure
     pub fn get_hedge_recommendation(&self) -> crate::Result<Option<HedgeRecommendation>> {
+        self.get_exposure_recommendation(0)
+    }
+
+    /// Get hedge recommendations for all exposures that need rehedging
+    ///
+    /// One (exposure id, result) per exposure with a recommendation or an
+    /// error, so a rejected book or infeasible constraint on one exposure
+    /// does not hold back the others.
+    pub fn get_hedge_recommendations(&self) -> Vec<(usize, crate::Result<HedgeRecommendation>)> {
+        (0..self.exposures.len())
+            .filter_map(|id| {
+                self.get_exposure_recommendation(id)
+                    .transpose()
+                    .map(|rec| (id, rec))
+            })
+            .collect()
+    }
+
+    /// Get hedge recommendation for one exposure
+    ///
+    /// Both books must pass the health check, and the hedge book must
+    /// have liquidity on the execution side. Otherwise the recommendation
+    /// is suppressed or rejected with `Error::UnhealthyBook`, depending on
+    /// `HedgeConfig::unhealthy_book_action`.
+    pub fn get_exposure_recommendation(
+        &self,
+        exposure_id: usize,
+    ) -> crate::Result<Option<HedgeRecommendation>> {
+        let exposure = self.exposure_state(exposure_id)?;
+
+        // Refuse to price off unhealthy market data
+        let now_ns = self.clock.now_ns();
+        if let Err((symbol_id, issue)) = self.check_exposure_books(exposure, now_ns) {
+            return self.unhealthy(symbol_id, issue);
+        }
+
+        let choice = self.choose_mvhr_ratio(exposure);
+        if let Some(RatioChoice {
+            ratio,
+            decision: Some(RatioDecision::Accepted),
+            ..
+        }) = choice
+        {
+            exposure
+                .accepted_ratio
+                .store(ratio.to_bits(), Ordering::Release);
+        }
+
+        // Policy limits apply before sizing the hedge
+        let allocation = if self.constraints.is_unconstrained() {
+            None
+        } else {
+            match self.allocate_exposure(exposure, choice.as_ref(), now_ns) {
+                Ok(allocation) => Some(allocation),
+                Err(crate::Error::UnhealthyBook { symbol_id, issue }) => {
+                    return self.unhealthy(symbol_id, issue);
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        // Size with the constrained ratio, else the chosen MVHR ratio
+        let ratio = match (&allocation, &choice) {
+            (Some(allocation), _) => Some(allocation.legs[0].ratio),
+            (None, Some(choice)) => Some(choice.ratio),
+            (None, None) => None,
+        };
+        if let Some(ratio) = ratio {
+            exposure.delta_hedge.update_hedge_ratio(ratio);
+        }
+
         // Calculate base delta hedge
-        let recommendation = self.delta_hedge.get_recommendation(&self.futures_orderbook);
+        let recommendation = exposure
+            .delta_hedge
+            .get_recommendation(&exposure.hedge_orderbook);
 
         if let Some(mut rec) = recommendation {
-            // Adjust with MVHR if enabled
-            if let Some(ref mvhr) = self.mvhr_strategy {
-                let optimal_ratio = mvhr.get_hedge_ratio();
-                self.delta_hedge.update_hedge_ratio(optimal_ratio);
-                rec.reason
-                    .push_str(&format!(" [MVHR ratio: {:.3}]", optimal_ratio));
+            // The hedge book needs liquidity on the execution side
+            if let Err(issue) = self
+                .health
+                .check(&exposure.hedge_orderbook, Some(rec.side), now_ns)
+            {
+                return self.unhealthy(exposure.config.hedge_symbol_id, issue);
             }
 
-            // Adjust with mean reversion if enabled
-            if let Some(ref mr) = self.mean_reversion {
-                let current_price = self.spot_orderbook.mid_price();
-                if let Some(adjustment) = mr.should_adjust_hedge(current_price) {
-                    rec.quantity *= adjustment;
-                    rec.reason
-                        .push_str(&format!(" [MR adjustment: {:.2}]", adjustment));
+            if let (Some(mvhr), Some(choice)) = (&exposure.mvhr_strategy, &choice) {
+                match mvhr.kalman_diagnostics() {
+                    Some(kalman) => rec.reason.push_str(&format!(
+                        " [Kalman ratio: {:.3} ± {:.3}]",
+                        choice.ratio,
+                        kalman.variance.sqrt()
+                    )),
+                    None => rec
+                        .reason
+                        .push_str(&format!(" [MVHR ratio: {:.3}]", choice.ratio)),
+                }
+                if let Some(decision) = choice.decision
+                    && decision != RatioDecision::Accepted
+                {
+                    rec.reason.push_str(&format!(
+                        " [new ratio {:.3} not applied: {}]",
+                        choice.candidate, decision
+                    ));
                 }
             }
 
+            // Adjust with mean reversion if enabled, scoring the price
+            // definition its history was built from
+            if let Some(ref mr) = exposure.mean_reversion
+                && let Some(current_price) = mr.last_price()
+                && let Some(adjustment) = mr.should_adjust_hedge(current_price)
+            {
+                rec.quantity *= adjustment;
+                rec.reason
+                    .push_str(&format!(" [MR adjustment: {:.2}]", adjustment));
+            }
+
+            if let Some(ref allocation) = allocation {
+                let leg = &allocation.legs[0];
+                rec.reason.push_str(&format!(
+                    " [constrained ratio: {:.3} ({})]",
+                    leg.ratio, leg.binding
+                ));
+            }
+
+            let Some(rec) = self.round_to_lots(rec, exposure.config.hedge_symbol_id) else {
+                return Ok(None);
+            };
+            let mut rec = self.price_against_depth(
+                rec,
+                exposure.config.hedge_symbol_id,
+                &exposure.hedge_orderbook,
+            );
+            rec.exposure_id = exposure_id;
             Ok(Some(rec))
         } else {
             Ok(None)
         }
     }
 
+    /// MVHR ratio to apply to an exposure (None without MVHR or before its
+    /// first estimate, leaving the configured ratio in place)
+    ///
+    /// With `HedgeConfig::ratio_acceptance`, a new estimate replaces the
+    /// last accepted ratio only if it passes the checks; the caller
+    /// records an accepted one. The O(n) checks run once per MVHR
+    /// estimate: the choice is reused until the ratio is recalculated.
+    fn choose_mvhr_ratio(&self, exposure: &Exposure) -> Option<RatioChoice> {
+        let mvhr = exposure
+            .mvhr_strategy
+            .as_ref()
+            .filter(|mvhr| mvhr.ratio_revision() > 0)?;
+        let candidate = mvhr.get_hedge_ratio();
+        let Some(acceptance) = self.ratio_acceptance else {
+            return Some(RatioChoice {
+                ratio: candidate,
+                candidate,
+                decision: None,
+            });
+        };
+
+        let revision = mvhr.ratio_revision();
+        let mut cached = exposure.ratio_choice.lock();
+        if let Some((cached_revision, choice)) = *cached
+            && cached_revision == revision
+        {
+            return Some(choice);
+        }
+
+        let current = f64::from_bits(exposure.accepted_ratio.load(Ordering::Acquire));
+        let decision = (candidate != current).then(|| {
+            let diagnostics =
+                mvhr.ratio_diagnostics(acceptance.confidence_level, acceptance.stability_windows);
+            acceptance.evaluate(current, candidate, diagnostics.as_ref())
+        });
+
+        let choice = RatioChoice {
+            ratio: match decision {
+                Some(RatioDecision::Accepted) => candidate,
+                _ => current,
+            },
+            candidate,
+            decision,
+        };
+        *cached = Some((revision, choice));
+        Some(choice)
+    }
+
+    /// Health of an exposure's books, before a hedge side is known
+    fn check_exposure_books(
+        &self,
+        exposure: &Exposure,
+        now_ns: u64,
+    ) -> Result<(), (u8, BookIssue)> {
+        self.health
+            .check(&exposure.orderbook, None, now_ns)
+            .map_err(|issue| (exposure.config.symbol_id, issue))?;
+        self.health
+            .check(&exposure.hedge_orderbook, None, now_ns)
+            .map_err(|issue| (exposure.config.hedge_symbol_id, issue))
+    }
+
+    /// Constrained allocation of an exposure's hedge
+    ///
+    /// Starts from the chosen MVHR ratio when available, otherwise from
+    /// the configured ratio. Notional is measured at the hedge book mid;
+    /// fails with `Error::UnhealthyBook` without one.
+    fn allocate_exposure(
+        &self,
+        exposure: &Exposure,
+        choice: Option<&RatioChoice>,
+        now_ns: u64,
+    ) -> crate::Result<ConstrainedAllocation> {
+        let ratio = choice.map_or(exposure.config.hedge_ratio, |choice| choice.ratio);
+        let covariance = match exposure
+            .mvhr_strategy
+            .as_ref()
+            .and_then(|mvhr| mvhr.get_statistics())
+            .filter(|stats| stats.futures_volatility > 0.0)
+        {
+            Some(stats) => HedgeCovariance::single(
+                stats.futures_volatility.powi(2),
+                stats.spot_volatility.powi(2),
+                ratio,
+            ),
+            None => HedgeCovariance::target(&[ratio]),
+        };
+
+        let legs = [self.leg_terms(
+            exposure.config.hedge_symbol_id,
+            &exposure.hedge_orderbook,
+            now_ns,
+        )?];
+        self.constraints
+            .allocate(&covariance, exposure.delta_hedge.get_position(), &legs)
+    }
+
+    /// Market terms of a hedge leg for constrained allocation
+    ///
+    /// The leg book must pass the health check and have both sides: an
+    /// empty or one-sided book has no mid to measure notional at.
+    fn leg_terms(
+        &self,
+        symbol_id: u8,
+        orderbook: &OrderBook,
+        now_ns: u64,
+    ) -> crate::Result<LegTerms> {
+        let unhealthy = |issue| crate::Error::UnhealthyBook { symbol_id, issue };
+        self.health
+            .check(orderbook, None, now_ns)
+            .map_err(unhealthy)?;
+
+        let top = orderbook.snapshot(1);
+        let price = top.mid_price().ok_or_else(|| {
+            let side = if top.best_bid().is_none() {
+                Side::Bid
+            } else {
+                Side::Ask
+            };
+            unhealthy(BookIssue::EmptySide(side))
+        })?;
+
+        Ok(LegTerms {
+            symbol_id,
+            price,
+            contract_size: self.instrument(symbol_id).contract_size,
+        })
+    }
+
+    /// Round a recommendation to whole lots if the constraints require it
+    ///
+    /// None if less than half a lot remains.
+    fn round_to_lots(
+        &self,
+        mut rec: HedgeRecommendation,
+        symbol_id: u8,
+    ) -> Option<HedgeRecommendation> {
+        if self.constraints.whole_lots {
+            let instrument = self.instrument(symbol_id);
+            let lots = instrument.quantity_to_lots(rec.quantity).round();
+            if lots == 0.0 {
+                return None;
+            }
+            rec.quantity = instrument.lots_to_quantity(lots);
+        }
+        Some(rec)
+    }
+
+    /// Get the constrained hedge allocation of an exposure
+    ///
+    /// Reports the feasible ratio, lots and notional of the hedge under
+    /// `HedgeConfig::constraints`, and which constraint binds. Fails with
+    /// `Error::UnhealthyBook` if either book fails the health check or the
+    /// hedge book has no mid.
+    pub fn get_exposure_allocation(
+        &self,
+        exposure_id: usize,
+    ) -> crate::Result<ConstrainedAllocation> {
+        let exposure = self.exposure_state(exposure_id)?;
+        let now_ns = self.clock.now_ns();
+        self.check_exposure_books(exposure, now_ns)
+            .map_err(|(symbol_id, issue)| crate::Error::UnhealthyBook { symbol_id, issue })?;
+        self.allocate_exposure(exposure, self.choose_mvhr_ratio(exposure).as_ref(), now_ns)
+    }
+
+    /// Get the regression diagnostics of an exposure's MVHR ratio
+    ///
+    /// Standard error, confidence interval, hedge effectiveness, residual
+    /// autocorrelation and sub-window stability, at the levels of
+    /// `HedgeConfig::ratio_acceptance` (defaults if unset). None without
+    /// MVHR or enough changes. O(n) over the window.
+    pub fn get_ratio_diagnostics(
+        &self,
+        exposure_id: usize,
+    ) -> crate::Result<Option<RatioDiagnostics>> {
+        let acceptance = self.ratio_acceptance.unwrap_or_default();
+        Ok(self
+            .exposure_state(exposure_id)?
+            .mvhr_strategy
+            .as_ref()
+            .and_then(|mvhr| {
+                mvhr.ratio_diagnostics(acceptance.confidence_level, acceptance.stability_windows)
+            }))
+    }
+
+    /// Get an exposure's mean reversion statistics and OU fit
+    ///
+    /// None without mean reversion enabled.
+    pub fn get_mean_reversion_statistics(
+        &self,
+        exposure_id: usize,
+    ) -> crate::Result<Option<MeanReversionStats>> {
+        Ok(self
+            .exposure_state(exposure_id)?
+            .mean_reversion
+            .as_ref()
+            .map(|mr| mr.get_statistics()))
+    }
+
+    /// Test whether an exposure's prices mean-revert
+    ///
+    /// ADF and KPSS on the mean reversion price history (if enabled) and
+    /// Engle-Granger of spot against futures on the MVHR window (if
+    /// enabled); spark spreads are tested by
+    /// `SparkSpreadHedge::cointegration`. O(n · lags) over windows thinned
+    /// to `StationarityReport::MAX_OBSERVATIONS`: cold path.
+    pub fn get_stationarity(&self, exposure_id: usize) -> crate::Result<StationarityReport> {
+        let exposure = self.exposure_state(exposure_id)?;
+        let mut report = exposure
+            .mean_reversion
+            .as_ref()
+            .map(|mr| mr.stationarity())
+            .unwrap_or_default();
+        report.cointegration = exposure
+            .mvhr_strategy
+            .as_ref()
+            .and_then(|mvhr| mvhr.cointegration());
+        Ok(report)
+    }
+
+    /// Get the constrained allocation of a cross hedge across its legs
+    ///
+    /// None before the first recalibration. Fails with
+    /// `Error::UnhealthyBook` if a leg book fails the health check or has
+    /// no mid.
+    pub fn get_cross_hedge_allocation(
+        &self,
+        cross_hedge_id: usize,
+    ) -> crate::Result<Option<ConstrainedAllocation>> {
+        let cross_hedge = self.cross_hedge_state(cross_hedge_id)?;
+        match cross_hedge.strategy.get_statistics() {
+            Some(stats) => self
+                .allocate_cross_hedge(cross_hedge, &stats.covariance, self.clock.now_ns())
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn allocate_cross_hedge(
+        &self,
+        cross_hedge: &CrossHedge,
+        covariance: &HedgeCovariance,
+        now_ns: u64,
+    ) -> crate::Result<ConstrainedAllocation> {
+        let legs: Vec<LegTerms> = cross_hedge
+            .config
+            .hedge_symbol_ids
+            .iter()
+            .zip(&cross_hedge.leg_orderbooks)
+            .map(|(&symbol_id, orderbook)| self.leg_terms(symbol_id, orderbook, now_ns))
+            .collect::<crate::Result<_>>()?;
+        self.constraints
+            .allocate(covariance, cross_hedge.legs[0].get_position(), &legs)
+    }
+
+    /// Resolve a recommendation against its hedge instrument and price the
+    /// full quantity against visible depth
+    fn price_against_depth(
+        &self,
+        rec: HedgeRecommendation,
+        symbol_id: u8,
+        orderbook: &OrderBook,
+    ) -> HedgeRecommendation {
+        let rec = rec.with_instrument(self.instrument(symbol_id));
+        let book = orderbook.snapshot(OrderBook::MAX_LEVELS);
+        let fill = book.estimate_fill(rec.side, rec.lots);
+        let reference = book.mid_price().unwrap_or(rec.price);
+        rec.with_fill(&fill, reference)
+    }
+
+    /// Get one recommendation per leg of a cross hedge that needs rehedging
+    ///
+    /// Each leg targets the exposure times the leg's minimum-variance
+    /// ratio, so legs are empty until the first recalibration. Books are
+    /// health checked per leg like `get_exposure_recommendation`; a
+    /// suppressed leg does not hold back the others, except under
+    /// constraints, where the joint allocation needs every leg priced.
+    pub fn get_cross_hedge_recommendations(
+        &self,
+        cross_hedge_id: usize,
+    ) -> crate::Result<Vec<HedgeRecommendation>> {
+        let cross_hedge = self.cross_hedge_state(cross_hedge_id)?;
+        let statistics = cross_hedge.strategy.get_statistics();
+        let r_squared = statistics.as_ref().map_or(0.0, |stats| stats.r_squared);
+
+        // Policy limits apply once ratios are estimated
+        let allocation = match statistics {
+            Some(ref stats) if !self.constraints.is_unconstrained() => {
+                match self.allocate_cross_hedge(cross_hedge, &stats.covariance, self.clock.now_ns())
+                {
+                    Ok(allocation) => Some(allocation),
+                    Err(crate::Error::UnhealthyBook { symbol_id, issue }) => {
+                        self.unhealthy(symbol_id, issue)?;
+                        return Ok(Vec::new());
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => None,
+        };
+
+        let mut recommendations = Vec::new();
+        for (leg, delta_hedge) in cross_hedge.legs.iter().enumerate() {
+            let ratio = match allocation {
+                Some(ref allocation) => allocation.legs[leg].ratio,
+                None => cross_hedge.strategy.get_hedge_ratio(leg),
+            };
+            delta_hedge.update_hedge_ratio(ratio);
+
+            let orderbook = &cross_hedge.leg_orderbooks[leg];
+            let Some(mut rec) = delta_hedge.get_recommendation(orderbook) else {
+                continue;
+            };
+
+            let symbol_id = cross_hedge.config.hedge_symbol_ids[leg];
+            let now_ns = self.clock.now_ns();
+            let health = self
+                .health
+                .check(&cross_hedge.orderbook, None, now_ns)
+                .map_err(|issue| (cross_hedge.config.symbol_id, issue))
+                .and_then(|_| {
+                    self.health
+                        .check(orderbook, Some(rec.side), now_ns)
+                        .map_err(|issue| (symbol_id, issue))
+                });
+
+            if let Err((unhealthy_id, issue)) = health {
+                self.unhealthy(unhealthy_id, issue)?;
+                continue;
+            }
+
+            rec.reason.push_str(&format!(
+                " [cross hedge leg {}: ratio {:.3}, R² {:.2}]",
+                leg, ratio, r_squared
+            ));
+            if let Some(ref allocation) = allocation {
+                rec.reason
+                    .push_str(&format!(" [{}]", allocation.legs[leg].binding));
+            }
+
+            let Some(rec) = self.round_to_lots(rec, symbol_id) else {
+                continue;
+            };
+            let mut rec = self.price_against_depth(rec, symbol_id, orderbook);
+            rec.cross_hedge_id = Some(cross_hedge_id);
+            rec.leg = Some(leg);
+            recommendations.push(rec);
+        }
+
+        Ok(recommendations)
+    }
+
+    /// Suppress or reject a recommendation priced off an unhealthy book
+    fn unhealthy(
+        &self,
+        symbol_id: u8,
+        issue: BookIssue,
+    ) -> crate::Result<Option<HedgeRecommendation>> {
+        match self.unhealthy_book_action {
+            UnhealthyBookAction::Suppress => Ok(None),
+            UnhealthyBookAction::Reject => Err(crate::Error::UnhealthyBook { symbol_id, issue }),
+        }
+    }
+
     /// Execute hedge (update internal state)
+    ///
+    /// The hedge is booked against the recommendation's `exposure_id`, or
+    /// against leg `leg` of cross hedge `cross_hedge_id` if that is set.
     pub fn execute_hedge(&self, recommendation: &HedgeRecommendation) -> crate::Result<()> {
-        self.delta_hedge
-            .execute_hedge(recommendation.quantity, recommendation.side);
+        let delta_hedge = match recommendation.cross_hedge_id {
+            Some(cross_hedge_id) => {
+                let legs = &self.cross_hedge_state(cross_hedge_id)?.legs;
+                recommendation
+                    .leg
+                    .and_then(|leg| legs.get(leg))
+                    .ok_or_else(|| {
+                        crate::Error::InvalidState(format!(
+                            "Unknown leg {:?} of cross hedge {}",
+                            recommendation.leg, cross_hedge_id
+                        ))
+                    })?
+            }
+            None => &self.exposure_state(recommendation.exposure_id)?.delta_hedge,
+        };
+        delta_hedge.execute_hedge(recommendation.quantity, recommendation.side);
         self.metrics
             .write()
             .record_hedge_execution(recommendation.quantity);
         Ok(())
     }
 
-    /// Get current position
+    /// Get current position of the primary exposure
     pub fn get_position(&self) -> f64 {
-        self.delta_hedge.get_position()
+        self.exposures[0].delta_hedge.get_position()
     }
 
-    /// Get current hedge position
+    /// Get current hedge position of the primary exposure
     pub fn get_hedge_position(&self) -> f64 {
-        self.delta_hedge.get_hedge_position()
+        self.exposures[0].delta_hedge.get_hedge_position()
     }
 
-    /// Get metrics
-    pub fn get_metrics(&self) -> Metrics {
-        self.metrics.read().clone()
+    /// Get current position of an exposure
+    pub fn get_exposure_position(&self, exposure_id: usize) -> crate::Result<f64> {
+        Ok(self.exposure_state(exposure_id)?.delta_hedge.get_position())
     }
 
-    /// Get spot orderbook
-    pub fn spot_orderbook(&self) -> &OrderBook {
-        &self.spot_orderbook
+    /// Get current hedge position of an exposure
+    pub fn get_exposure_hedge_position(&self, exposure_id: usize) -> crate::Result<f64> {
+        Ok(self
+            .exposure_state(exposure_id)?
+            .delta_hedge
+            .get_hedge_position())
     }
 
-    /// Get futures orderbook
-    pub fn futures_orderbook(&self) -> &OrderBook {
-        &self.futures_orderbook
+    /// Update the physical position of an exposure
+    pub fn update_exposure_position(&self, exposure_id: usize, position: f64) -> crate::Result<()> {
+        self.exposure_state(exposure_id)?
+            .delta_hedge
+            .update_position(position);
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::market_data::MarketTick;
-    use crate::utils::get_timestamp_ns;
+    /// Number of managed exposures
+    pub fn exposure_count(&self) -> usize {
+        self.exposures.len()
+    }
 
-    #[test]
-    fn test_engine_creation() {
-        let config = HedgeConfig::simple(-10_000.0, 1.125);
-        let engine = HedgeEngine::new(config);
+    /// Get an exposure definition
+    pub fn exposure(&self, exposure_id: usize) -> Option<&ExposureConfig> {
+        self.exposures.get(exposure_id).map(|e| &e.config)
+    }
 
-        assert!(engine.is_ok());
+    /// Find an exposure by name
+    pub fn exposure_id(&self, name: &str) -> Option<usize> {
+        self.exposures.iter().position(|e| e.config.name == name)
+    }
 
-        let engine = engine.unwrap();
-        assert_eq!(engine.get_position(), -10_000.0);
+    /// Number of cross hedges
+    pub fn cross_hedge_count(&self) -> usize {
+        self.cross_hedges.len()
     }
 
-    #[test]
-    fn test_engine_tick_processing() {
-        let config = HedgeConfig::simple(-10_000.0, 1.125);
-        let engine = HedgeEngine::new(config).unwrap();
+    /// Get a cross hedge definition
+    pub fn cross_hedge(&self, cross_hedge_id: usize) -> Option<&CrossHedgeConfig> {
+        self.cross_hedges.get(cross_hedge_id).map(|c| &c.config)
+    }
 
-        // Send spot tick
-        let tick = MarketTick::bid(get_timestamp_ns(), 45.50, 100, 1);
-        engine.on_tick(tick);
+    /// Find a cross hedge by name
+    pub fn cross_hedge_id(&self, name: &str) -> Option<usize> {
+        self.cross_hedges.iter().position(|c| c.config.name == name)
+    }
 
-        // Send futures tick
-        let tick = MarketTick::ask(get_timestamp_ns(), 50.15, 120, 2);
-        engine.on_tick(tick);
+    /// Get the ratios, residual variance and R² of the last cross hedge
+    /// recalibration (None before the first)
+    pub fn get_cross_hedge_statistics(
+        &self,
+        cross_hedge_id: usize,
+    ) -> crate::Result<Option<CrossHedgeStatistics>> {
+        Ok(self
+            .cross_hedge_state(cross_hedge_id)?
+            .strategy
+            .get_statistics())
+    }
 
-        // Check orderbooks updated
-        let (spot_bid, _) = engine.spot_orderbook().best_bid();
-        assert_eq!(spot_bid, 45.50);
+    /// Get the hedge position of every leg of a cross hedge
+    pub fn get_cross_hedge_positions(&self, cross_hedge_id: usize) -> crate::Result<Vec<f64>> {
+        Ok(self
+            .cross_hedge_state(cross_hedge_id)?
+            .legs
+            .iter()
+            .map(DeltaHedge::get_hedge_position)
+            .collect())
+    }
 
-        let (futures_ask, _) = engine.futures_orderbook().best_ask();
-        assert_eq!(futures_ask, 50.15);
+    /// Update the physical position of a cross hedge
+    pub fn update_cross_hedge_position(
+        &self,
+        cross_hedge_id: usize,
+        position: f64,
+    ) -> crate::Result<()> {
+        for leg in &self.cross_hedge_state(cross_hedge_id)?.legs {
+            leg.update_position(position);
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_hedge_recommendation() {
-        let config = HedgeConfig::simple(-10_000.0, 1.125);
-        let engine = HedgeEngine::new(config).unwrap();
+    fn cross_hedge_state(&self, cross_hedge_id: usize) -> crate::Result<&CrossHedge> {
+        self.cross_hedges.get(cross_hedge_id).ok_or_else(|| {
+            crate::Error::InvalidState(format!("Unknown cross hedge {}", cross_hedge_id))
+        })
+    }
+
+    fn exposure_state(&self, exposure_id: usize) -> crate::Result<&Exposure> {
+        self.exposures
+            .get(exposure_id)
+            .ok_or_else(|| crate::Error::InvalidState(format!("Unknown exposure {}", exposure_id)))
+    }
+
+    fn instrument(&self, symbol_id: u8) -> &Instrument {
+        self.instruments
+            .get(symbol_id)
+            .expect("exposure instruments validated at construction")
+    }
+
+    /// Get metrics
+    pub fn get_metrics(&self) -> Metrics {
+        self.metrics.read().clone()
+    }
+
+    /// Get the instrument registry
+    pub fn instruments(&self) -> &InstrumentRegistry {
+        &self.instruments
+    }
+
+    /// Get the spot (exposure) instrument of the primary exposure
+    pub fn spot_instrument(&self) -> &Instrument {
+        self.instrument(self.exposures[0].config.symbol_id)
+    }
+
+    /// Get the futures (hedge) instrument of the primary exposure
+    pub fn futures_instrument(&self) -> &Instrument {
+        self.instrument(self.exposures[0].config.hedge_symbol_id)
+    }
+
+    /// Get the orderbook for a symbol
+    pub fn orderbook(&self, symbol_id: u8) -> Option<&OrderBook> {
+        self.routes[symbol_id as usize].orderbook.as_deref()
+    }
+
+    /// Get the engine's time source
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Get the book health checker
+    pub fn health_checker(&self) -> &BookHealthChecker {
+        &self.health
+    }
+
+    /// Record every tick reaching `on_tick` into a journal
+    ///
+    /// Full blocks are written on a background thread (see
+    /// `TickJournalWriter::in_background`), keeping disk I/O off
+    /// `on_tick`. Replaces (and flushes) any previous journal.
+    pub fn set_journal(&mut self, journal: TickJournalWriter) -> crate::Result<()> {
+        self.journal = Some(Mutex::new(journal.in_background()?));
+        Ok(())
+    }
+
+    /// Stop journaling and return the journal
+    pub fn take_journal(&mut self) -> Option<TickJournalWriter> {
+        self.journal.take().map(Mutex::into_inner)
+    }
+
+    /// Write buffered journal ticks to disk
+    pub fn flush_journal(&self) -> crate::Result<()> {
+        match self.journal {
+            Some(ref journal) => journal.lock().flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Run due recalibrations on the calling thread
+    ///
+    /// Recomputes the MVHR ratio and mean reversion mean/std of every
+    /// exposure and the ratios of every cross hedge whose interval has
+    /// elapsed on the engine clock, plus due user tasks. With a
+    /// `SimulatedClock`, call this between ticks for deterministic replay.
+    /// Returns the number of recalibrations run.
+    pub fn recalibrate(&self) -> usize {
+        self.recalibrator.run_due()
+    }
+
+    /// Start recalibrating on a background thread
+    ///
+    /// The thread checks for due tasks every
+    /// `RecalibrationConfig::poll_interval_ms` until
+    /// `stop_recalibration` is called or the engine is dropped.
+    pub fn start_recalibration(&self) -> crate::Result<()> {
+        let mut thread = self.recalibration_thread.lock();
+        if thread.is_some() {
+            return Err(crate::Error::InvalidState(
+                "Recalibration thread already running".to_string(),
+            ));
+        }
+        *thread = Some(RecalibrationThread::spawn(self.recalibrator.clone())?);
+        Ok(())
+    }
+
+    /// Stop the background recalibration thread and wait for it to exit
+    pub fn stop_recalibration(&self) {
+        if let Some(thread) = self.recalibration_thread.lock().take() {
+            thread.stop();
+        }
+    }
+
+    /// Check whether the background recalibration thread is running
+    pub fn is_recalibrating(&self) -> bool {
+        self.recalibration_thread.lock().is_some()
+    }
+
+    /// Run `task` on the recalibration schedule every `interval`
+    ///
+    /// E.g. to call `HedgingStrategy::update_parameters` on strategies
+    /// owned by the caller. Errors are reported like built-in
+    /// recalibrations.
+    pub fn schedule_recalibration<F>(&self, name: &str, interval: Duration, task: F)
+    where
+        F: FnMut() -> crate::Result<()> + Send + 'static,
+    {
+        self.recalibrator.add_custom(name, interval, Box::new(task));
+    }
+
+    /// Take recalibration failures recorded since the last call
+    ///
+    /// Failures are also logged and counted in the metrics. At most the
+    /// 256 most recent are kept.
+    pub fn take_recalibration_failures(&self) -> Vec<RecalibrationFailure> {
+        self.recalibrator.take_failures()
+    }
+
+    /// Get a shared handle to the orderbook of a symbol
+    ///
+    /// Used to attach books to a feed that marks them stale on gaps.
+    pub fn shared_orderbook(&self, symbol_id: u8) -> Option<Arc<OrderBook>> {
+        self.routes[symbol_id as usize].orderbook.clone()
+    }
+
+    /// Get spot orderbook of the primary exposure
+    pub fn spot_orderbook(&self) -> &OrderBook {
+        &self.exposures[0].orderbook
+    }
+
+    /// Get futures orderbook of the primary exposure
+    pub fn futures_orderbook(&self) -> &OrderBook {
+        &self.exposures[0].hedge_orderbook
+    }
+}
+
+impl Drop for HedgeEngine {
+    fn drop(&mut self) {
+        self.stop_recalibration();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use crate::hedging::SamplingConfig;
+    use crate::market_data::{Commodity, MarketTick, Side};
+    use crate::utils::get_timestamp_ns;
+
+    #[test]
+    fn test_engine_creation() {
+        let config = HedgeConfig::simple(-10_000.0, 1.125);
+        let engine = HedgeEngine::new(config);
+
+        assert!(engine.is_ok());
+
+        let engine = engine.unwrap();
+        assert_eq!(engine.get_position(), -10_000.0);
+    }
+
+    #[test]
+    fn test_engine_tick_processing() {
+        let config = HedgeConfig::simple(-10_000.0, 1.125);
+        let engine = HedgeEngine::new(config).unwrap();
+
+        // Send spot tick
+        let tick = MarketTick::bid(get_timestamp_ns(), 45.50, 100, 1);
+        engine.on_tick(tick);
+
+        // Send futures tick
+        let tick = MarketTick::ask(get_timestamp_ns(), 50.15, 120, 2);
+        engine.on_tick(tick);
+
+        // Check orderbooks updated
+        let (spot_bid, _) = engine.spot_orderbook().best_bid();
+        assert_eq!(spot_bid, 45.50);
+
+        let (futures_ask, _) = engine.futures_orderbook().best_ask();
+        assert_eq!(futures_ask, 50.15);
+    }
+
+    #[test]
+    fn test_engine_received_tick_feed_latency() {
+        let engine = HedgeEngine::new(HedgeConfig::simple(-10_000.0, 1.125)).unwrap();
+
+        let tick = MarketTick::bid(1_000_000, 45.50, 100, 1);
+        engine.on_received_tick(ReceivedTick::new(tick, 1_030_000));
+        engine.on_tick(MarketTick::ask(1_000_000, 50.15, 120, 2));
+
+        let metrics = engine.get_metrics();
+        assert_eq!(metrics.ticks_processed(), 2);
+        assert_eq!(metrics.avg_feed_latency_ns(), 30_000);
+        assert_eq!(engine.spot_orderbook().best_bid().0, 45.50);
+    }
+
+    #[test]
+    fn test_hedge_recommendation() {
+        let config = HedgeConfig::simple(-10_000.0, 1.125);
+        let engine = HedgeEngine::new(config).unwrap();
 
         // Setup orderbooks
         let spot_tick = MarketTick::bid(get_timestamp_ns(), 45.50, 100, 1);
@@ -259,4 +1357,1035 @@ mod tests {
         // Should recommend ~11,250 MWh
         assert!((rec.quantity - 11_250.0).abs() < 100.0);
     }
+
+    #[test]
+    fn test_engine_book_updates() {
+        let engine = HedgeEngine::new(HedgeConfig::simple(-10_000.0, 1.125)).unwrap();
+
+        let ts = get_timestamp_ns();
+        for (i, price) in [501500, 502000, 502500].iter().enumerate() {
+            let update = BookUpdate::New {
+                side: Side::Ask,
+                price: *price,
+                size: 100 + i as u64,
+            };
+            engine.on_book_update(2, update, ts);
+        }
+        engine.on_book_update(
+            2,
+            BookUpdate::Delete {
+                side: Side::Ask,
+                price: 501500,
+            },
+            ts,
+        );
+
+        let asks = engine.futures_orderbook().get_asks(10);
+        assert_eq!(asks, vec![(50.2, 101), (50.25, 102)]);
+        assert_eq!(engine.get_metrics().ticks_processed(), 4);
+    }
+
+    #[test]
+    fn test_engine_negative_prices() {
+        let config = HedgeConfig {
+            mvhr_change_mode: crate::hedging::PriceChangeMode::Absolute,
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, -5.25, 100, 1));
+        engine.on_tick(MarketTick::ask(ts, 0.0, 100, 1));
+        engine.on_tick(MarketTick::bid(ts, -2.10, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, -2.00, 100, 2));
+
+        assert_eq!(engine.spot_orderbook().best_bid(), (-5.25, 100));
+        assert_eq!(engine.spot_orderbook().get_asks(10), vec![(0.0, 100)]);
+        assert_eq!(engine.futures_orderbook().mid_price(), -2.05);
+
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert_eq!(rec.price, -2.00);
+        assert!(rec.quantity.is_finite());
+    }
+
+    #[test]
+    fn test_engine_one_sided_books_skip_statistics() {
+        let config = HedgeConfig {
+            enable_mvhr: true,
+            sampling: SamplingConfig::every_tick(),
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+        let mvhr = engine.exposures[0].mvhr_strategy.as_ref().unwrap();
+
+        // No mid while a side is empty, even at a zero price
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 0.0, 100, 1));
+        engine.on_tick(MarketTick::bid(ts, 50.0, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, 50.2, 100, 2));
+        assert!(mvhr.get_statistics().is_none());
+
+        engine.on_tick(MarketTick::ask(ts, 0.2, 100, 1));
+        engine.on_tick(MarketTick::ask(ts, 50.3, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, 50.4, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, 50.5, 100, 2));
+        assert_eq!(mvhr.get_statistics().unwrap().observations, 3);
+    }
+
+    #[test]
+    fn test_engine_with_instruments() {
+        let mut registry = InstrumentRegistry::new();
+        registry
+            .register(Instrument::new(10, "DE Spot", "EPEX", Commodity::Power))
+            .unwrap();
+        let mut cal = Instrument::new(11, "DE Base Cal-26", "EEX", Commodity::Power);
+        cal.contract_size = 8760.0;
+        registry.register(cal).unwrap();
+
+        let config = HedgeConfig {
+            spot_symbol_id: 10,
+            futures_symbol_id: 11,
+            ..HedgeConfig::simple(-87_600.0, 1.0)
+        };
+        let engine = HedgeEngine::with_instruments(config, registry).unwrap();
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 80.0, 10, 10));
+        engine.on_tick(MarketTick::ask(ts, 85.123, 10, 11));
+        // Unregistered symbols are ignored
+        engine.on_tick(MarketTick::ask(ts, 99.0, 10, 2));
+
+        assert_eq!(engine.futures_orderbook().best_ask(), (85.123, 10));
+
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert_eq!(rec.symbol_id, 11);
+        assert!((rec.lots - 10.0).abs() < 1e-9);
+        assert!((rec.price - 85.12).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_engine_rejects_unknown_symbols() {
+        let config = HedgeConfig {
+            futures_symbol_id: 42,
+            ..HedgeConfig::default()
+        };
+        assert!(HedgeEngine::new(config).is_err());
+    }
+
+    #[test]
+    fn test_engine_multiple_exposures() {
+        use crate::hedging::ExposureConfig;
+        use crate::market_data::{Commodity, Instrument};
+
+        let mut registry = InstrumentRegistry::new();
+        for (id, name) in [
+            (1, "DE Spot"),
+            (2, "DE Base M1"),
+            (3, "DE Base Q1"),
+            (4, "FR Spot"),
+        ] {
+            registry
+                .register(Instrument::new(id, name, "EEX", Commodity::Power))
+                .unwrap();
+        }
+
+        let config = HedgeConfig {
+            enable_mvhr: false,
+            exposures: vec![
+                ExposureConfig::new("DE month", 1, 2, -10_000.0, 1.0),
+                ExposureConfig::new("DE quarter", 1, 3, -30_000.0, 1.0),
+                ExposureConfig::new("FR month", 4, 2, 5_000.0, 1.0),
+            ],
+            ..HedgeConfig::default()
+        };
+        let engine = HedgeEngine::with_instruments(config, registry).unwrap();
+        assert_eq!(engine.exposure_count(), 3);
+        assert_eq!(engine.exposure_id("FR month"), Some(2));
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 79.90, 50, 2));
+        engine.on_tick(MarketTick::ask(ts, 80.00, 50, 2));
+        engine.on_tick(MarketTick::bid(ts, 84.90, 50, 3));
+        engine.on_tick(MarketTick::ask(ts, 85.00, 50, 3));
+
+        let recs: Vec<_> = engine
+            .get_hedge_recommendations()
+            .into_iter()
+            .map(|(_, rec)| rec.unwrap())
+            .collect();
+        assert_eq!(recs.len(), 3);
+
+        assert_eq!((recs[0].exposure_id, recs[0].symbol_id), (0, 2));
+        assert_eq!((recs[1].exposure_id, recs[1].symbol_id), (1, 3));
+        assert_eq!((recs[2].exposure_id, recs[2].symbol_id), (2, 2));
+        assert_eq!(recs[1].price, 85.00);
+        assert_eq!(recs[2].side, Side::Bid);
+        assert_eq!(recs[2].price, 79.90);
+
+        for rec in &recs {
+            engine.execute_hedge(rec).unwrap();
+        }
+        assert_eq!(engine.get_exposure_hedge_position(1).unwrap(), 30_000.0);
+        assert_eq!(engine.get_exposure_hedge_position(2).unwrap(), -5_000.0);
+        assert!(engine.get_hedge_recommendations().is_empty());
+
+        // Convenience accessors follow the primary exposure
+        assert_eq!(engine.futures_orderbook().symbol_id(), 2);
+        assert_eq!(engine.orderbook(3).unwrap().best_ask(), (85.00, 50));
+        assert!(engine.get_exposure_position(3).is_err());
+    }
+
+    #[test]
+    fn test_engine_recommendations_isolate_failures() {
+        use crate::hedging::ExposureConfig;
+        use crate::market_data::{Commodity, Instrument};
+
+        let mut registry = InstrumentRegistry::new();
+        for (id, name) in [(1, "DE Spot"), (2, "DE Base M1"), (3, "DE Base Q1")] {
+            registry
+                .register(Instrument::new(id, name, "EEX", Commodity::Power))
+                .unwrap();
+        }
+        let config = HedgeConfig {
+            exposures: vec![
+                ExposureConfig::new("DE month", 1, 2, -10_000.0, 1.0),
+                ExposureConfig::new("DE quarter", 1, 3, -30_000.0, 1.0),
+            ],
+            unhealthy_book_action: UnhealthyBookAction::Reject,
+            ..HedgeConfig::default()
+        };
+        let engine = HedgeEngine::with_instruments(config, registry).unwrap();
+
+        // The quarter book has no asks: rejected, the month still hedges
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 79.90, 50, 2));
+        engine.on_tick(MarketTick::ask(ts, 80.00, 50, 2));
+        engine.on_tick(MarketTick::bid(ts, 84.90, 50, 3));
+
+        let recs = engine.get_hedge_recommendations();
+        assert_eq!(recs.len(), 2);
+        assert!(matches!(recs[0], (0, Ok(ref rec)) if rec.symbol_id == 2));
+        assert!(matches!(
+            recs[1],
+            (1, Err(Error::UnhealthyBook { symbol_id: 3, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_engine_trade_and_status_events() {
+        let engine = HedgeEngine::new(HedgeConfig::simple(-10_000.0, 1.0)).unwrap();
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 49.90, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, 50.10, 100, 2));
+        engine.on_tick(MarketTick::trade(ts, 50.05, 20, Side::Ask, 2));
+        engine.on_tick(MarketTick::settlement(ts, 50.00, 2));
+
+        let futures = engine.futures_orderbook();
+        assert_eq!(futures.last_trade(), Some((50.05, 20)));
+        assert_eq!(futures.settlement_price(), Some(50.00));
+        // Trades do not move the quoted book
+        assert_eq!(futures.best_ask(), (50.10, 100));
+
+        engine.on_tick(MarketTick::halt(ts, 2));
+        assert!(futures.is_halted());
+        assert!(engine.get_hedge_recommendation().unwrap().is_none());
+
+        // Next quote resumes trading
+        engine.on_tick(MarketTick::ask(ts, 50.20, 100, 2));
+        assert!(!futures.is_halted());
+        assert!(engine.get_hedge_recommendation().unwrap().is_some());
+
+        engine.on_tick(MarketTick::book_clear(ts, 2));
+        assert_eq!(futures.depth(Side::Ask), 0);
+        assert_eq!(engine.get_metrics().ticks_processed(), 7);
+    }
+
+    #[test]
+    fn test_engine_trade_price_source() {
+        let config = HedgeConfig {
+            price_source: PriceSource::Trades,
+            enable_mean_reversion: true,
+            sampling: SamplingConfig::every_tick(),
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        for i in 0..40 {
+            let ts = get_timestamp_ns();
+            let shock = ((i * 7) % 11) as f64 * 0.2;
+
+            // Quotes alone must not feed statistics
+            engine.on_tick(MarketTick::bid(ts, 40.0, 100, 1));
+            engine.on_tick(MarketTick::ask(ts, 60.0, 100, 2));
+
+            engine.on_tick(MarketTick::trade(ts, 45.0 + shock, 10, Side::Bid, 1));
+            engine.on_tick(MarketTick::trade(ts, 50.0 + shock, 10, Side::Ask, 2));
+        }
+
+        let exposure = &engine.exposures[0];
+        let mr = exposure.mean_reversion.as_ref().unwrap();
+        assert_eq!(mr.get_statistics().observations, 40);
+
+        let mvhr = exposure.mvhr_strategy.as_ref().unwrap();
+        assert_eq!(mvhr.get_statistics().unwrap().observations, 40);
+        let ratio = mvhr.calculate_optimal_ratio().unwrap();
+        assert!(ratio > 0.5 && ratio < 1.5, "got {}", ratio);
+    }
+
+    #[test]
+    fn test_engine_stale_books() {
+        let engine = HedgeEngine::new(HedgeConfig::simple(-10_000.0, 1.0)).unwrap();
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 45.0, 100, 1).with_sequence(1, 1));
+        engine.on_tick(MarketTick::ask(ts, 50.0, 100, 2).with_sequence(1, 2));
+        assert!(engine.get_hedge_recommendation().unwrap().is_some());
+
+        let spot = engine.shared_orderbook(1).unwrap();
+        spot.set_stale(true);
+        assert!(engine.get_hedge_recommendation().unwrap().is_none());
+
+        spot.set_stale(false);
+        assert!(engine.get_hedge_recommendation().unwrap().is_some());
+        assert!(engine.shared_orderbook(9).is_none());
+    }
+
+    #[test]
+    fn test_engine_unhealthy_books() {
+        let config = HedgeConfig {
+            unhealthy_book_action: UnhealthyBookAction::Reject,
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        // No asks to buy the hedge from
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 45.0, 100, 1));
+        engine.on_tick(MarketTick::bid(ts, 50.0, 100, 2));
+        match engine.get_hedge_recommendation() {
+            Err(Error::UnhealthyBook { symbol_id, issue }) => {
+                assert_eq!(symbol_id, 2);
+                assert_eq!(issue, BookIssue::EmptySide(Side::Ask));
+            }
+            other => panic!("expected unhealthy book, got {:?}", other),
+        }
+
+        // Crossed hedge book, from depth updates (quotes uncross the book)
+        let ask = BookUpdate::New {
+            side: Side::Ask,
+            price: 490000,
+            size: 100,
+        };
+        engine.on_book_update(2, ask, ts);
+        assert!(matches!(
+            engine.get_hedge_recommendation(),
+            Err(Error::UnhealthyBook {
+                issue: BookIssue::Crossed { .. },
+                ..
+            })
+        ));
+
+        engine.on_tick(MarketTick::ask(ts, 50.1, 100, 2));
+        assert!(engine.get_hedge_recommendation().unwrap().is_some());
+
+        // Suppressed by default
+        let engine = HedgeEngine::new(HedgeConfig::simple(-10_000.0, 1.0)).unwrap();
+        engine.on_tick(MarketTick::bid(ts, 50.0, 100, 2));
+        assert!(engine.get_hedge_recommendation().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_engine_staleness_limit() {
+        let mut registry = InstrumentRegistry::default();
+        let mut futures = Instrument::new(3, "Stale Futures", "EEX", Commodity::Power);
+        futures.health.max_staleness_ms = Some(1_000);
+        registry.register(futures).unwrap();
+
+        let config = HedgeConfig {
+            futures_symbol_id: 3,
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::with_instruments(config, registry).unwrap();
+
+        // Quote from ten seconds ago
+        let ts = get_timestamp_ns() - 10_000_000_000;
+        engine.on_tick(MarketTick::ask(ts, 50.0, 100, 3));
+        assert!(engine.get_hedge_recommendation().unwrap().is_none());
+
+        engine.on_tick(MarketTick::ask(get_timestamp_ns(), 50.0, 100, 3));
+        assert!(engine.get_hedge_recommendation().unwrap().is_some());
+        assert_eq!(
+            engine.health_checker().limits(3).max_staleness_ms,
+            Some(1_000)
+        );
+    }
+
+    #[test]
+    fn test_engine_depth_aware_recommendation() {
+        let engine = HedgeEngine::new(HedgeConfig::simple(-400.0, 1.0)).unwrap();
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 45.0, 100, 1));
+        engine.on_book_update(
+            2,
+            BookUpdate::New {
+                side: Side::Bid,
+                price: 499000,
+                size: 100,
+            },
+            ts,
+        );
+        engine.on_book_update(
+            2,
+            BookUpdate::New {
+                side: Side::Ask,
+                price: 500000,
+                size: 120,
+            },
+            ts,
+        );
+        engine.on_book_update(
+            2,
+            BookUpdate::New {
+                side: Side::Ask,
+                price: 501000,
+                size: 200,
+            },
+            ts,
+        );
+
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert_eq!(rec.side, Side::Ask);
+        assert_eq!(rec.price, 50.0);
+
+        // 120 @ 50.0 + 200 @ 50.1 of the 400 needed
+        let vwap = (120.0 * 50.0 + 200.0 * 50.1) / 320.0;
+        assert!((rec.expected_price - vwap).abs() < 1e-9);
+        assert!((rec.liquidity_coverage - 0.8).abs() < 1e-12);
+
+        let slippage = (vwap - 49.95) / 49.95 * 10000.0;
+        assert!((rec.slippage_bps - slippage).abs() < 1e-9);
+        assert!(rec.slippage_bps > 0.0);
+    }
+
+    #[test]
+    fn test_engine_reference_price() {
+        let config = HedgeConfig {
+            reference_price: Some(ReferencePrice::Microprice),
+            enable_mean_reversion: true,
+            sampling: SamplingConfig::every_tick(),
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        let ts = get_timestamp_ns();
+        // One-sided books have no microprice and feed nothing
+        engine.on_tick(MarketTick::bid(ts, 45.0, 300, 1));
+        engine.on_tick(MarketTick::bid(ts, 50.0, 100, 2));
+
+        let exposure = &engine.exposures[0];
+        let mr = exposure.mean_reversion.as_ref().unwrap();
+        assert_eq!(mr.get_statistics().observations, 0);
+
+        engine.on_tick(MarketTick::ask(ts, 45.1, 100, 1));
+        engine.on_tick(MarketTick::ask(ts, 50.1, 100, 2));
+        for _ in 0..29 {
+            engine.on_tick(MarketTick::bid(ts, 45.0, 300, 1));
+            engine.on_tick(MarketTick::bid(ts, 50.0, 100, 2));
+        }
+
+        // Microprice of 45.0 x 300 / 45.1 x 100
+        let (mean, _) = mr.calculate_statistics().unwrap();
+        assert!((mean - 45.075).abs() < 1e-9);
+
+        // Scored at the microprice the history holds, not the mid
+        assert!((mr.last_price().unwrap() - 45.075).abs() < 1e-9);
+
+        let mvhr = exposure.mvhr_strategy.as_ref().unwrap();
+        assert_eq!(mvhr.get_statistics().unwrap().observations, 30);
+    }
+
+    #[test]
+    fn test_engine_journal() {
+        use crate::market_data::{TickJournalReader, TickJournalWriter};
+
+        let path =
+            std::env::temp_dir().join(format!("hedging-engine-journal-{}.bin", std::process::id()));
+
+        let mut engine = HedgeEngine::new(HedgeConfig::default()).unwrap();
+        engine
+            .set_journal(TickJournalWriter::create(&path).unwrap())
+            .unwrap();
+
+        engine.on_tick(MarketTick::bid(1_000, 45.0, 100, 1));
+        engine.on_received_tick(ReceivedTick::new(
+            MarketTick::trade(2_000, 50.1, 10, Side::Ask, 2),
+            2_750,
+        ));
+        engine.on_tick(MarketTick::halt(3_000, 99));
+        engine.flush_journal().unwrap();
+
+        let reader = TickJournalReader::open(&path).unwrap();
+        let ticks: Vec<ReceivedTick> = reader.iter().received().map(|t| t.unwrap()).collect();
+        assert_eq!(ticks.len(), 3);
+        assert!(ticks[1].tick.is_trade());
+        assert_eq!(ticks[1].receive_ns, 2_750);
+        assert_eq!(ticks[0].receive_ns, 0);
+        assert_eq!(ticks[2].tick.symbol_id, 99);
+
+        assert_eq!(engine.take_journal().unwrap().len(), 3);
+        engine.on_tick(MarketTick::bid(4_000, 45.0, 100, 1));
+        assert!(engine.flush_journal().is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_engine_deterministic_replay() {
+        use crate::utils::SimulatedClock;
+
+        let run = || {
+            let clock = SimulatedClock::shared(0);
+            let config = HedgeConfig {
+                enable_mvhr: true,
+                enable_mean_reversion: true,
+                sampling: SamplingConfig::every(1),
+                ..HedgeConfig::simple(-10_000.0, 1.0)
+            };
+            let engine =
+                HedgeEngine::with_clock(config, InstrumentRegistry::default(), clock).unwrap();
+
+            let mut recommendations = Vec::new();
+            for i in 0..40u64 {
+                let ts = 1_700_000_000_000_000_000 + i * 1_000_000_000;
+                let shock = ((i * 7) % 11) as f64 * 0.2;
+                engine.on_tick(MarketTick::bid(ts, 45.0 + shock, 100, 1));
+                engine.on_tick(MarketTick::ask(ts, 45.1 + shock, 100, 1));
+                engine.on_tick(MarketTick::bid(ts, 50.0 + shock, 100, 2));
+                engine.on_tick(MarketTick::ask(ts, 50.1 + shock, 100, 2));
+
+                engine.recalibrate();
+                recommendations.push(engine.get_hedge_recommendation().unwrap());
+            }
+            (engine.clock().now_ns(), recommendations)
+        };
+
+        let (now_a, recs_a) = run();
+        let (now_b, recs_b) = run();
+
+        assert_eq!(now_a, 1_700_000_039_000_000_000);
+        assert_eq!(now_a, now_b);
+        for (a, b) in recs_a.iter().zip(&recs_b) {
+            let (a, b) = (a.as_ref().unwrap(), b.as_ref().unwrap());
+            assert_eq!(a.timestamp_ns, b.timestamp_ns);
+            assert_eq!(a.quantity.to_bits(), b.quantity.to_bits());
+            assert_eq!(a.reason, b.reason);
+        }
+        assert_eq!(recs_a[39].as_ref().unwrap().timestamp_ns, now_a);
+    }
+
+    #[test]
+    fn test_engine_hourly_sampling() {
+        let config = HedgeConfig {
+            enable_mvhr: true,
+            enable_mean_reversion: true,
+            statistics_window_hours: 24,
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        // A quote every 10 seconds for 36 hours, with a weekend-style gap;
+        // asks rest above the bids so both books have a mid
+        let start = 1_699_999_200 * 1_000_000_000; // On the hour
+        engine.on_tick(MarketTick::ask(start, 47.0, 100, 1));
+        engine.on_tick(MarketTick::ask(start, 52.0, 100, 2));
+        for i in (0..36 * 360u64).filter(|i| !(12 * 360..20 * 360).contains(i)) {
+            let ts = start + i * 10_000_000_000;
+            let shock = ((i * 7) % 11) as f64 * 0.1;
+            engine.on_tick(MarketTick::bid(ts, 45.0 + shock, 100, 1));
+            engine.on_tick(MarketTick::bid(ts, 50.0 + shock, 100, 2));
+        }
+
+        // Hour 35 is still open; the window holds hours 11-34 minus the gap
+        let exposure = &engine.exposures[0];
+        let mvhr = exposure.mvhr_strategy.as_ref().unwrap();
+        assert_eq!(mvhr.get_statistics().unwrap().observations, 24 - 8);
+
+        let mr = exposure.mean_reversion.as_ref().unwrap();
+        assert_eq!(mr.get_statistics().observations, 24 - 8);
+    }
+
+    #[test]
+    fn test_engine_recalibration() {
+        use crate::utils::SimulatedClock;
+
+        let clock = SimulatedClock::shared(0);
+        let config = HedgeConfig {
+            enable_mvhr: true,
+            enable_mean_reversion: true,
+            sampling: SamplingConfig::every_tick(),
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine =
+            HedgeEngine::with_clock(config, InstrumentRegistry::default(), clock.clone()).unwrap();
+
+        let start = 1_700_000_000_000_000_000;
+        engine.on_tick(MarketTick::ask(start, 47.5, 100, 1));
+        engine.on_tick(MarketTick::ask(start, 53.0, 100, 2));
+        for i in 0..40u64 {
+            let ts = start + i * 1_000_000_000;
+            let shock = ((i * 7) % 11) as f64 * 0.2;
+            engine.on_tick(MarketTick::bid(ts, 45.0 + shock, 100, 1));
+            engine.on_tick(MarketTick::bid(ts, 50.0 + 1.2 * shock, 100, 2));
+        }
+
+        let mr = engine.exposures[0].mean_reversion.as_ref().unwrap();
+        assert_eq!(mr.calculate_z_score(60.0), 0.0);
+
+        // MVHR and mean reversion are both due
+        assert_eq!(engine.recalibrate(), 2);
+        assert!(mr.calculate_z_score(60.0) > 2.0);
+        let stats = engine.get_mean_reversion_statistics(0).unwrap().unwrap();
+        assert_eq!(stats.observations, 41); // Resting ask, then 40 bids
+        assert!(stats.mean_reverting);
+        let report = engine.get_stationarity(0).unwrap();
+        assert_eq!(
+            report.adf.unwrap().observations + 1 + report.adf.unwrap().lags,
+            41
+        );
+        assert!(report.kpss.is_some());
+        assert!(report.cointegration.is_some());
+        assert_ne!(
+            engine.exposures[0]
+                .mvhr_strategy
+                .as_ref()
+                .unwrap()
+                .get_hedge_ratio(),
+            1.0
+        );
+
+        // Mean reversion is due again after an hour, MVHR after a day
+        assert_eq!(engine.recalibrate(), 0);
+        clock.advance_by(3601 * 1_000_000_000);
+        assert_eq!(engine.recalibrate(), 1);
+
+        let metrics = engine.get_metrics();
+        assert_eq!(metrics.recalibrations(), 3);
+        assert_eq!(metrics.recalibration_failures(), 0);
+    }
+
+    #[test]
+    fn test_engine_recalibration_thread() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let config = HedgeConfig {
+            recalibration: crate::hedging::RecalibrationConfig {
+                poll_interval_ms: 1,
+                retry_interval_secs: 0,
+                ..Default::default()
+            },
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task_runs = runs.clone();
+        engine.schedule_recalibration("params", Duration::ZERO, move || {
+            match task_runs.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(Error::Calculation("not enough data".to_string())),
+                _ => Ok(()),
+            }
+        });
+
+        engine.start_recalibration().unwrap();
+        assert!(engine.is_recalibrating());
+        assert!(matches!(
+            engine.start_recalibration(),
+            Err(Error::InvalidState(_))
+        ));
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while runs.load(Ordering::SeqCst) < 3 && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        engine.stop_recalibration();
+        assert!(!engine.is_recalibrating());
+
+        // No more runs once stopped
+        let stopped_at = runs.load(Ordering::SeqCst);
+        assert!(stopped_at >= 3);
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+
+        let failures = engine.take_recalibration_failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].task, "params");
+        assert_eq!(engine.get_metrics().recalibration_failures(), 1);
+    }
+
+    #[test]
+    fn test_engine_kalman_ratio() {
+        let config = HedgeConfig {
+            ratio_estimator: crate::hedging::RatioEstimator::kalman(),
+            mvhr_change_mode: crate::hedging::PriceChangeMode::Absolute,
+            sampling: SamplingConfig::every_tick(),
+            ratio_acceptance: Some(RatioAcceptance::default()),
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+
+        // Spot moves 0.6x the futures
+        let start = get_timestamp_ns();
+        engine.on_tick(MarketTick::ask(start, 60.0, 100, 1));
+        engine.on_tick(MarketTick::bid(start, 40.0, 100, 2));
+        let mut futures_move = 0.0;
+        for i in 0..300u64 {
+            futures_move += ((i * 37) % 11) as f64 * 0.1 - 0.5;
+            engine.on_tick(MarketTick::bid(
+                start + i,
+                45.0 + 0.6 * futures_move,
+                100,
+                1,
+            ));
+            engine.on_tick(MarketTick::ask(start + i, 50.0 + futures_move, 100, 2));
+        }
+
+        // The filter tracks every change; the ratio is published at
+        // recalibration
+        let mvhr = engine.exposures[0].mvhr_strategy.as_ref().unwrap();
+        let kalman = mvhr.kalman_diagnostics().unwrap();
+        assert_eq!(mvhr.ratio_revision(), 0);
+        assert!(kalman.variance < 1.0);
+        assert!(kalman.observations > 200);
+
+        engine.recalibrate();
+        let revision = mvhr.ratio_revision();
+        assert_eq!(revision, 1);
+        assert!((mvhr.get_hedge_ratio() - 0.6).abs() < 0.05);
+
+        // Acceptance margin from the filter's posterior variance
+        let diagnostics = mvhr.ratio_diagnostics(0.95, 3).unwrap();
+        let kalman = mvhr.kalman_diagnostics().unwrap();
+        assert_eq!(diagnostics.ratio, kalman.ratio);
+        assert!((diagnostics.standard_error - kalman.variance.sqrt()).abs() < 1e-12);
+
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert!(rec.reason.contains("[Kalman ratio: "), "{}", rec.reason);
+
+        // More observations leave the published estimate, and its checked
+        // choice, in place until the next recalibration
+        engine.on_tick(MarketTick::ask(start + 400, 50.5, 100, 2));
+        assert_eq!(mvhr.ratio_revision(), revision);
+        engine.get_hedge_recommendation().unwrap();
+        let cached = *engine.exposures[0].ratio_choice.lock();
+        assert_eq!(cached.map(|(revision, _)| revision), Some(revision));
+    }
+
+    #[test]
+    fn test_engine_cross_hedge() {
+        use crate::hedging::CrossHedgeConfig;
+        use crate::market_data::{Commodity, Instrument};
+        use crate::utils::SimulatedClock;
+
+        let mut registry = InstrumentRegistry::new();
+        for (id, name) in [(1, "NL Spot"), (2, "DE Base M1"), (3, "FR Base M1")] {
+            registry
+                .register(Instrument::new(id, name, "EEX", Commodity::Power))
+                .unwrap();
+        }
+
+        let clock = SimulatedClock::shared(0);
+        let config = HedgeConfig {
+            enable_mvhr: false,
+            mvhr_change_mode: crate::hedging::PriceChangeMode::Absolute,
+            sampling: SamplingConfig::every(60),
+            exposures: vec![ExposureConfig::new("NL month", 1, 2, -1_000.0, 1.0)],
+            cross_hedges: vec![CrossHedgeConfig::new("NL cross", 1, &[2, 3], -10_000.0)],
+            ..HedgeConfig::default()
+        };
+        let engine = HedgeEngine::with_clock(config, registry, clock).unwrap();
+        assert_eq!(engine.cross_hedge_count(), 1);
+        assert_eq!(engine.cross_hedge_id("NL cross"), Some(0));
+
+        // Spot moves 0.5x DE plus 0.3x FR
+        let start = 1_700_000_000_000_000_000;
+        engine.on_tick(MarketTick::bid(start, 50.0, 100, 2));
+        engine.on_tick(MarketTick::bid(start, 50.0, 100, 3));
+        engine.on_tick(MarketTick::ask(start, 90.0, 100, 1));
+        let (mut de, mut fr) = (0.0, 0.0);
+        for i in 0..100u64 {
+            de += ((i * 37) % 11) as f64 * 0.1 - 0.5;
+            fr += ((i * 13) % 7) as f64 * 0.1 - 0.3;
+            let ts = start + i * 60_000_000_000;
+            engine.on_tick(MarketTick::ask(ts, 80.0 + de, 100, 2));
+            engine.on_tick(MarketTick::ask(ts, 75.0 + fr, 100, 3));
+            engine.on_tick(MarketTick::bid(ts, 60.0 + 0.5 * de + 0.3 * fr, 100, 1));
+        }
+
+        // No hedge before the first recalibration
+        assert!(
+            engine
+                .get_cross_hedge_recommendations(0)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(engine.get_cross_hedge_statistics(0).unwrap().is_none());
+        assert_eq!(engine.recalibrate(), 1);
+
+        let stats = engine.get_cross_hedge_statistics(0).unwrap().unwrap();
+        assert!((stats.ratios[0] - 0.5).abs() < 0.01, "{:?}", stats.ratios);
+        assert!((stats.ratios[1] - 0.3).abs() < 0.01, "{:?}", stats.ratios);
+        assert!(stats.r_squared > 0.99);
+
+        let recs = engine.get_cross_hedge_recommendations(0).unwrap();
+        assert_eq!(recs.len(), 2);
+        assert!(recs.iter().all(|r| r.cross_hedge_id == Some(0)));
+        assert_eq!((recs[0].leg, recs[0].symbol_id), (Some(0), 2));
+        assert_eq!((recs[1].leg, recs[1].symbol_id), (Some(1), 3));
+        assert!((recs[0].quantity - 5_000.0).abs() < 100.0);
+        assert!((recs[1].quantity - 3_000.0).abs() < 100.0);
+        assert!(recs.iter().all(|rec| rec.side == Side::Ask));
+        assert!(recs[0].reason.contains("[cross hedge leg 0: ratio "));
+
+        // Legs are booked on the cross hedge, not on exposure 0
+        for rec in &recs {
+            engine.execute_hedge(rec).unwrap();
+        }
+        let positions = engine.get_cross_hedge_positions(0).unwrap();
+        assert_eq!(positions, vec![recs[0].quantity, recs[1].quantity]);
+        assert_eq!(engine.get_exposure_hedge_position(0).unwrap(), 0.0);
+        assert!(
+            engine
+                .get_cross_hedge_recommendations(0)
+                .unwrap()
+                .is_empty()
+        );
+
+        engine.update_cross_hedge_position(0, -20_000.0).unwrap();
+        assert_eq!(engine.get_cross_hedge_recommendations(0).unwrap().len(), 2);
+        assert!(engine.get_cross_hedge_positions(1).is_err());
+    }
+
+    #[test]
+    fn test_cross_hedge_config_validation() {
+        use crate::hedging::CrossHedgeConfig;
+
+        assert!(
+            CrossHedgeConfig::new("ok", 1, &[2, 3], 0.0)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            CrossHedgeConfig::new("none", 1, &[], 0.0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            CrossHedgeConfig::new("dup", 1, &[2, 2], 0.0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            CrossHedgeConfig::new("self", 1, &[1, 2], 0.0)
+                .validate()
+                .is_err()
+        );
+
+        // Every leg must be registered
+        let config = HedgeConfig {
+            cross_hedges: vec![CrossHedgeConfig::new("missing", 1, &[2, 9], 0.0)],
+            ..HedgeConfig::default()
+        };
+        assert!(matches!(HedgeEngine::new(config), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_engine_constrained_hedge() {
+        use crate::hedging::{Binding, HedgeConstraints};
+        use crate::market_data::{Commodity, Instrument};
+
+        let mut registry = InstrumentRegistry::new();
+        registry
+            .register(Instrument::new(1, "DE Spot", "EPEX", Commodity::Power))
+            .unwrap();
+        let mut month = Instrument::new(2, "DE Base M1", "EEX", Commodity::Power);
+        month.contract_size = 744.0;
+        registry.register(month).unwrap();
+
+        let config = HedgeConfig {
+            enable_mvhr: false,
+            constraints: HedgeConstraints::corridor(0.6, 1.1).with_whole_lots(),
+            ..HedgeConfig::simple(-10_000.0, 1.25)
+        };
+        let engine = HedgeEngine::with_instruments(config.clone(), registry.clone()).unwrap();
+
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 45.00, 100, 1));
+        engine.on_tick(MarketTick::bid(ts, 49.95, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, 50.05, 100, 2));
+
+        // 1.25 capped at 1.1 = 14.78 lots, rounded down to 14
+        let allocation = engine.get_exposure_allocation(0).unwrap();
+        assert_eq!(allocation.legs[0].binding, Binding::MaxRatio);
+        assert!((allocation.legs[0].lots - 14.0).abs() < 1e-9);
+
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert_eq!(rec.lots, 14.0);
+        assert_eq!(rec.quantity, 14.0 * 744.0);
+        assert!(rec.reason.contains("(max ratio)"), "{}", rec.reason);
+
+        // A 400k notional cap at a 50 mid binds before the corridor
+        let config = HedgeConfig {
+            constraints: HedgeConstraints::corridor(0.6, 1.1).with_max_notional(2, 400_000.0),
+            ..config
+        };
+        let engine = HedgeEngine::with_instruments(config, registry).unwrap();
+        engine.on_tick(MarketTick::bid(ts, 49.95, 100, 2));
+        engine.on_tick(MarketTick::ask(ts, 50.05, 100, 2));
+
+        let allocation = engine.get_exposure_allocation(0).unwrap();
+        assert_eq!(allocation.legs[0].binding, Binding::MaxNotional);
+        assert!((allocation.legs[0].ratio - 0.8).abs() < 1e-9);
+        assert!((allocation.legs[0].notional - 400_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_engine_constrained_one_sided_hedge_book() {
+        use crate::hedging::HedgeConstraints;
+
+        let config = HedgeConfig {
+            enable_mvhr: false,
+            constraints: HedgeConstraints::corridor(0.6, 1.1).with_max_notional(2, 400_000.0),
+            ..HedgeConfig::simple(-10_000.0, 1.25)
+        };
+        let engine = HedgeEngine::new(config.clone()).unwrap();
+
+        // Only an ask on the hedge book: no mid to measure notional at
+        let ts = get_timestamp_ns();
+        engine.on_tick(MarketTick::bid(ts, 45.00, 100, 1));
+        engine.on_tick(MarketTick::ask(ts, 50.05, 100, 2));
+
+        assert!(engine.get_hedge_recommendation().unwrap().is_none());
+        assert!(matches!(
+            engine.get_exposure_allocation(0),
+            Err(crate::Error::UnhealthyBook {
+                symbol_id: 2,
+                issue: BookIssue::EmptySide(Side::Bid)
+            })
+        ));
+
+        let config = HedgeConfig {
+            unhealthy_book_action: UnhealthyBookAction::Reject,
+            ..config
+        };
+        let engine = HedgeEngine::new(config).unwrap();
+        engine.on_tick(MarketTick::bid(ts, 45.00, 100, 1));
+        engine.on_tick(MarketTick::ask(ts, 50.05, 100, 2));
+        assert!(matches!(
+            engine.get_hedge_recommendation(),
+            Err(crate::Error::UnhealthyBook { symbol_id: 2, .. })
+        ));
+
+        // Once two-sided, the cap applies at the 50 mid
+        engine.on_tick(MarketTick::bid(ts, 49.95, 100, 2));
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert!((rec.quantity - 8_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_engine_ratio_acceptance() {
+        use crate::hedging::{RatioAcceptance, RatioDecision};
+        use crate::utils::{Clock, SimulatedClock};
+
+        let feed = |engine: &HedgeEngine, start: u64, count: u64, noise: f64| {
+            let (mut spot, mut futures) = (45.0, 50.0);
+            for i in 0..count {
+                let df = ((i * 37) % 11) as f64 * 0.1 - 0.5;
+                futures += df;
+                spot += 0.7 * df + (((i * 13) % 7) as f64 - 3.0) * noise;
+                // MVHR samples on futures ticks, so the spot quote goes first
+                let ts = start + i * 1_000_000_000;
+                engine.on_tick(MarketTick::bid(ts, spot - 0.05, 100, 1));
+                engine.on_tick(MarketTick::ask(ts, spot + 0.05, 100, 1));
+                engine.on_tick(MarketTick::bid(ts, futures - 0.05, 100, 2));
+                engine.on_tick(MarketTick::ask(ts, futures + 0.05, 100, 2));
+            }
+        };
+
+        let clock = SimulatedClock::shared(0);
+        let config = HedgeConfig {
+            mvhr_change_mode: crate::hedging::PriceChangeMode::Absolute,
+            sampling: SamplingConfig::every(1),
+            ratio_acceptance: Some(RatioAcceptance::default()),
+            ..HedgeConfig::simple(-10_000.0, 1.0)
+        };
+        let engine =
+            HedgeEngine::with_clock(config.clone(), InstrumentRegistry::default(), clock.clone())
+                .unwrap();
+
+        // Far outside the interval of the first estimate: accepted
+        let start = 1_700_000_000_000_000_000;
+        feed(&engine, start, 200, 0.02);
+        assert_eq!(engine.recalibrate(), 1);
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert!(!rec.reason.contains("not applied"), "{}", rec.reason);
+        let accepted = engine.exposures[0].accepted_ratio.load(Ordering::Acquire);
+        assert!((f64::from_bits(accepted) - 0.7).abs() < 0.01);
+
+        // The accepted estimate is not re-checked against itself, and the
+        // checks are not rerun until the next estimate
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert!(!rec.reason.contains("not applied"), "{}", rec.reason);
+        let mvhr = engine.exposures[0].mvhr_strategy.as_ref().unwrap();
+        let (revision, choice) = engine.exposures[0].ratio_choice.lock().unwrap();
+        assert_eq!(revision, mvhr.ratio_revision());
+        assert_eq!(choice.decision, Some(RatioDecision::Accepted));
+
+        let diagnostics = engine.get_ratio_diagnostics(0).unwrap().unwrap();
+        let (low, high) = diagnostics.confidence_interval;
+        assert!(low < 0.7 && 0.7 < high);
+
+        // A day later the estimate moves within its own noise: kept
+        clock.advance_by(86_401 * 1_000_000_000);
+        feed(&engine, clock.now_ns(), 50, 0.05);
+        assert_eq!(engine.recalibrate(), 1);
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert!(
+            rec.reason.contains(&format!(
+                "not applied: {}",
+                RatioDecision::Indistinguishable
+            )),
+            "{}",
+            rec.reason
+        );
+        assert_eq!(
+            engine.exposures[0].accepted_ratio.load(Ordering::Acquire),
+            accepted
+        );
+
+        // Too noisy for a strict limit: the configured ratio stays
+        let config = HedgeConfig {
+            ratio_acceptance: Some(RatioAcceptance {
+                max_standard_error: Some(1e-6),
+                ..Default::default()
+            }),
+            ..config
+        };
+        let engine = HedgeEngine::with_clock(
+            config,
+            InstrumentRegistry::default(),
+            SimulatedClock::shared(0),
+        )
+        .unwrap();
+        feed(&engine, start, 200, 0.02);
+        engine.recalibrate();
+        let rec = engine.get_hedge_recommendation().unwrap().unwrap();
+        assert!(rec.reason.contains("[MVHR ratio: 1.000]"), "{}", rec.reason);
+        assert!(
+            rec.reason.contains("not applied: too noisy"),
+            "{}",
+            rec.reason
+        );
+    }
 }
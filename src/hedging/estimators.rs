@@ -0,0 +1,428 @@
+//! Covariance estimators for dynamic hedge ratios
+
+use crate::hedging::KalmanConfig;
+use serde::{Deserialize, Serialize};
+
+/// Estimator of the spot/futures change covariance behind the MVHR ratio
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RatioEstimator {
+    /// Equal-weighted sample covariance over the window (static OLS ratio)
+    #[default]
+    Ols,
+
+    /// Exponentially weighted covariance (RiskMetrics)
+    /// `lambda` = weight decay per observation, e.g. 0.94
+    Ewma { lambda: f64 },
+
+    /// Bivariate GARCH(1,1) with dynamic conditional correlation (DCC),
+    /// refitted over the window at every recalculation
+    GarchDcc,
+
+    /// Ratio as a latent random walk, filtered on every change
+    Kalman(KalmanConfig),
+}
+
+impl RatioEstimator {
+    /// RiskMetrics EWMA (λ = 0.94)
+    pub fn ewma() -> Self {
+        Self::Ewma { lambda: 0.94 }
+    }
+
+    /// Kalman filter with default noise, observation noise estimated online
+    pub fn kalman() -> Self {
+        Self::Kalman(KalmanConfig::default())
+    }
+
+    /// Validate estimator parameters
+    pub fn validate(&self) -> crate::Result<()> {
+        match *self {
+            Self::Kalman(config) => config.validate(),
+            Self::Ewma { lambda } if !(lambda > 0.0 && lambda < 1.0) => Err(crate::Error::Config(
+                "EWMA lambda must be between 0 and 1".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One-step covariance estimate of (spot change, futures change)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CovarianceForecast {
+    /// Variance of spot changes
+    pub variance_x: f64,
+
+    /// Variance of futures changes
+    pub variance_y: f64,
+
+    /// Covariance of spot and futures changes
+    pub covariance: f64,
+}
+
+impl CovarianceForecast {
+    /// Correlation (0 if either variance is zero)
+    pub fn correlation(&self) -> f64 {
+        if self.variance_x > 0.0 && self.variance_y > 0.0 {
+            self.covariance / (self.variance_x.sqrt() * self.variance_y.sqrt())
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Exponentially weighted (co)variance of a paired series
+///
+/// Zero-mean RiskMetrics recursion, O(1) per observation:
+/// σ²_t = λ σ²_{t-1} + (1 - λ) r²_t, seeded with the first observation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EwmaCovariance {
+    /// Decay per observation
+    lambda: f64,
+
+    /// Number of observations
+    n: u64,
+
+    /// Weighted variance of x
+    var_x: f64,
+
+    /// Weighted variance of y
+    var_y: f64,
+
+    /// Weighted covariance of x and y
+    cov: f64,
+}
+
+impl EwmaCovariance {
+    /// Create an empty estimator with decay `lambda`
+    pub fn new(lambda: f64) -> Self {
+        Self {
+            lambda,
+            n: 0,
+            var_x: 0.0,
+            var_y: 0.0,
+            cov: 0.0,
+        }
+    }
+
+    /// Add an observation
+    #[inline(always)]
+    pub fn update(&mut self, x: f64, y: f64) {
+        let weight = if self.n == 0 { 1.0 } else { 1.0 - self.lambda };
+        self.var_x += weight * (x * x - self.var_x);
+        self.var_y += weight * (y * y - self.var_y);
+        self.cov += weight * (x * y - self.cov);
+        self.n += 1;
+    }
+
+    /// Number of observations
+    pub fn len(&self) -> usize {
+        self.n as usize
+    }
+
+    /// Check whether no observation was added
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Current estimate (None below 2 observations)
+    pub fn forecast(&self) -> Option<CovarianceForecast> {
+        (self.n >= 2).then_some(CovarianceForecast {
+            variance_x: self.var_x,
+            variance_y: self.var_y,
+            covariance: self.cov,
+        })
+    }
+}
+
+/// Univariate GARCH(1,1): σ²_t = ω + α ε²_{t-1} + β σ²_{t-1}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Garch11 {
+    pub omega: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Garch11 {
+    /// Fit to zero-mean residuals by Gaussian quasi-maximum likelihood
+    ///
+    /// ω is set by variance targeting, so the unconditional variance
+    /// equals the sample variance. None for a constant series.
+    pub fn fit(residuals: &[f64]) -> Option<Self> {
+        let variance = sample_variance(residuals)?;
+        let model = |alpha: f64, beta: f64| Self {
+            omega: variance * (1.0 - alpha - beta),
+            alpha,
+            beta,
+        };
+
+        let (alpha, beta) = maximize_persistence(|a, b| model(a, b).log_likelihood(residuals));
+        Some(model(alpha, beta))
+    }
+
+    /// Unconditional variance ω / (1 - α - β)
+    pub fn long_run_variance(&self) -> f64 {
+        self.omega / (1.0 - self.persistence())
+    }
+
+    /// Volatility persistence α + β
+    pub fn persistence(&self) -> f64 {
+        self.alpha + self.beta
+    }
+
+    /// Conditional variances σ²_0..σ²_n; the last one is the forecast
+    ///
+    /// The recursion starts at the unconditional variance.
+    pub fn variances(&self, residuals: &[f64]) -> Vec<f64> {
+        let mut variances = Vec::with_capacity(residuals.len() + 1);
+        let mut variance = self.long_run_variance();
+        variances.push(variance);
+        for &e in residuals {
+            variance = self.omega + self.alpha * e * e + self.beta * variance;
+            variances.push(variance);
+        }
+        variances
+    }
+
+    /// Gaussian log-likelihood (constants dropped)
+    pub fn log_likelihood(&self, residuals: &[f64]) -> f64 {
+        let mut variance = self.long_run_variance();
+        let mut ll = 0.0;
+        for &e in residuals {
+            ll -= 0.5 * (variance.ln() + e * e / variance);
+            variance = self.omega + self.alpha * e * e + self.beta * variance;
+        }
+        ll
+    }
+}
+
+/// Bivariate GARCH(1,1)-DCC fit of (spot change, futures change)
+///
+/// Each series gets a GARCH(1,1) variance; correlation follows
+/// Q_t = (1 - a - b) Q̄ + a z_{t-1} z'_{t-1} + b Q_{t-1} on the
+/// standardized residuals z (Engle, 2002). Fitted in two steps by
+/// quasi-maximum likelihood.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DccGarch {
+    /// Spot change variance model
+    pub spot: Garch11,
+
+    /// Futures change variance model
+    pub futures: Garch11,
+
+    /// Correlation news parameter
+    pub a: f64,
+
+    /// Correlation persistence parameter
+    pub b: f64,
+
+    /// One-step-ahead covariance
+    forecast: CovarianceForecast,
+}
+
+impl DccGarch {
+    /// Changes required for a fit
+    pub const MIN_OBSERVATIONS: usize = 50;
+
+    /// Fit to (spot change, futures change) pairs, oldest first
+    ///
+    /// O(n) per likelihood evaluation, a few hundred evaluations per
+    /// step: cold path only. None below `MIN_OBSERVATIONS` changes or
+    /// for a constant series.
+    pub fn fit(changes: &[(f64, f64)]) -> Option<Self> {
+        if changes.len() < Self::MIN_OBSERVATIONS {
+            return None;
+        }
+
+        let n = changes.len() as f64;
+        let mean_x = changes.iter().map(|c| c.0).sum::<f64>() / n;
+        let mean_y = changes.iter().map(|c| c.1).sum::<f64>() / n;
+        let ex: Vec<f64> = changes.iter().map(|c| c.0 - mean_x).collect();
+        let ey: Vec<f64> = changes.iter().map(|c| c.1 - mean_y).collect();
+
+        let spot = Garch11::fit(&ex)?;
+        let futures = Garch11::fit(&ey)?;
+        let var_x = spot.variances(&ex);
+        let var_y = futures.variances(&ey);
+
+        // Standardized residuals
+        let z: Vec<(f64, f64)> = (0..changes.len())
+            .map(|t| (ex[t] / var_x[t].sqrt(), ey[t] / var_y[t].sqrt()))
+            .collect();
+        let q_bar = [
+            z.iter().map(|z| z.0 * z.0).sum::<f64>() / n,
+            z.iter().map(|z| z.1 * z.1).sum::<f64>() / n,
+            z.iter().map(|z| z.0 * z.1).sum::<f64>() / n,
+        ];
+
+        let (a, b) = maximize_persistence(|a, b| dcc_pass(&z, q_bar, a, b).0);
+        let rho = dcc_pass(&z, q_bar, a, b).1;
+
+        let (sx, sy) = (var_x[changes.len()], var_y[changes.len()]);
+        Some(Self {
+            spot,
+            futures,
+            a,
+            b,
+            forecast: CovarianceForecast {
+                variance_x: sx,
+                variance_y: sy,
+                covariance: rho * (sx * sy).sqrt(),
+            },
+        })
+    }
+
+    /// One-step-ahead covariance of spot and futures changes
+    pub fn forecast(&self) -> CovarianceForecast {
+        self.forecast
+    }
+}
+
+/// DCC correlation log-likelihood and the one-step-ahead correlation
+fn dcc_pass(z: &[(f64, f64)], q_bar: [f64; 3], a: f64, b: f64) -> (f64, f64) {
+    let c = 1.0 - a - b;
+    let mut q = q_bar;
+    let mut ll = 0.0;
+
+    for &(z1, z2) in z {
+        let rho = (q[2] / (q[0] * q[1]).sqrt()).clamp(-0.9999, 0.9999);
+        let det = 1.0 - rho * rho;
+        ll -= 0.5 * (det.ln() + (z1 * z1 + z2 * z2 - 2.0 * rho * z1 * z2) / det);
+
+        q = [
+            c * q_bar[0] + a * z1 * z1 + b * q[0],
+            c * q_bar[1] + a * z2 * z2 + b * q[1],
+            c * q_bar[2] + a * z1 * z2 + b * q[2],
+        ];
+    }
+
+    let rho = (q[2] / (q[0] * q[1]).sqrt()).clamp(-0.9999, 0.9999);
+    (ll, rho)
+}
+
+fn sample_variance(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (variance > 1e-20).then_some(variance)
+}
+
+/// Maximize `f(a, b)` over a, b ≥ 0, a + b < 1 by successive grid refinement
+fn maximize_persistence(f: impl Fn(f64, f64) -> f64) -> (f64, f64) {
+    const STEPS: usize = 12;
+    const ROUNDS: usize = 5;
+    const MAX_PERSISTENCE: f64 = 0.999;
+
+    let (mut a_range, mut b_range) = ((0.0, 0.5), (0.0, MAX_PERSISTENCE));
+    let mut best = (0.0, 0.0, f(0.0, 0.0));
+
+    for _ in 0..ROUNDS {
+        let a_step = (a_range.1 - a_range.0) / STEPS as f64;
+        let b_step = (b_range.1 - b_range.0) / STEPS as f64;
+
+        for i in 0..=STEPS {
+            for j in 0..=STEPS {
+                let (a, b) = (a_range.0 + i as f64 * a_step, b_range.0 + j as f64 * b_step);
+                if a + b >= MAX_PERSISTENCE {
+                    continue;
+                }
+                let value = f(a, b);
+                if value.is_finite() && value > best.2 {
+                    best = (a, b, value);
+                }
+            }
+        }
+
+        a_range = ((best.0 - a_step).max(0.0), best.0 + a_step);
+        b_range = ((best.1 - b_step).max(0.0), best.1 + b_step);
+    }
+
+    (best.0, best.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic standard normal draws (LCG + Box-Muller)
+    struct Normal(u64);
+
+    impl Normal {
+        fn uniform(&mut self) -> f64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        }
+
+        fn sample(&mut self) -> f64 {
+            let (u1, u2) = (self.uniform(), self.uniform());
+            (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+        }
+    }
+
+    #[test]
+    fn test_ewma_recursion() {
+        let mut ewma = EwmaCovariance::new(0.9);
+        assert!(ewma.forecast().is_none());
+
+        ewma.update(1.0, 2.0);
+        ewma.update(3.0, -1.0);
+        let forecast = ewma.forecast().unwrap();
+        assert!((forecast.variance_x - (0.9 * 1.0 + 0.1 * 9.0)).abs() < 1e-12);
+        assert!((forecast.variance_y - (0.9 * 4.0 + 0.1 * 1.0)).abs() < 1e-12);
+        assert!((forecast.covariance - (0.9 * 2.0 + 0.1 * -3.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_garch_recovers_parameters() {
+        let (alpha, beta) = (0.10, 0.85);
+        let mut normal = Normal(7);
+        let mut variance: f64 = 1.0;
+        let residuals: Vec<f64> = (0..4000)
+            .map(|_| {
+                let e = variance.sqrt() * normal.sample();
+                variance = 0.05 + alpha * e * e + beta * variance;
+                e
+            })
+            .collect();
+
+        let fit = Garch11::fit(&residuals).unwrap();
+        assert!((fit.alpha - alpha).abs() < 0.05, "alpha {}", fit.alpha);
+        assert!((fit.beta - beta).abs() < 0.08, "beta {}", fit.beta);
+        assert!(fit.persistence() < 1.0);
+    }
+
+    #[test]
+    fn test_dcc_forecast() {
+        // Futures with clustered volatility, spot = 0.8 * futures + noise
+        let mut normal = Normal(11);
+        let mut variance: f64 = 1e-4;
+        let changes: Vec<(f64, f64)> = (0..1500)
+            .map(|_| {
+                let f = variance.sqrt() * normal.sample();
+                variance = 5e-6 + 0.08 * f * f + 0.87 * variance;
+                (0.8 * f + 0.004 * normal.sample(), f)
+            })
+            .collect();
+
+        let fit = DccGarch::fit(&changes).unwrap();
+        let forecast = fit.forecast();
+        assert!(forecast.variance_y > 0.0);
+        assert!(fit.a + fit.b < 1.0);
+
+        let ratio = forecast.covariance / forecast.variance_y;
+        assert!((ratio - 0.8).abs() < 0.15, "ratio {}", ratio);
+
+        assert!(DccGarch::fit(&changes[..10]).is_none());
+    }
+
+    #[test]
+    fn test_estimator_validation() {
+        assert!(RatioEstimator::ewma().validate().is_ok());
+        assert!(RatioEstimator::Ewma { lambda: 1.0 }.validate().is_err());
+        assert!(RatioEstimator::GarchDcc.validate().is_ok());
+    }
+}
@@ -0,0 +1,304 @@
+//! Kalman filter estimate of a time-varying hedge ratio
+
+use serde::{Deserialize, Serialize};
+
+/// Kalman hedge ratio parameters
+///
+/// State: β_t = β_{t-1} + η_t, η ~ N(0, Q)
+/// Observation: ΔS_t = β_t ΔF_t + ε_t, ε ~ N(0, R)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KalmanConfig {
+    /// Q: variance of the ratio's random walk per observation
+    /// Larger = faster, noisier adaptation
+    #[serde(default = "default_process_noise")]
+    pub process_noise: f64,
+
+    /// R: variance of the observation error, in squared change units
+    /// None = estimated online from the innovations
+    #[serde(default)]
+    pub observation_noise: Option<f64>,
+
+    /// Prior ratio
+    #[serde(default = "default_initial_ratio")]
+    pub initial_ratio: f64,
+
+    /// Prior ratio variance
+    #[serde(default = "default_initial_variance")]
+    pub initial_variance: f64,
+}
+
+fn default_process_noise() -> f64 {
+    1e-4
+}
+
+fn default_initial_ratio() -> f64 {
+    1.0
+}
+
+fn default_initial_variance() -> f64 {
+    1.0
+}
+
+impl Default for KalmanConfig {
+    fn default() -> Self {
+        Self {
+            process_noise: default_process_noise(),
+            observation_noise: None,
+            initial_ratio: default_initial_ratio(),
+            initial_variance: default_initial_variance(),
+        }
+    }
+}
+
+impl KalmanConfig {
+    /// Validate filter parameters
+    pub fn validate(&self) -> crate::Result<()> {
+        let valid = self.process_noise >= 0.0 && self.initial_variance > 0.0;
+        if !valid {
+            return Err(crate::Error::Config(
+                "Kalman process noise must be non-negative and initial variance positive"
+                    .to_string(),
+            ));
+        }
+
+        if self
+            .observation_noise
+            .is_some_and(|r| r.is_nan() || r <= 0.0)
+        {
+            return Err(crate::Error::Config(
+                "Kalman observation noise must be positive".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Filter state and innovation diagnostics after the last update
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KalmanDiagnostics {
+    /// Posterior ratio estimate
+    pub ratio: f64,
+
+    /// Posterior ratio variance
+    pub variance: f64,
+
+    /// Last innovation ΔS - β⁻ ΔF
+    pub innovation: f64,
+
+    /// Predicted variance of the last innovation
+    pub innovation_variance: f64,
+
+    /// Innovation / √(innovation variance), ~N(0, 1) if well specified
+    pub standardized_innovation: f64,
+
+    /// Mean normalized innovation squared, ~1 if well specified
+    pub mean_nis: f64,
+
+    /// Observations filtered
+    pub observations: usize,
+}
+
+/// Kalman filter tracking the spot/futures ratio as a random walk
+///
+/// O(1) per observation, no allocation.
+#[derive(Debug, Clone, Copy)]
+pub struct KalmanRatio {
+    /// Filter parameters
+    config: KalmanConfig,
+
+    /// Posterior ratio
+    ratio: f64,
+
+    /// Posterior ratio variance
+    variance: f64,
+
+    /// Smoothed squared innovation (online R estimate)
+    innovation_ewma: f64,
+
+    /// Sum of normalized innovations squared
+    nis_sum: f64,
+
+    /// Last innovation and its predicted variance
+    last: (f64, f64),
+
+    /// Observations filtered
+    n: u64,
+}
+
+impl KalmanRatio {
+    /// Decay of the online observation noise estimate
+    const NOISE_DECAY: f64 = 0.98;
+
+    /// Create a filter at the configured prior
+    pub fn new(config: KalmanConfig) -> Self {
+        Self {
+            config,
+            ratio: config.initial_ratio,
+            variance: config.initial_variance,
+            innovation_ewma: 0.0,
+            nis_sum: 0.0,
+            last: (0.0, 0.0),
+            n: 0,
+        }
+    }
+
+    /// Filter one (spot change, futures change) observation
+    #[inline]
+    pub fn update(&mut self, spot_change: f64, futures_change: f64) {
+        let prior_variance = self.variance + self.config.process_noise;
+        let innovation = spot_change - self.ratio * futures_change;
+        let explained = futures_change * futures_change * prior_variance;
+
+        let noise = match self.config.observation_noise {
+            Some(noise) => noise,
+            None => {
+                let squared = innovation * innovation;
+                self.innovation_ewma = match self.n {
+                    0 => squared,
+                    _ => {
+                        Self::NOISE_DECAY * self.innovation_ewma
+                            + (1.0 - Self::NOISE_DECAY) * squared
+                    }
+                };
+                // Keep R positive when the prior explains all innovation
+                (self.innovation_ewma - explained).max(0.01 * self.innovation_ewma)
+            }
+        };
+
+        let innovation_variance = explained + noise;
+        self.n += 1;
+        self.last = (innovation, innovation_variance);
+        if innovation_variance <= 0.0 {
+            // Zero changes and no noise: nothing to learn
+            self.variance = prior_variance;
+            return;
+        }
+
+        let gain = prior_variance * futures_change / innovation_variance;
+        self.ratio += gain * innovation;
+        self.variance = (1.0 - gain * futures_change) * prior_variance;
+        self.nis_sum += innovation * innovation / innovation_variance;
+    }
+
+    /// Posterior ratio estimate
+    #[inline(always)]
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Posterior ratio variance
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+
+    /// Observations filtered
+    pub fn len(&self) -> usize {
+        self.n as usize
+    }
+
+    /// Check whether no observation was filtered
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// State and innovation diagnostics
+    pub fn diagnostics(&self) -> KalmanDiagnostics {
+        let (innovation, innovation_variance) = self.last;
+        KalmanDiagnostics {
+            ratio: self.ratio,
+            variance: self.variance,
+            innovation,
+            innovation_variance,
+            standardized_innovation: if innovation_variance > 0.0 {
+                innovation / innovation_variance.sqrt()
+            } else {
+                0.0
+            },
+            mean_nis: match self.n {
+                0 => 0.0,
+                n => self.nis_sum / n as f64,
+            },
+            observations: self.n as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kalman_converges_and_tracks() {
+        let config = KalmanConfig {
+            process_noise: 1e-4,
+            observation_noise: Some(0.01),
+            ..Default::default()
+        };
+        let mut kalman = KalmanRatio::new(config);
+
+        for i in 0..500u64 {
+            let df = ((i * 37) % 11) as f64 * 0.2 - 1.0;
+            let noise = ((i * 13) % 7) as f64 * 0.02 - 0.06;
+            let beta = if i < 250 { 0.8 } else { 1.2 };
+            kalman.update(beta * df + noise, df);
+            if i == 249 {
+                assert!((kalman.ratio() - 0.8).abs() < 0.05);
+            }
+        }
+
+        assert!((kalman.ratio() - 1.2).abs() < 0.05);
+        assert!(kalman.variance() < config.initial_variance);
+
+        let diagnostics = kalman.diagnostics();
+        assert_eq!(diagnostics.observations, 500);
+        assert_eq!(diagnostics.ratio, kalman.ratio());
+        assert!(diagnostics.mean_nis > 0.2 && diagnostics.mean_nis < 5.0);
+    }
+
+    #[test]
+    fn test_kalman_online_noise() {
+        let mut kalman = KalmanRatio::new(KalmanConfig::default());
+
+        // Relative changes: a few tenths of a percent
+        for i in 0..2000u64 {
+            let df = (((i * 37) % 11) as f64 - 5.0) * 1e-3;
+            let noise = (((i * 13) % 7) as f64 - 3.0) * 2e-4;
+            kalman.update(0.9 * df + noise, df);
+        }
+
+        assert!((kalman.ratio() - 0.9).abs() < 0.05);
+        let diagnostics = kalman.diagnostics();
+        assert!(diagnostics.standardized_innovation.abs() < 5.0);
+        assert!(diagnostics.mean_nis > 0.2 && diagnostics.mean_nis < 5.0);
+    }
+
+    #[test]
+    fn test_kalman_ignores_flat_futures() {
+        let config = KalmanConfig {
+            observation_noise: Some(1e-4),
+            ..Default::default()
+        };
+        let mut kalman = KalmanRatio::new(config);
+        kalman.update(0.5, 0.0);
+        assert_eq!(kalman.ratio(), 1.0);
+        assert!(kalman.variance() > config.initial_variance);
+    }
+
+    #[test]
+    fn test_kalman_validation() {
+        assert!(KalmanConfig::default().validate().is_ok());
+
+        let negative = KalmanConfig {
+            process_noise: -1.0,
+            ..Default::default()
+        };
+        assert!(negative.validate().is_err());
+
+        let zero_noise = KalmanConfig {
+            observation_noise: Some(0.0),
+            ..Default::default()
+        };
+        assert!(zero_noise.validate().is_err());
+    }
+}
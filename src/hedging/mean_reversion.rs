@@ -1,6 +1,10 @@
+use crate::hedging::{
+    AdfTest, Deterministic, OrnsteinUhlenbeck, SampledWindow, SamplingConfig, StationarityReport,
+};
+use crate::utils::{SharedClock, SystemClock};
 use parking_lot::RwLock;
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
 
 /// Mean reversion hedging strategy
 ///
@@ -11,9 +15,16 @@ use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 /// - κ (kappa) = speed of mean reversion
 /// - μ (mu) = long-term mean
 /// - σ (sigma) = volatility
+///
+/// κ, μ and σ are fitted from the price history on each recalculation; the
+/// constructor's κ is only a prior until the first fit. When the fit finds
+/// no mean reversion, hedges are not adjusted.
 pub struct MeanReversionHedge {
     /// Historical prices for mean calculation
-    price_history: RwLock<VecDeque<f64>>,
+    price_history: RwLock<SampledWindow<f64>>,
+
+    /// Last price added, before bucketing (f64 bits, NaN if none)
+    last_price: AtomicU64,
 
     /// Cached mean price (fixed-point: price * 10000)
     mean_price: AtomicI64,
@@ -24,73 +35,163 @@ pub struct MeanReversionHedge {
     /// Kappa (mean reversion speed) (fixed-point: kappa * 10000)
     kappa: AtomicI64,
 
+    /// Last Ornstein-Uhlenbeck fit
+    fit: RwLock<Option<OrnsteinUhlenbeck>>,
+
+    /// False when the last fit found no mean reversion
+    mean_reverting: AtomicBool,
+
     /// Last calculation timestamp
     last_calc_ns: AtomicU64,
 
+    /// Recalculation interval (nanoseconds)
+    recalc_interval_ns: u64,
+
     /// Z-score threshold for hedging
     z_threshold: f64,
 
-    /// Window size
+    /// Window size (observations, or hours once sampled by time)
     window_size: usize,
 
     /// Hedge strength factor (0.0 - 1.0)
     hedge_strength: f64,
+
+    /// Time source for calculation timestamps
+    clock: SharedClock,
 }
 
 impl MeanReversionHedge {
+    /// Prices required before statistics are calculated
+    pub const MIN_OBSERVATIONS: usize = 30;
+
+    /// Significance of the unit root test behind `is_mean_reverting`
+    pub const SIGNIFICANCE: f64 = 0.05;
+
     /// Create new mean reversion strategy
+    ///
+    /// Every price is kept until `window_size` newer ones arrive; use
+    /// `with_sampling` for a window of `window_size` wall-clock hours.
     pub fn new(window_size: usize, kappa: f64, z_threshold: f64, hedge_strength: f64) -> Self {
         Self {
-            price_history: RwLock::new(VecDeque::with_capacity(window_size)),
+            price_history: RwLock::new(SampledWindow::with_capacity(window_size)),
+            last_price: AtomicU64::new(f64::NAN.to_bits()),
             mean_price: AtomicI64::new(0),
             std_dev: AtomicI64::new(0),
             kappa: AtomicI64::new((kappa * 10000.0) as i64),
+            fit: RwLock::new(None),
+            mean_reverting: AtomicBool::new(true),
             last_calc_ns: AtomicU64::new(0),
+            recalc_interval_ns: 3600 * 1_000_000_000,
             z_threshold,
             window_size,
             hedge_strength,
+            clock: SystemClock::shared(),
         }
     }
 
-    /// Add price observation
+    /// Use a custom time source (e.g. a simulated clock for replay)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the recalculation interval (default: 1 hour)
+    pub fn with_recalc_interval(mut self, interval: Duration) -> Self {
+        self.recalc_interval_ns = interval.as_nanos() as u64;
+        self
+    }
+
+    /// Sample prices into time buckets over `window_size` hours
+    ///
+    /// Replaces any prices recorded so far.
+    pub fn with_sampling(self, sampling: SamplingConfig) -> Self {
+        let window_ns = self.window_size as u64 * 3600 * 1_000_000_000;
+        *self.price_history.write() = SampledWindow::with_duration(window_ns, sampling);
+        self
+    }
+
+    /// Add price observation at the current clock time
     pub fn add_price(&self, price: f64) {
-        let mut history = self.price_history.write();
-        history.push_back(price);
+        self.add_price_at(self.clock.now_ns(), price);
+    }
 
-        if history.len() > self.window_size {
-            history.pop_front();
-        }
+    /// Add price observation made at `timestamp_ns`
+    ///
+    /// When sampling by time, the last price of each bucket is kept.
+    pub fn add_price_at(&self, timestamp_ns: u64, price: f64) {
+        self.price_history.write().record(timestamp_ns, price);
+        self.last_price.store(price.to_bits(), Ordering::Release);
+    }
+
+    /// Last price added, the one to score against the history
+    ///
+    /// Unlike the window's closes, includes the still-open bucket.
+    #[inline(always)]
+    pub fn last_price(&self) -> Option<f64> {
+        let price = f64::from_bits(self.last_price.load(Ordering::Acquire));
+        (!price.is_nan()).then_some(price)
     }
 
-    /// Calculate statistics (mean, std dev)
+    /// Calculate statistics (mean, std dev) and fit the OU process
     ///
-    /// Runs in background thread (cold path)
+    /// Cold path: run by the engine's recalibration scheduler. Returns
+    /// None below `MIN_OBSERVATIONS` prices. A mean-reverting fit replaces
+    /// κ; a fit without mean reversion disables hedge adjustments.
     pub fn calculate_statistics(&self) -> Option<(f64, f64)> {
         let history = self.price_history.read();
 
-        if history.len() < 30 {
+        if history.len() < Self::MIN_OBSERVATIONS {
             return None;
         }
 
         // Calculate mean
-        let mean: f64 = history.iter().sum::<f64>() / history.len() as f64;
+        let mean: f64 = history.values().sum::<f64>() / history.len() as f64;
 
         // Calculate standard deviation
         let variance: f64 =
-            history.iter().map(|&p| (p - mean).powi(2)).sum::<f64>() / (history.len() - 1) as f64;
+            history.values().map(|p| (p - mean).powi(2)).sum::<f64>() / (history.len() - 1) as f64;
         let std_dev = variance.sqrt();
 
+        let pairs: Vec<_> = history.consecutive_timed().collect();
+        let fit = OrnsteinUhlenbeck::fit(&pairs);
+        let prices: Vec<f64> = history
+            .thinned(StationarityReport::MAX_OBSERVATIONS)
+            .collect();
+        drop(history);
+        if let Some(fit) = fit {
+            // ADF with lagged differences when available: robust to
+            // autocorrelated shocks, unlike the AR(1) Dickey-Fuller check
+            let mean_reverting = match AdfTest::new(&prices, Deterministic::Constant) {
+                Some(adf) => fit.ar_coefficient < 1.0 && adf.is_stationary(Self::SIGNIFICANCE),
+                None => fit.is_mean_reverting(),
+            };
+            if mean_reverting {
+                self.kappa
+                    .store((fit.kappa * 10000.0) as i64, Ordering::Release);
+            }
+            self.mean_reverting.store(mean_reverting, Ordering::Release);
+        }
+        *self.fit.write() = fit;
+
         // Update cached values
         self.mean_price
             .store((mean * 10000.0) as i64, Ordering::Release);
         self.std_dev
             .store((std_dev * 10000.0) as i64, Ordering::Release);
         self.last_calc_ns
-            .store(crate::utils::get_timestamp_ns(), Ordering::Release);
+            .store(self.clock.now_ns(), Ordering::Release);
 
         Some((mean, std_dev))
     }
 
+    /// Check if recalculation is needed
+    ///
+    /// True until the first successful calculation, then once per interval.
+    pub fn needs_recalculation(&self) -> bool {
+        let last_calc = self.last_calc_ns.load(Ordering::Relaxed);
+        last_calc == 0 || self.clock.now_ns().saturating_sub(last_calc) > self.recalc_interval_ns
+    }
+
     /// Calculate z-score for current price
     ///
     /// # Performance
@@ -109,8 +210,13 @@ impl MeanReversionHedge {
 
     /// Check if hedge adjustment is needed
     ///
-    /// Returns adjusted hedge strength based on z-score
+    /// Returns adjusted hedge strength based on z-score; full hedge when
+    /// the last fit found no mean reversion
     pub fn should_adjust_hedge(&self, current_price: f64) -> Option<f64> {
+        if !self.mean_reverting.load(Ordering::Acquire) {
+            return Some(1.0);
+        }
+
         let z_score = self.calculate_z_score(current_price);
 
         if z_score.abs() > self.z_threshold {
@@ -139,6 +245,37 @@ impl MeanReversionHedge {
         (2.0_f64.ln()) / kappa
     }
 
+    /// Check whether the last fit detected mean reversion
+    ///
+    /// The ADF test must reject a unit root at `SIGNIFICANCE`. True before
+    /// the first fit.
+    pub fn is_mean_reverting(&self) -> bool {
+        self.mean_reverting.load(Ordering::Acquire)
+    }
+
+    /// ADF and KPSS tests of the price history (around a level)
+    ///
+    /// Histories longer than `StationarityReport::MAX_OBSERVATIONS` are
+    /// thinned.
+    ///
+    /// # Performance
+    /// O(n · lags), n bounded: cold path.
+    pub fn stationarity(&self) -> StationarityReport {
+        let prices: Vec<f64> = self
+            .price_history
+            .read()
+            .thinned(StationarityReport::MAX_OBSERVATIONS)
+            .collect();
+        StationarityReport::of_series(&prices, Deterministic::Constant)
+    }
+
+    /// Last Ornstein-Uhlenbeck fit, with standard errors
+    ///
+    /// None before the first fit or when the history was degenerate.
+    pub fn ou_fit(&self) -> Option<OrnsteinUhlenbeck> {
+        *self.fit.read()
+    }
+
     /// Get current statistics
     pub fn get_statistics(&self) -> MeanReversionStats {
         MeanReversionStats {
@@ -147,6 +284,8 @@ impl MeanReversionHedge {
             kappa: (self.kappa.load(Ordering::Acquire) as f64) / 10000.0,
             half_life_days: self.half_life_days(),
             observations: self.price_history.read().len(),
+            mean_reverting: self.is_mean_reverting(),
+            fit: self.ou_fit(),
         }
     }
 }
@@ -159,20 +298,25 @@ pub struct MeanReversionStats {
     pub kappa: f64,
     pub half_life_days: f64,
     pub observations: usize,
+    pub mean_reverting: bool,
+    pub fit: Option<OrnsteinUhlenbeck>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::SimulatedClock;
 
     #[test]
     fn test_mean_reversion_basic() {
         let strategy = MeanReversionHedge::new(100, 0.20, 2.0, 0.7);
+        assert_eq!(strategy.last_price(), None);
 
         // Add prices around mean of 45.0
         for i in 0..50 {
             strategy.add_price(45.0 + (i % 10) as f64 * 0.5);
         }
+        assert_eq!(strategy.last_price(), Some(49.5));
 
         let stats = strategy.calculate_statistics();
         assert!(stats.is_some());
@@ -225,4 +369,46 @@ mod tests {
         let half_life = strategy.half_life_days();
         assert!((half_life - 3.47).abs() < 0.1);
     }
+
+    #[test]
+    fn test_fitted_kappa_and_rejection() {
+        let clock = SimulatedClock::shared(0);
+        let strategy = MeanReversionHedge::new(24 * 30, 0.20, 2.0, 1.0)
+            .with_clock(clock.clone())
+            .with_sampling(SamplingConfig::hourly());
+
+        // Hourly AR(1) around 45: b = 0.9 → κ = -ln(0.9) × 24 ≈ 2.53/day
+        let mut price = 45.0;
+        for i in 0..500u64 {
+            let shock = ((i * 7) % 11) as f64 * 0.2 - 1.0;
+            price = 45.0 + 0.9 * (price - 45.0) + shock;
+            clock.advance_by(3600 * 1_000_000_000);
+            strategy.add_price(price);
+        }
+        strategy.calculate_statistics().unwrap();
+
+        let fit = strategy.ou_fit().unwrap();
+        assert!((fit.interval_days - 1.0 / 24.0).abs() < 1e-9);
+        assert!(fit.is_mean_reverting());
+        assert!(fit.kappa_se > 0.0 && fit.mu_se > 0.0 && fit.sigma_se > 0.0);
+        let stats = strategy.get_statistics();
+        assert!(stats.mean_reverting);
+        assert!((stats.kappa - fit.kappa).abs() < 1e-3);
+        assert!((stats.half_life_days - 3.47).abs() > 1.0);
+
+        // A random walk is rejected: full hedge whatever the z-score
+        let walk = MeanReversionHedge::new(100, 0.20, 2.0, 1.0).with_clock(clock.clone());
+        let mut price = 45.0;
+        for i in 0..100u64 {
+            price += ((i * i * 7 + 3 * i) % 101) as f64 / 50.0 - 1.0;
+            clock.advance_by(60 * 1_000_000_000);
+            walk.add_price(price);
+        }
+        walk.calculate_statistics().unwrap();
+        assert!(!walk.is_mean_reverting());
+        assert!(!walk.stationarity().adf.unwrap().is_stationary(0.05));
+        assert!(strategy.stationarity().adf.unwrap().is_stationary(0.05));
+        assert_eq!(walk.should_adjust_hedge(60.0), Some(1.0));
+        assert!((walk.half_life_days() - 3.47).abs() < 0.1);
+    }
 }
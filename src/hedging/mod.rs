@@ -1,17 +1,44 @@
 //! Hedging strategies and execution engine
 
 mod config;
+mod constraints;
+mod cross_hedge;
 mod delta;
+mod diagnostics;
 mod engine;
+mod estimators;
+mod kalman;
 mod mean_reversion;
 mod mvhr;
+mod ou;
+mod recalibration;
+mod rolling;
+mod sampling;
 mod spark_spread;
+mod stationarity;
+#[cfg(test)]
+mod test_rng;
 
-pub use config::{HedgeConfig, HedgeRecommendation, Urgency};
+pub use config::{
+    CrossHedgeConfig, ExposureConfig, HedgeConfig, HedgeRecommendation, PriceSource,
+    UnhealthyBookAction, Urgency,
+};
+pub use constraints::{
+    Binding, ConstrainedAllocation, HedgeConstraints, HedgeCovariance, LegAllocation, LegTerms,
+};
+pub use cross_hedge::{CrossHedgeStatistics, CrossHedgeStrategy};
 pub use delta::DeltaHedge;
+pub use diagnostics::{RatioAcceptance, RatioDecision, RatioDiagnostics, StabilityTest};
 pub use engine::HedgeEngine;
+pub use estimators::{CovarianceForecast, DccGarch, EwmaCovariance, Garch11, RatioEstimator};
+pub use kalman::{KalmanConfig, KalmanDiagnostics, KalmanRatio};
 pub use mean_reversion::{MeanReversionHedge, MeanReversionStats};
-pub use mvhr::{MVHRStatistics, MVHRStrategy};
+pub use mvhr::{MVHRStatistics, MVHRStrategy, PriceChangeMode};
+pub use ou::OrnsteinUhlenbeck;
+pub use recalibration::{RecalibrationConfig, RecalibrationFailure, RecalibrationTask};
+pub use rolling::RollingCovariance;
+pub use sampling::{GapPolicy, PairEvent, SampledWindow, SamplingConfig};
 pub use spark_spread::{
     CostsBreakdown, SparkSpreadHedge, SparkSpreadPositions, SparkSpreadRecommendations,
 };
+pub use stationarity::{AdfTest, Deterministic, EngleGranger, KpssTest, StationarityReport};
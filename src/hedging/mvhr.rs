@@ -1,127 +1,337 @@
-use parking_lot::lock_api::{RwLockReadGuard, RwLockWriteGuard};
-use parking_lot::{RawRwLock, RwLock};
-use std::collections::VecDeque;
+use crate::hedging::{
+    CovarianceForecast, DccGarch, EngleGranger, EwmaCovariance, KalmanDiagnostics, KalmanRatio,
+    PairEvent, RatioDiagnostics, RatioEstimator, RollingCovariance, SampledWindow, SamplingConfig,
+    StationarityReport,
+};
+use crate::utils::{SharedClock, SystemClock};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How price changes between observations are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PriceChangeMode {
+    /// Percentage returns: (P_t - P_{t-1}) / P_{t-1}
+    ///
+    /// Undefined when the previous price is zero; such observations are
+    /// skipped.
+    #[default]
+    Relative,
+
+    /// Absolute price changes: P_t - P_{t-1}
+    ///
+    /// Well-defined for zero and negative prices, as seen in power markets.
+    Absolute,
+}
+
+impl PriceChangeMode {
+    /// Price change between two consecutive observations
+    #[inline(always)]
+    pub(crate) fn change(self, prev: f64, curr: f64) -> Option<f64> {
+        match self {
+            PriceChangeMode::Relative if prev.abs() < 1e-9 => None,
+            PriceChangeMode::Relative => Some((curr - prev) / prev.abs()),
+            PriceChangeMode::Absolute => Some(curr - prev),
+        }
+    }
+
+    /// Changes of a (spot, futures) pair, if both are defined
+    #[inline(always)]
+    fn pair_change(self, prev: (f64, f64), curr: (f64, f64)) -> Option<(f64, f64)> {
+        self.change(prev.0, curr.0).zip(self.change(prev.1, curr.1))
+    }
+}
+
+/// Largest ratio accepted; beyond it, likely numerical issues
+pub(crate) const MAX_RATIO: f64 = 5.0;
+
+/// Price window with incrementally maintained change statistics
+struct Observations {
+    /// Historical (spot, futures) price pairs
+    window: SampledWindow<(f64, f64)>,
+
+    /// Moments of (spot change, futures change) over the window
+    changes: RollingCovariance,
+
+    /// Exponentially weighted moments of all changes (EWMA estimator only)
+    ewma: Option<EwmaCovariance>,
+
+    /// Filtered ratio over all changes (Kalman estimator only)
+    kalman: Option<KalmanRatio>,
+}
+
+impl Observations {
+    fn new(window: SampledWindow<(f64, f64)>, estimator: RatioEstimator) -> Self {
+        let mut observations = Self {
+            window,
+            changes: RollingCovariance::new(),
+            ewma: None,
+            kalman: None,
+        };
+        observations.reset_estimator(estimator);
+        observations
+    }
+
+    /// Start the per-change state of `estimator` from scratch
+    fn reset_estimator(&mut self, estimator: RatioEstimator) {
+        self.ewma = match estimator {
+            RatioEstimator::Ewma { lambda } => Some(EwmaCovariance::new(lambda)),
+            _ => None,
+        };
+        self.kalman = match estimator {
+            RatioEstimator::Kalman(config) => Some(KalmanRatio::new(config)),
+            _ => None,
+        };
+    }
+
+    /// Record a price pair, updating the change moments in O(1)
+    fn record(&mut self, timestamp_ns: u64, prices: (f64, f64), mode: PriceChangeMode) {
+        let changes = &mut self.changes;
+        let ewma = &mut self.ewma;
+        let kalman = &mut self.kalman;
+        self.window
+            .record_with(timestamp_ns, prices, |event| match event {
+                PairEvent::Added(prev, curr) => {
+                    if let Some((s, f)) = mode.pair_change(prev, curr) {
+                        changes.add(s, f);
+                        if let Some(ewma) = ewma {
+                            ewma.update(s, f);
+                        }
+                        if let Some(kalman) = kalman {
+                            kalman.update(s, f);
+                        }
+                    }
+                }
+                PairEvent::Removed(prev, curr) => {
+                    if let Some((s, f)) = mode.pair_change(prev, curr) {
+                        changes.remove(s, f);
+                    }
+                }
+            });
+
+        // Bound the drift of repeated removals
+        if self.changes.removals() >= RollingCovariance::RECOMPUTE_INTERVAL {
+            let window = &self.window;
+            self.changes.recompute(
+                window
+                    .consecutive()
+                    .filter_map(|(prev, curr)| mode.pair_change(prev, curr)),
+            );
+        }
+    }
+
+    /// Change moments, if the window holds enough data
+    ///
+    /// Needs at least 3 observations and 2 defined changes.
+    fn moments(&self) -> Option<&RollingCovariance> {
+        (self.window.len() >= 3 && self.changes.len() >= 2).then_some(&self.changes)
+    }
+
+    /// Equal-weighted or EWMA covariance of the changes
+    fn forecast(&self) -> Option<CovarianceForecast> {
+        let moments = self.moments()?;
+        match self.ewma {
+            Some(ref ewma) => ewma.forecast(),
+            None => Some(CovarianceForecast {
+                variance_x: moments.variance_x()?,
+                variance_y: moments.variance_y()?,
+                covariance: moments.covariance()?,
+            }),
+        }
+    }
+
+    /// Defined changes in the window, oldest first
+    fn change_series(&self, mode: PriceChangeMode) -> Vec<(f64, f64)> {
+        self.window
+            .consecutive()
+            .filter_map(|(prev, curr)| mode.pair_change(prev, curr))
+            .collect()
+    }
+}
 
 /// MVHR (Minimum Variance Hedge Ratio) strategy
 ///
 /// Calculates optimal hedge ratio using historical correlation
 pub struct MVHRStrategy {
-    /// Historical spot prices
-    spot_prices: RwLock<VecDeque<f64>>,
-
-    /// Historical futures prices
-    futures_prices: RwLock<VecDeque<f64>>,
+    /// Historical prices and change statistics
+    observations: RwLock<Observations>,
 
     /// Cached optimal ratio (fixed-point: ratio * 10000)
     cached_ratio: AtomicI64,
 
+    /// Number of estimates stored in `cached_ratio` (0 = default ratio)
+    ratio_revision: AtomicU64,
+
     /// Last calculation timestamp (nanoseconds)
     last_calc_ns: AtomicU64,
 
-    /// Window size (number of observations)
+    /// Window size (observations, or hours once sampled by time)
     window_size: usize,
 
     /// Recalculation interval (nanoseconds)
     recalc_interval_ns: u64,
+
+    /// Price change measure
+    change_mode: PriceChangeMode,
+
+    /// Covariance estimator behind the ratio
+    estimator: RatioEstimator,
+
+    /// Last GARCH-DCC forecast (GarchDcc estimator only)
+    garch_forecast: RwLock<Option<CovarianceForecast>>,
+
+    /// Time source for the recalculation schedule
+    clock: SharedClock,
 }
 
 impl MVHRStrategy {
-    /// Create new MVHR strategy
+    /// Create new MVHR strategy using percentage returns
+    ///
+    /// Every observation is kept until `window_hours` newer ones arrive;
+    /// use `with_sampling` for a window of `window_hours` wall-clock hours.
     pub fn new(window_hours: usize, recalc_hours: usize) -> Self {
+        Self::with_change_mode(window_hours, recalc_hours, PriceChangeMode::Relative)
+    }
+
+    /// Create new MVHR strategy with an explicit price change measure
+    pub fn with_change_mode(
+        window_hours: usize,
+        recalc_hours: usize,
+        change_mode: PriceChangeMode,
+    ) -> Self {
         Self {
-            spot_prices: RwLock::new(VecDeque::with_capacity(window_hours)),
-            futures_prices: RwLock::new(VecDeque::with_capacity(window_hours)),
+            observations: RwLock::new(Observations::new(
+                SampledWindow::with_capacity(window_hours),
+                RatioEstimator::Ols,
+            )),
             cached_ratio: AtomicI64::new(10000), // Default 1.0
+            ratio_revision: AtomicU64::new(0),
             last_calc_ns: AtomicU64::new(0),
             window_size: window_hours,
             recalc_interval_ns: (recalc_hours as u64) * 3600 * 1_000_000_000,
+            change_mode,
+            estimator: RatioEstimator::Ols,
+            garch_forecast: RwLock::new(None),
+            clock: SystemClock::shared(),
         }
     }
 
-    /// Add new price observation
-    pub fn add_observation(&self, spot_price: f64, futures_price: f64) {
-        let mut spot_prices: RwLockWriteGuard<RawRwLock, VecDeque<f64>> = self.spot_prices.write();
-        let mut futures_prices: RwLockWriteGuard<RawRwLock, VecDeque<f64>> =
-            self.futures_prices.write();
-
-        // Add new prices
-        spot_prices.push_back(spot_price);
-        futures_prices.push_back(futures_price);
-
-        // Maintain window size
-        if spot_prices.len() > self.window_size {
-            spot_prices.pop_front();
-            futures_prices.pop_front();
-        }
+    /// Use a custom time source (e.g. a simulated clock for replay)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
     }
 
-    /// Calculate optimal hedge ratio
-    ///
-    /// h* = Cov(ΔS, ΔF) / Var(ΔF)
-    ///
-    /// Requires at least 3 observations (to get 2 returns for variance calculation)
-    pub fn calculate_optimal_ratio(&self) -> Option<f64> {
-        let spot_prices = self.spot_prices.read();
-        let futures_prices = self.futures_prices.read();
-
-        // Need at least 3 observations to calculate meaningful statistics
-        // (3 prices → 2 returns → can calculate variance)
-        if spot_prices.len() < 3 {
-            return None;
-        }
-
-        // Calculate returns
-        let mut spot_returns = Vec::with_capacity(spot_prices.len() - 1);
-        let mut futures_returns = Vec::with_capacity(futures_prices.len() - 1);
-
-        for i in 1..spot_prices.len() {
-            let spot_ret = (spot_prices[i] - spot_prices[i - 1]) / spot_prices[i - 1];
-            let futures_ret = (futures_prices[i] - futures_prices[i - 1]) / futures_prices[i - 1];
-
-            spot_returns.push(spot_ret);
-            futures_returns.push(futures_ret);
-        }
-
-        let n = spot_returns.len();
+    /// Set the recalculation interval
+    pub fn with_recalc_interval(mut self, interval: Duration) -> Self {
+        self.recalc_interval_ns = interval.as_nanos() as u64;
+        self
+    }
 
-        // Calculate means
-        let spot_mean: f64 = spot_returns.iter().sum::<f64>() / n as f64;
-        let futures_mean: f64 = futures_returns.iter().sum::<f64>() / n as f64;
+    /// Sample observations into time buckets over `window_hours` hours
+    ///
+    /// Replaces any observations recorded so far.
+    pub fn with_sampling(self, sampling: SamplingConfig) -> Self {
+        let window_ns = self.window_size as u64 * 3600 * 1_000_000_000;
+        *self.observations.write() = Observations::new(
+            SampledWindow::with_duration(window_ns, sampling),
+            self.estimator,
+        );
+        self
+    }
 
-        // Calculate covariance and variance
-        let mut covariance = 0.0;
-        let mut variance = 0.0;
+    /// Estimate the ratio with `estimator` instead of the window OLS
+    ///
+    /// Resets EWMA and Kalman state; call before adding observations.
+    pub fn with_estimator(mut self, estimator: RatioEstimator) -> Self {
+        self.estimator = estimator;
+        self.observations.get_mut().reset_estimator(estimator);
+        self
+    }
 
-        for i in 0..n {
-            let spot_diff = spot_returns[i] - spot_mean;
-            let futures_diff = futures_returns[i] - futures_mean;
+    /// Get the covariance estimator
+    pub fn estimator(&self) -> RatioEstimator {
+        self.estimator
+    }
 
-            covariance += spot_diff * futures_diff;
-            variance += futures_diff * futures_diff;
-        }
+    /// Get the price change measure
+    pub fn change_mode(&self) -> PriceChangeMode {
+        self.change_mode
+    }
 
-        covariance /= (n - 1) as f64;
-        variance /= (n - 1) as f64;
+    /// Add new price observation at the current clock time
+    pub fn add_observation(&self, spot_price: f64, futures_price: f64) {
+        self.add_observation_at(self.clock.now_ns(), spot_price, futures_price);
+    }
 
-        // Avoid division by zero
-        if variance.abs() < 1e-10 {
-            return None;
-        }
+    /// Add new price observation made at `timestamp_ns`
+    ///
+    /// When sampling by time, the last observation of each bucket is kept.
+    /// Change statistics, and the Kalman filter, are updated incrementally
+    /// in O(1); the cached ratio changes only in `calculate_optimal_ratio`.
+    pub fn add_observation_at(&self, timestamp_ns: u64, spot_price: f64, futures_price: f64) {
+        self.observations.write().record(
+            timestamp_ns,
+            (spot_price, futures_price),
+            self.change_mode,
+        );
+    }
 
-        let ratio = covariance / variance;
+    /// Calculate optimal hedge ratio
+    ///
+    /// h* = Cov(ΔS, ΔF) / Var(ΔF)
+    ///
+    /// ΔS and ΔF are percentage returns or absolute changes depending on
+    /// the configured `PriceChangeMode`. Changes with an undefined value or
+    /// spanning a skipped gap are left out. The covariance comes from the
+    /// configured `RatioEstimator`.
+    ///
+    /// Requires at least 3 observations (to get 2 returns for variance
+    /// calculation), or `DccGarch::MIN_OBSERVATIONS` changes for GARCH-DCC.
+    /// The Kalman estimator returns its current filtered ratio.
+    ///
+    /// # Performance
+    /// O(1), no allocation with OLS, EWMA and Kalman: cheap enough to
+    /// refresh on every observation. GARCH-DCC refits over the window
+    /// (cold path).
+    pub fn calculate_optimal_ratio(&self) -> Option<f64> {
+        let ratio = match self.estimator {
+            RatioEstimator::Kalman(_) => {
+                let observations = self.observations.read();
+                let kalman = observations.kalman.as_ref().filter(|k| !k.is_empty())?;
+                kalman.ratio()
+            }
+            estimator => {
+                let forecast = match estimator {
+                    RatioEstimator::GarchDcc => {
+                        let changes = self.observations.read().change_series(self.change_mode);
+                        let forecast = DccGarch::fit(&changes)?.forecast();
+                        *self.garch_forecast.write() = Some(forecast);
+                        forecast
+                    }
+                    _ => self.observations.read().forecast()?,
+                };
+                let (covariance, variance) = (forecast.covariance, forecast.variance_y);
+
+                // Avoid division by zero
+                if variance.abs() < 1e-10 {
+                    return None;
+                }
+
+                covariance / variance
+            }
+        };
 
         // Sanity check: ratio should be reasonable (-5 to +5)
-        // If outside this range, likely numerical issues
-        if ratio.abs() > 5.0 {
+        if ratio.abs() > MAX_RATIO {
             return None;
         }
 
         // Update cached value
-        self.cached_ratio
-            .store((ratio * 10000.0) as i64, Ordering::Release);
+        self.store_ratio(ratio);
         self.last_calc_ns
-            .store(crate::utils::get_timestamp_ns(), Ordering::Release);
+            .store(self.clock.now_ns(), Ordering::Release);
 
         Some(ratio)
     }
@@ -132,71 +342,140 @@ impl MVHRStrategy {
         (self.cached_ratio.load(Ordering::Acquire) as f64) / 10000.0
     }
 
+    /// Number of estimates behind the cached ratio
+    ///
+    /// 0 while `get_hedge_ratio` still returns the default of 1.0.
+    #[inline(always)]
+    pub fn ratio_revision(&self) -> u64 {
+        self.ratio_revision.load(Ordering::Acquire)
+    }
+
+    #[inline(always)]
+    fn store_ratio(&self, ratio: f64) {
+        self.cached_ratio
+            .store((ratio * 10000.0) as i64, Ordering::Release);
+        self.ratio_revision.fetch_add(1, Ordering::AcqRel);
+    }
+
     /// Check if recalculation is needed
+    ///
+    /// True until the first successful calculation, then once per interval.
     pub fn needs_recalculation(&self) -> bool {
         let last_calc: u64 = self.last_calc_ns.load(Ordering::Relaxed);
-        let now: u64 = crate::utils::get_timestamp_ns();
+        let now: u64 = self.clock.now_ns();
 
-        now - last_calc > self.recalc_interval_ns
+        last_calc == 0 || now.saturating_sub(last_calc) > self.recalc_interval_ns
     }
 
-    /// Get statistics
-    pub fn get_statistics(&self) -> Option<MVHRStatistics> {
-        let spot_prices: RwLockReadGuard<RawRwLock, VecDeque<f64>> = self.spot_prices.read();
-        let futures_prices: RwLockReadGuard<RawRwLock, VecDeque<f64>> = self.futures_prices.read();
-
-        // Need at least 3 observations
-        if spot_prices.len() < 3 {
-            return None;
-        }
-
-        // Calculate returns
-        let mut spot_returns: Vec<f64> = Vec::new();
-        let mut futures_returns: Vec<f64> = Vec::new();
-
-        for i in 1..spot_prices.len() {
-            spot_returns.push((spot_prices[i] - spot_prices[i - 1]) / spot_prices[i - 1]);
-            futures_returns
-                .push((futures_prices[i] - futures_prices[i - 1]) / futures_prices[i - 1]);
+    /// Check whether the window holds enough data for the estimator
+    pub fn has_sufficient_data(&self) -> bool {
+        let observations = self.observations.read();
+        match self.estimator {
+            RatioEstimator::GarchDcc => {
+                observations.window.len() > DccGarch::MIN_OBSERVATIONS
+                    && observations.changes.len() >= DccGarch::MIN_OBSERVATIONS
+            }
+            RatioEstimator::Kalman(_) => observations.kalman.is_some_and(|k| !k.is_empty()),
+            _ => observations.forecast().is_some(),
         }
+    }
 
-        let n = spot_returns.len();
-
-        // Calculate statistics
-        let spot_mean: f64 = spot_returns.iter().sum::<f64>() / n as f64;
-        let futures_mean: f64 = futures_returns.iter().sum::<f64>() / n as f64;
+    /// Kalman filter state and innovation diagnostics (Kalman estimator only)
+    pub fn kalman_diagnostics(&self) -> Option<KalmanDiagnostics> {
+        self.observations
+            .read()
+            .kalman
+            .as_ref()
+            .map(KalmanRatio::diagnostics)
+    }
 
-        let spot_var: f64 = spot_returns
-            .iter()
-            .map(|&r| (r - spot_mean).powi(2))
-            .sum::<f64>()
-            / (n - 1) as f64;
+    /// Regression diagnostics of the ratio over the window
+    ///
+    /// OLS of spot on futures changes: confidence interval at
+    /// `confidence_level`, hedge effectiveness, residual autocorrelation
+    /// and a stability test over `stability_windows` sub-windows. With the
+    /// Kalman estimator, the ratio and its interval are the filter's
+    /// posterior. None with fewer than 3 changes or flat futures.
+    ///
+    /// # Performance
+    /// O(n) over the window: cold path.
+    pub fn ratio_diagnostics(
+        &self,
+        confidence_level: f64,
+        stability_windows: usize,
+    ) -> Option<RatioDiagnostics> {
+        let observations = self.observations.read();
+        let changes = observations.change_series(self.change_mode);
+        let kalman = observations
+            .kalman
+            .as_ref()
+            .filter(|kalman| !kalman.is_empty())
+            .map(|kalman| (kalman.ratio(), kalman.variance()));
+        drop(observations);
+
+        let diagnostics =
+            RatioDiagnostics::estimate(&changes, confidence_level, stability_windows)?;
+        Some(match kalman {
+            Some((ratio, variance)) => diagnostics.with_estimate(ratio, variance),
+            None => diagnostics,
+        })
+    }
 
-        let futures_var: f64 = futures_returns
-            .iter()
-            .map(|&r| (r - futures_mean).powi(2))
-            .sum::<f64>()
-            / (n - 1) as f64;
+    /// Engle-Granger test of spot against futures price levels
+    ///
+    /// Whether the basis mean-reverts, whatever the change mode. Windows
+    /// longer than `StationarityReport::MAX_OBSERVATIONS` are thinned.
+    /// None with fewer than `AdfTest::MIN_OBSERVATIONS` prices.
+    ///
+    /// # Performance
+    /// O(n · lags), n bounded: cold path.
+    pub fn cointegration(&self) -> Option<EngleGranger> {
+        let (spot, futures): (Vec<f64>, Vec<f64>) = self
+            .observations
+            .read()
+            .window
+            .thinned(StationarityReport::MAX_OBSERVATIONS)
+            .unzip();
+        EngleGranger::new(&spot, &[&futures])
+    }
 
-        let covariance: f64 = spot_returns
-            .iter()
-            .zip(futures_returns.iter())
-            .map(|(&s, &f)| (s - spot_mean) * (f - futures_mean))
-            .sum::<f64>()
-            / (n - 1) as f64;
-
-        let correlation = if spot_var > 0.0 && futures_var > 0.0 {
-            covariance / (spot_var.sqrt() * futures_var.sqrt())
-        } else {
-            0.0
+    /// Get statistics
+    ///
+    /// Volatilities and correlation come from the configured estimator;
+    /// for GARCH-DCC, from the last fit (None before the first one), and
+    /// for Kalman from the window. Standard error and R² are those of the
+    /// window OLS, in O(1) from the rolling moments.
+    pub fn get_statistics(&self) -> Option<MVHRStatistics> {
+        let observations = self.observations.read();
+        let forecast = match self.estimator {
+            RatioEstimator::GarchDcc => (*self.garch_forecast.read())?,
+            _ => observations.forecast()?,
         };
 
+        // SE(β) = √(SSE / (n - 2) / Sxx), SSE = (n - 1)(Var(ΔS) - Cov² / Var(ΔF))
+        let (standard_error, r_squared) = observations
+            .moments()
+            .filter(|moments| moments.len() >= 3)
+            .and_then(|moments| {
+                let n = moments.len() as f64;
+                let (var_s, var_f) = (moments.variance_x()?, moments.variance_y()?);
+                let cov = moments.covariance()?;
+                (var_s > 0.0 && var_f > 0.0).then(|| {
+                    let r_squared = (cov * cov / (var_s * var_f)).min(1.0);
+                    let residual_variance = (n - 1.0) * var_s * (1.0 - r_squared) / (n - 2.0);
+                    ((residual_variance / ((n - 1.0) * var_f)).sqrt(), r_squared)
+                })
+            })
+            .unwrap_or((0.0, 0.0));
+
         Some(MVHRStatistics {
             hedge_ratio: self.get_hedge_ratio(),
-            correlation,
-            observations: spot_prices.len(),
-            spot_volatility: spot_var.sqrt(),
-            futures_volatility: futures_var.sqrt(),
+            correlation: forecast.correlation(),
+            observations: observations.window.len(),
+            spot_volatility: forecast.variance_x.sqrt(),
+            futures_volatility: forecast.variance_y.sqrt(),
+            standard_error,
+            r_squared,
         })
     }
 }
@@ -209,6 +488,12 @@ pub struct MVHRStatistics {
     pub observations: usize,
     pub spot_volatility: f64,
     pub futures_volatility: f64,
+
+    /// Standard error of the window OLS ratio
+    pub standard_error: f64,
+
+    /// Hedge effectiveness over the window (squared correlation)
+    pub r_squared: f64,
 }
 
 #[cfg(test)]
@@ -275,13 +560,11 @@ mod tests {
             mvhr.add_observation(spot, futures);
         }
 
-        let spot_prices = mvhr.spot_prices.read();
-        let futures_prices = mvhr.futures_prices.read();
+        let observations = mvhr.observations.read();
 
-        assert_eq!(spot_prices.len(), 10);
-        assert_eq!(futures_prices.len(), 10);
+        assert_eq!(observations.window.len(), 10);
 
-        let last_spot = spot_prices.back().unwrap();
+        let (last_spot, _) = observations.window.latest().unwrap();
         let expected_last_spot = 45.0 + 19.0 * 0.1;
         assert!((last_spot - expected_last_spot).abs() < 0.01);
     }
@@ -429,4 +712,215 @@ mod tests {
             stats.correlation
         );
     }
+
+    #[test]
+    fn test_mvhr_absolute_mode_negative_prices() {
+        let mvhr = MVHRStrategy::with_change_mode(100, 1, PriceChangeMode::Absolute);
+
+        // Spot swings through zero and below, futures moves 0.8x as much
+        let moves = [-30.0, -15.0, 5.0, 20.0, -10.0, 12.0, -8.0, 25.0, -18.0];
+        let mut spot = 10.0;
+        let mut futures = 40.0;
+        mvhr.add_observation(spot, futures);
+        for m in moves.iter().cycle().take(40) {
+            spot += m;
+            futures += m / 1.25;
+            mvhr.add_observation(spot, futures);
+        }
+
+        let ratio = mvhr.calculate_optimal_ratio().unwrap();
+        assert!((ratio - 1.25).abs() < 1e-6, "got {}", ratio);
+
+        let stats = mvhr.get_statistics().unwrap();
+        assert!((stats.correlation - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mvhr_relative_mode_skips_zero_prices() {
+        let mvhr = MVHRStrategy::new(100, 1);
+
+        for i in 0..50 {
+            // Spot touches exactly zero every 10th observation
+            let spot = if i % 10 == 0 {
+                0.0
+            } else {
+                45.0 + i as f64 * 0.1
+            };
+            mvhr.add_observation(spot, 1.0 + 1.1 * spot + (i % 3) as f64 * 0.05);
+        }
+
+        let ratio = mvhr.calculate_optimal_ratio();
+        assert!(ratio.is_some_and(|r| r.is_finite() && r > 0.5 && r < 1.5));
+
+        // 49 pairs; the 5 leaving a zero spot have no return and are
+        // skipped (moves into zero are -100% and kept)
+        let observations = mvhr.observations.read();
+        assert_eq!(observations.window.len(), 50);
+        assert_eq!(observations.changes.len(), 44);
+        assert_eq!(observations.change_series(mvhr.change_mode).len(), 44);
+        drop(observations);
+
+        let stats = mvhr.get_statistics().unwrap();
+        assert!(stats.spot_volatility.is_finite());
+    }
+
+    #[test]
+    fn test_mvhr_recalculation_schedule_follows_clock() {
+        use crate::utils::SimulatedClock;
+
+        let hour_ns = 3600 * 1_000_000_000;
+        let clock = SimulatedClock::shared(10 * hour_ns);
+        let mvhr = MVHRStrategy::new(100, 1).with_clock(clock.clone());
+
+        for i in 0..20 {
+            mvhr.add_observation(45.0 + (i % 4) as f64, 50.0 + (i % 5) as f64);
+        }
+        assert!(mvhr.needs_recalculation());
+        mvhr.calculate_optimal_ratio().unwrap();
+        assert!(!mvhr.needs_recalculation());
+
+        clock.advance_by(hour_ns);
+        assert!(!mvhr.needs_recalculation());
+        clock.advance_by(1);
+        assert!(mvhr.needs_recalculation());
+    }
+
+    #[test]
+    fn test_mvhr_hourly_sampling() {
+        let hour_ns = 3600 * 1_000_000_000;
+        let mvhr = MVHRStrategy::with_change_mode(24, 1, PriceChangeMode::Absolute)
+            .with_sampling(SamplingConfig::hourly());
+
+        // A tick every minute for 30 hours
+        for minute in 0..30 * 60u64 {
+            let shock = ((minute * 7) % 11) as f64 * 0.1;
+            mvhr.add_observation_at(minute * 60_000_000_000, 45.0 + shock, 50.0 + 2.0 * shock);
+        }
+
+        // 29 closed hours, of which the last 24 are in the window
+        let stats = mvhr.get_statistics().unwrap();
+        assert_eq!(stats.observations, 24);
+
+        let ratio = mvhr.calculate_optimal_ratio().unwrap();
+        assert!((ratio - 0.5).abs() < 1e-9);
+
+        mvhr.add_observation_at(30 * hour_ns, 45.0, 50.0);
+        assert_eq!(mvhr.get_statistics().unwrap().observations, 24);
+    }
+
+    #[test]
+    fn test_mvhr_incremental_matches_full_window() {
+        let mvhr = MVHRStrategy::new(100, 1);
+
+        // Enough evictions to trigger several exact recomputes
+        for i in 0..5_000u64 {
+            let shock = ((i * 37) % 101) as f64 * 0.05;
+            let noise = ((i * 13) % 7) as f64 * 0.01;
+            mvhr.add_observation(45.0 + shock, 50.0 + 0.9 * shock + noise);
+        }
+        let ratio = mvhr.calculate_optimal_ratio().unwrap();
+
+        // Two-pass reference over the current window
+        let observations = mvhr.observations.read();
+        let prices: Vec<(f64, f64)> = observations.window.values().collect();
+        let changes: Vec<(f64, f64)> = prices
+            .windows(2)
+            .map(|w| ((w[1].0 - w[0].0) / w[0].0, (w[1].1 - w[0].1) / w[0].1))
+            .collect();
+        let n = changes.len() as f64;
+        let mean_s = changes.iter().map(|c| c.0).sum::<f64>() / n;
+        let mean_f = changes.iter().map(|c| c.1).sum::<f64>() / n;
+        let cov: f64 = changes
+            .iter()
+            .map(|c| (c.0 - mean_s) * (c.1 - mean_f))
+            .sum();
+        let var: f64 = changes.iter().map(|c| (c.1 - mean_f).powi(2)).sum();
+
+        assert_eq!(observations.changes.len(), 99);
+        assert!((ratio - cov / var).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mvhr_ewma_tracks_regime_change() {
+        let ols = MVHRStrategy::with_change_mode(1000, 1, PriceChangeMode::Absolute);
+        let ewma = MVHRStrategy::with_change_mode(1000, 1, PriceChangeMode::Absolute)
+            .with_estimator(RatioEstimator::ewma());
+
+        // Spot moves 0.5x the futures change, then 1.5x
+        let (mut spot, mut futures) = (45.0, 50.0);
+        for i in 0..600u64 {
+            let change = ((i * 37) % 11) as f64 * 0.1 - 0.5;
+            let beta = if i < 300 { 0.5 } else { 1.5 };
+            spot += beta * change;
+            futures += change;
+            ols.add_observation(spot, futures);
+            ewma.add_observation(spot, futures);
+        }
+
+        let static_ratio = ols.calculate_optimal_ratio().unwrap();
+        let dynamic_ratio = ewma.calculate_optimal_ratio().unwrap();
+        assert!((static_ratio - 1.0).abs() < 0.1);
+        assert!((dynamic_ratio - 1.5).abs() < 1e-3);
+        assert_eq!(ewma.estimator(), RatioEstimator::ewma());
+        assert_eq!(ewma.get_statistics().unwrap().observations, 600);
+    }
+
+    #[test]
+    fn test_mvhr_garch_dcc() {
+        let mvhr = MVHRStrategy::with_change_mode(500, 1, PriceChangeMode::Absolute)
+            .with_estimator(RatioEstimator::GarchDcc);
+
+        let (mut spot, mut futures) = (45.0, 50.0);
+        for i in 0..300u64 {
+            if i == 40 {
+                assert!(!mvhr.has_sufficient_data());
+                assert!(mvhr.calculate_optimal_ratio().is_none());
+            }
+            // Volatility bursts every 50 observations
+            let scale = if i % 50 < 10 { 3.0 } else { 1.0 };
+            let change = scale * (((i * 37) % 11) as f64 * 0.1 - 0.5);
+            let noise = ((i * 13) % 7) as f64 * 0.01 - 0.03;
+            spot += 0.8 * change + noise;
+            futures += change;
+            mvhr.add_observation(spot, futures);
+        }
+
+        assert!(mvhr.has_sufficient_data());
+        assert!(mvhr.get_statistics().is_none()); // Not fitted yet
+
+        let ratio = mvhr.calculate_optimal_ratio().unwrap();
+        assert!((ratio - 0.8).abs() < 0.05, "ratio {}", ratio);
+
+        let stats = mvhr.get_statistics().unwrap();
+        assert_eq!(stats.hedge_ratio, mvhr.get_hedge_ratio());
+        assert!(stats.correlation > 0.9);
+        assert!(stats.futures_volatility > 0.0);
+    }
+
+    #[test]
+    fn test_mvhr_ratio_diagnostics() {
+        let mvhr = MVHRStrategy::with_change_mode(500, 24, PriceChangeMode::Absolute);
+        assert!(mvhr.ratio_diagnostics(0.95, 3).is_none());
+
+        let (mut spot, mut futures) = (45.0, 50.0);
+        for i in 0..301u64 {
+            let df = ((i * 37) % 11) as f64 * 0.2 - 1.0;
+            let noise = (((i * 13) % 7) as f64 - 3.0) * 0.05;
+            futures += df;
+            spot += 0.7 * df + noise;
+            mvhr.add_observation(spot, futures);
+        }
+        mvhr.calculate_optimal_ratio().unwrap();
+
+        let diagnostics = mvhr.ratio_diagnostics(0.95, 3).unwrap();
+        assert_eq!(diagnostics.observations, 300);
+        let (low, high) = diagnostics.confidence_interval;
+        assert!(low < 0.7 && 0.7 < high);
+        assert!(diagnostics.stability.unwrap().is_stable(0.05));
+
+        // O(1) statistics agree with the full regression
+        let stats = mvhr.get_statistics().unwrap();
+        assert!((stats.standard_error - diagnostics.standard_error).abs() < 1e-9);
+        assert!((stats.r_squared - diagnostics.r_squared).abs() < 1e-9);
+    }
 }
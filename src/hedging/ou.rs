@@ -0,0 +1,237 @@
+//! Ornstein-Uhlenbeck parameter estimation
+//!
+//! dS = κ(μ - S)dt + σdW sampled every Δ days is exactly an AR(1):
+//! S_{t+Δ} = a + b·S_t + ε, with b = e^{-κΔ}, a = μ(1 - b) and
+//! Var(ε) = σ²(1 - b²)/(2κ). Least squares on consecutive prices is the
+//! conditional maximum-likelihood estimate of (a, b, Var(ε)), from which
+//! κ, μ and σ follow.
+
+/// Nanoseconds per day
+const DAY_NS: f64 = 86_400.0 * 1e9;
+
+/// Fitted Ornstein-Uhlenbeck process
+///
+/// Rates are per day and σ per √day, whatever the sampling interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrnsteinUhlenbeck {
+    /// κ: speed of mean reversion (per day)
+    pub kappa: f64,
+
+    /// μ: long-term mean
+    pub mu: f64,
+
+    /// σ: volatility (per √day)
+    pub sigma: f64,
+
+    /// Standard error of κ (delta method on b)
+    pub kappa_se: f64,
+
+    /// Standard error of μ (delta method on a, b)
+    pub mu_se: f64,
+
+    /// Standard error of σ (given κ)
+    pub sigma_se: f64,
+
+    /// AR(1) coefficient b = e^{-κΔ}
+    pub ar_coefficient: f64,
+
+    /// Dickey-Fuller statistic (b - 1) / SE(b)
+    pub dickey_fuller: f64,
+
+    /// Sampling interval Δ (days): median time between observations
+    pub interval_days: f64,
+
+    /// Price pairs fitted
+    pub observations: usize,
+}
+
+impl OrnsteinUhlenbeck {
+    /// Pairs required for a fit
+    pub const MIN_OBSERVATIONS: usize = 10;
+
+    /// 5% Dickey-Fuller critical value (regression with constant)
+    pub const DF_CRITICAL_5PCT: f64 = -2.86;
+
+    /// Fit from (elapsed nanoseconds, previous, current) price pairs
+    ///
+    /// None with too few pairs, a flat series, or b ≤ 0 (reversion faster
+    /// than the sampling interval, so κ is not identified). A fit with
+    /// b ≥ 1 is returned but is not mean reverting.
+    pub fn fit(pairs: &[(u64, f64, f64)]) -> Option<Self> {
+        let n = pairs.len();
+        if n < Self::MIN_OBSERVATIONS {
+            return None;
+        }
+
+        let mut elapsed: Vec<u64> = pairs.iter().map(|&(dt, _, _)| dt).collect();
+        elapsed.sort_unstable();
+        let interval_days = elapsed[n / 2] as f64 / DAY_NS;
+        if interval_days <= 0.0 {
+            return None;
+        }
+
+        let nf = n as f64;
+        let x_mean = pairs.iter().map(|&(_, x, _)| x).sum::<f64>() / nf;
+        let y_mean = pairs.iter().map(|&(_, _, y)| y).sum::<f64>() / nf;
+        let (sxx, sxy) = pairs.iter().fold((0.0, 0.0), |(sxx, sxy), &(_, x, y)| {
+            let dx = x - x_mean;
+            (sxx + dx * dx, sxy + dx * (y - y_mean))
+        });
+        if sxx <= 0.0 {
+            return None;
+        }
+
+        let b = sxy / sxx;
+        let a = y_mean - b * x_mean;
+        let sse: f64 = pairs.iter().map(|&(_, x, y)| (y - a - b * x).powi(2)).sum();
+        let residual_variance = sse / (nf - 2.0);
+        let b_se = (residual_variance / sxx).sqrt();
+        let dickey_fuller = if b_se > 0.0 {
+            (b - 1.0) / b_se
+        } else if b < 1.0 {
+            f64::NEG_INFINITY
+        } else {
+            0.0
+        };
+
+        if b <= 0.0 {
+            return None;
+        }
+
+        let kappa = -b.ln() / interval_days;
+        let kappa_se = b_se / (b * interval_days);
+
+        // μ = a / (1 - b); undefined without reversion
+        let (mu, mu_se) = if b < 1.0 {
+            let mu = a / (1.0 - b);
+            let a_var = residual_variance * (1.0 / nf + x_mean * x_mean / sxx);
+            let ab_cov = -x_mean * residual_variance / sxx;
+            let b_var = b_se * b_se;
+            let (da, db) = (1.0 / (1.0 - b), a / (1.0 - b).powi(2));
+            let mu_var = da * da * a_var + db * db * b_var + 2.0 * da * db * ab_cov;
+            (mu, mu_var.max(0.0).sqrt())
+        } else {
+            (f64::NAN, f64::NAN)
+        };
+
+        // σ² = 2κ Var(ε) / (1 - b²), → Var(ε)/Δ as κ → 0
+        let sigma = if b < 1.0 {
+            (2.0 * kappa * residual_variance / (1.0 - b * b)).sqrt()
+        } else {
+            (residual_variance / interval_days).sqrt()
+        };
+        let sigma_se = sigma / (2.0 * (nf - 2.0)).sqrt();
+
+        Some(Self {
+            kappa,
+            mu,
+            sigma,
+            kappa_se,
+            mu_se,
+            sigma_se,
+            ar_coefficient: b,
+            dickey_fuller,
+            interval_days,
+            observations: n,
+        })
+    }
+
+    /// Check whether the unit root is rejected at 5% (κ > 0)
+    pub fn is_mean_reverting(&self) -> bool {
+        self.ar_coefficient < 1.0 && self.dickey_fuller < Self::DF_CRITICAL_5PCT
+    }
+
+    /// Half-life ln 2 / κ (days)
+    pub fn half_life_days(&self) -> f64 {
+        if self.kappa <= 0.0 {
+            return f64::INFINITY;
+        }
+        std::f64::consts::LN_2 / self.kappa
+    }
+
+    /// Stationary standard deviation σ / √(2κ)
+    pub fn stationary_std(&self) -> f64 {
+        if self.kappa <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.sigma / (2.0 * self.kappa).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hedging::test_rng::TestRng;
+
+    const HOUR_NS: u64 = 3600 * 1_000_000_000;
+
+    /// Exact OU simulation sampled every `dt_ns`
+    fn simulate(kappa: f64, mu: f64, sigma: f64, dt_ns: u64, n: usize) -> Vec<(u64, f64, f64)> {
+        let mut rng = TestRng::new(7);
+        let dt = dt_ns as f64 / DAY_NS;
+        let b = (-kappa * dt).exp();
+        let step_std = sigma * ((1.0 - b * b) / (2.0 * kappa)).sqrt();
+
+        let mut price = mu;
+        (0..n)
+            .map(|_| {
+                let next = mu + b * (price - mu) + step_std * rng.normal();
+                let pair = (dt_ns, price, next);
+                price = next;
+                pair
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ou_recovers_parameters() {
+        // Hourly prices, κ = 0.5/day (half-life ≈ 1.4 days)
+        let pairs = simulate(0.5, 45.0, 2.0, HOUR_NS, 24 * 365);
+        let fit = OrnsteinUhlenbeck::fit(&pairs).unwrap();
+
+        assert!((fit.interval_days - 1.0 / 24.0).abs() < 1e-12);
+        assert!((fit.kappa - 0.5).abs() < 3.0 * fit.kappa_se);
+        assert!((fit.mu - 45.0).abs() < 3.0 * fit.mu_se);
+        assert!((fit.sigma - 2.0).abs() < 3.0 * fit.sigma_se);
+        assert!(fit.is_mean_reverting());
+        assert!((fit.half_life_days() - std::f64::consts::LN_2 / fit.kappa).abs() < 1e-12);
+        assert!((fit.stationary_std() - 2.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_ou_interval_awareness() {
+        // Same process sampled daily: κ is per day either way
+        let hourly = OrnsteinUhlenbeck::fit(&simulate(0.5, 45.0, 2.0, HOUR_NS, 24 * 365)).unwrap();
+        let daily = OrnsteinUhlenbeck::fit(&simulate(0.5, 45.0, 2.0, 24 * HOUR_NS, 2000)).unwrap();
+
+        assert!((daily.interval_days - 1.0).abs() < 1e-12);
+        assert!((daily.kappa - hourly.kappa).abs() < 3.0 * (daily.kappa_se + hourly.kappa_se));
+        assert!(daily.ar_coefficient < hourly.ar_coefficient);
+    }
+
+    #[test]
+    fn test_ou_rejects_random_walk() {
+        let mut rng = TestRng::new(11);
+        let mut price = 45.0;
+        let pairs: Vec<_> = (0..2000)
+            .map(|_| {
+                let next = price + rng.uniform() - 0.5;
+                let pair = (HOUR_NS, price, next);
+                price = next;
+                pair
+            })
+            .collect();
+
+        let fit = OrnsteinUhlenbeck::fit(&pairs).unwrap();
+        assert!(!fit.is_mean_reverting());
+        assert!(fit.dickey_fuller > OrnsteinUhlenbeck::DF_CRITICAL_5PCT);
+    }
+
+    #[test]
+    fn test_ou_degenerate_inputs() {
+        assert!(OrnsteinUhlenbeck::fit(&simulate(0.5, 45.0, 2.0, HOUR_NS, 5)).is_none());
+
+        let flat = vec![(HOUR_NS, 45.0, 45.0); 50];
+        assert!(OrnsteinUhlenbeck::fit(&flat).is_none());
+    }
+}
@@ -0,0 +1,436 @@
+//! Cold-path recalibration of strategy parameters
+
+use crate::hedging::{CrossHedgeStrategy, MVHRStrategy, MeanReversionHedge};
+use crate::utils::{Metrics, SharedClock, cycles_to_ns, read_cycles};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Recalibration schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecalibrationConfig {
+    /// Interval between MVHR hedge ratio recalculations (seconds)
+    #[serde(default = "default_mvhr_interval_secs")]
+    pub mvhr_interval_secs: u64,
+
+    /// Interval between mean reversion mean/std recalculations (seconds)
+    #[serde(default = "default_mean_reversion_interval_secs")]
+    pub mean_reversion_interval_secs: u64,
+
+    /// Delay before retrying a failed recalibration (seconds)
+    #[serde(default = "default_retry_interval_secs")]
+    pub retry_interval_secs: u64,
+
+    /// How often the background thread checks for due tasks (milliseconds)
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_mvhr_interval_secs() -> u64 {
+    86_400
+}
+
+fn default_mean_reversion_interval_secs() -> u64 {
+    3600
+}
+
+fn default_retry_interval_secs() -> u64 {
+    60
+}
+
+fn default_poll_interval_ms() -> u64 {
+    1000
+}
+
+impl Default for RecalibrationConfig {
+    fn default() -> Self {
+        Self {
+            mvhr_interval_secs: default_mvhr_interval_secs(),
+            mean_reversion_interval_secs: default_mean_reversion_interval_secs(),
+            retry_interval_secs: default_retry_interval_secs(),
+            poll_interval_ms: default_poll_interval_ms(),
+        }
+    }
+}
+
+impl RecalibrationConfig {
+    /// Polling interval of the background thread
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+}
+
+/// A recalibration that returned an error
+#[derive(Debug)]
+pub struct RecalibrationFailure {
+    /// Task name (e.g. "mvhr:default")
+    pub task: String,
+
+    /// Clock time of the attempt (nanoseconds)
+    pub timestamp_ns: u64,
+
+    /// Failure cause
+    pub error: crate::Error,
+}
+
+/// User task run on the recalibration schedule
+pub type RecalibrationTask = Box<dyn FnMut() -> crate::Result<()> + Send>;
+
+/// Parameters recomputed by a task
+enum Target {
+    /// MVHR hedge ratio
+    Mvhr(Arc<MVHRStrategy>),
+
+    /// Mean reversion mean and standard deviation
+    MeanReversion(Arc<MeanReversionHedge>),
+
+    /// Cross hedge ratios per leg
+    CrossHedge(Arc<CrossHedgeStrategy>),
+
+    /// User task with its own interval
+    Custom {
+        interval_ns: u64,
+        last_run_ns: Option<u64>,
+        run: RecalibrationTask,
+    },
+}
+
+/// Result of checking one task
+enum Outcome {
+    /// Not due yet, or waiting for a retry
+    Idle,
+
+    /// Not enough observations yet; retried on the next poll
+    Skipped,
+
+    /// Parameters updated
+    Done,
+
+    /// Recalibration failed
+    Failed(crate::Error),
+}
+
+struct Task {
+    name: String,
+    target: Target,
+
+    /// No attempt before this time after a failure (nanoseconds)
+    retry_at_ns: u64,
+}
+
+impl Task {
+    fn run(&mut self, now_ns: u64) -> Outcome {
+        if now_ns < self.retry_at_ns {
+            return Outcome::Idle;
+        }
+
+        match self.target {
+            Target::Mvhr(ref mvhr) => {
+                if !mvhr.needs_recalculation() {
+                    Outcome::Idle
+                } else if !mvhr.has_sufficient_data() {
+                    Outcome::Skipped
+                } else if mvhr.calculate_optimal_ratio().is_some() {
+                    Outcome::Done
+                } else {
+                    Outcome::Failed(crate::Error::Calculation(
+                        "MVHR ratio undefined or out of range (degenerate futures variance)"
+                            .to_string(),
+                    ))
+                }
+            }
+            Target::MeanReversion(ref mr) => {
+                if !mr.needs_recalculation() {
+                    Outcome::Idle
+                } else if mr.calculate_statistics().is_some() {
+                    Outcome::Done
+                } else {
+                    Outcome::Skipped
+                }
+            }
+            Target::CrossHedge(ref cross_hedge) => {
+                if !cross_hedge.needs_recalculation() {
+                    Outcome::Idle
+                } else if !cross_hedge.has_sufficient_data() {
+                    Outcome::Skipped
+                } else {
+                    match cross_hedge.calculate_optimal_ratios() {
+                        Ok(_) => Outcome::Done,
+                        Err(e) => Outcome::Failed(e),
+                    }
+                }
+            }
+            Target::Custom {
+                interval_ns,
+                ref mut last_run_ns,
+                ref mut run,
+            } => {
+                if last_run_ns.is_some_and(|last| now_ns.saturating_sub(last) < interval_ns) {
+                    return Outcome::Idle;
+                }
+                *last_run_ns = Some(now_ns);
+                match run() {
+                    Ok(()) => Outcome::Done,
+                    Err(e) => Outcome::Failed(e),
+                }
+            }
+        }
+    }
+}
+
+/// Recalibration tasks shared with the background thread
+pub(crate) struct Recalibrator {
+    /// Tasks, locked for the duration of a pass
+    tasks: Mutex<Vec<Task>>,
+
+    /// Schedule configuration
+    config: RecalibrationConfig,
+
+    /// Time source for due checks
+    clock: SharedClock,
+
+    /// Engine metrics
+    metrics: Arc<RwLock<Metrics>>,
+
+    /// Failures not yet taken (oldest dropped beyond `MAX_FAILURES`)
+    failures: Mutex<VecDeque<RecalibrationFailure>>,
+}
+
+impl Recalibrator {
+    /// Failures kept until taken
+    const MAX_FAILURES: usize = 256;
+
+    pub(crate) fn new(
+        config: RecalibrationConfig,
+        clock: SharedClock,
+        metrics: Arc<RwLock<Metrics>>,
+    ) -> Self {
+        Self {
+            tasks: Mutex::new(Vec::new()),
+            config,
+            clock,
+            metrics,
+            failures: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub(crate) fn add_mvhr(&self, name: &str, mvhr: Arc<MVHRStrategy>) {
+        self.push(format!("mvhr:{}", name), Target::Mvhr(mvhr));
+    }
+
+    pub(crate) fn add_mean_reversion(&self, name: &str, mr: Arc<MeanReversionHedge>) {
+        self.push(
+            format!("mean_reversion:{}", name),
+            Target::MeanReversion(mr),
+        );
+    }
+
+    pub(crate) fn add_cross_hedge(&self, name: &str, cross_hedge: Arc<CrossHedgeStrategy>) {
+        self.push(
+            format!("cross_hedge:{}", name),
+            Target::CrossHedge(cross_hedge),
+        );
+    }
+
+    pub(crate) fn add_custom(&self, name: &str, interval: Duration, run: RecalibrationTask) {
+        let target = Target::Custom {
+            interval_ns: interval.as_nanos() as u64,
+            last_run_ns: None,
+            run,
+        };
+        self.push(name.to_string(), target);
+    }
+
+    fn push(&self, name: String, target: Target) {
+        self.tasks.lock().push(Task {
+            name,
+            target,
+            retry_at_ns: 0,
+        });
+    }
+
+    /// Run every due task once; returns the number of tasks run
+    ///
+    /// Tasks still waiting for enough observations are not counted.
+    pub(crate) fn run_due(&self) -> usize {
+        let mut tasks = self.tasks.lock();
+        let mut ran = 0;
+
+        for task in tasks.iter_mut() {
+            let now_ns = self.clock.now_ns();
+            let start_cycles = read_cycles();
+            let outcome = task.run(now_ns);
+            let duration_ns = cycles_to_ns(read_cycles().saturating_sub(start_cycles));
+
+            match outcome {
+                Outcome::Idle | Outcome::Skipped => continue,
+                Outcome::Done => {
+                    self.metrics.write().record_recalibration(duration_ns, true);
+                }
+                Outcome::Failed(error) => {
+                    log::warn!("Recalibration {} failed: {}", task.name, error);
+                    task.retry_at_ns = now_ns + self.config.retry_interval_secs * 1_000_000_000;
+                    self.metrics
+                        .write()
+                        .record_recalibration(duration_ns, false);
+
+                    let mut failures = self.failures.lock();
+                    if failures.len() == Self::MAX_FAILURES {
+                        failures.pop_front();
+                    }
+                    failures.push_back(RecalibrationFailure {
+                        task: task.name.clone(),
+                        timestamp_ns: now_ns,
+                        error,
+                    });
+                }
+            }
+            ran += 1;
+        }
+
+        ran
+    }
+
+    /// Drain recorded failures, oldest first
+    pub(crate) fn take_failures(&self) -> Vec<RecalibrationFailure> {
+        self.failures.lock().drain(..).collect()
+    }
+}
+
+/// Background thread running `Recalibrator::run_due`
+pub(crate) struct RecalibrationThread {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl RecalibrationThread {
+    pub(crate) fn spawn(recalibrator: Arc<Recalibrator>) -> crate::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let poll_interval = recalibrator.config.poll_interval();
+
+        let thread_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("hedge-recalibration".to_string())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Acquire) {
+                    recalibrator.run_due();
+                    std::thread::park_timeout(poll_interval);
+                }
+            })
+            .map_err(|e| {
+                crate::Error::InvalidState(format!("Cannot spawn recalibration thread: {}", e))
+            })?;
+
+        Ok(Self { stop, handle })
+    }
+
+    /// Signal the thread and wait for its current pass to finish
+    pub(crate) fn stop(self) {
+        self.stop.store(true, Ordering::Release);
+        self.handle.thread().unpark();
+        if self.handle.join().is_err() {
+            log::error!("Recalibration thread panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::SimulatedClock;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn recalibrator(clock: SharedClock) -> Recalibrator {
+        let config = RecalibrationConfig {
+            retry_interval_secs: 10,
+            ..Default::default()
+        };
+        Recalibrator::new(config, clock, Arc::new(RwLock::new(Metrics::new())))
+    }
+
+    #[test]
+    fn test_mean_reversion_follows_interval() {
+        let clock = SimulatedClock::shared(SECOND);
+        let recalibrator = recalibrator(clock.clone());
+        let mr = Arc::new(
+            MeanReversionHedge::new(100, 0.2, 2.0, 1.0)
+                .with_recalc_interval(Duration::from_secs(60))
+                .with_clock(clock.clone()),
+        );
+        recalibrator.add_mean_reversion("power", mr.clone());
+
+        // Not enough prices yet: skipped, not counted
+        assert_eq!(recalibrator.run_due(), 0);
+
+        for i in 0..40 {
+            mr.add_price(40.0 + (i % 5) as f64);
+        }
+        assert_eq!(recalibrator.run_due(), 1);
+        assert!((mr.get_statistics().mean_price - 42.0).abs() < 1e-3);
+
+        // Next run once the interval has elapsed
+        clock.advance_by(30 * SECOND);
+        assert_eq!(recalibrator.run_due(), 0);
+        clock.advance_by(31 * SECOND);
+        assert_eq!(recalibrator.run_due(), 1);
+        assert_eq!(recalibrator.metrics.read().recalibrations(), 2);
+    }
+
+    #[test]
+    fn test_failures_are_reported_and_retried() {
+        let clock = SimulatedClock::shared(SECOND);
+        let recalibrator = recalibrator(clock.clone());
+
+        let mut calls = 0;
+        recalibrator.add_custom(
+            "flaky",
+            Duration::from_secs(1),
+            Box::new(move || {
+                calls += 1;
+                match calls {
+                    1 => Err(crate::Error::Calculation("no data".to_string())),
+                    _ => Ok(()),
+                }
+            }),
+        );
+
+        assert_eq!(recalibrator.run_due(), 1);
+        let failures = recalibrator.take_failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].task, "flaky");
+        assert_eq!(failures[0].timestamp_ns, SECOND);
+        assert!(matches!(failures[0].error, crate::Error::Calculation(_)));
+
+        // Waits for the retry delay, not the task interval
+        clock.advance_by(5 * SECOND);
+        assert_eq!(recalibrator.run_due(), 0);
+        clock.advance_by(5 * SECOND);
+        assert_eq!(recalibrator.run_due(), 1);
+
+        let metrics = recalibrator.metrics.read();
+        assert_eq!(metrics.recalibrations(), 2);
+        assert_eq!(metrics.recalibration_failures(), 1);
+        assert!(recalibrator.take_failures().is_empty());
+    }
+
+    #[test]
+    fn test_degenerate_mvhr_fails() {
+        let clock = SimulatedClock::shared(SECOND);
+        let recalibrator = recalibrator(clock.clone());
+        let mvhr = Arc::new(MVHRStrategy::new(100, 1).with_clock(clock.clone()));
+        recalibrator.add_mvhr("power", mvhr.clone());
+
+        // Constant futures price: zero variance
+        for i in 0..10 {
+            mvhr.add_observation(45.0 + i as f64, 50.0);
+        }
+        assert_eq!(recalibrator.run_due(), 1);
+        assert_eq!(recalibrator.take_failures()[0].task, "mvhr:power");
+        assert_eq!(mvhr.get_hedge_ratio(), 1.0);
+    }
+}
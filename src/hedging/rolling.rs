@@ -0,0 +1,211 @@
+//! Incremental rolling moments for windowed statistics
+
+/// Rolling mean, variance and covariance of a paired series
+///
+/// Welford-style updates in O(1) without allocation: observations are
+/// added as they enter a window and removed as they leave it. Removal is
+/// less stable than addition, so callers should `recompute` from the
+/// window once `removals` exceeds `RECOMPUTE_INTERVAL`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RollingCovariance {
+    /// Number of observations
+    n: u64,
+
+    /// Mean of x
+    mean_x: f64,
+
+    /// Mean of y
+    mean_y: f64,
+
+    /// Sum of squared deviations of x
+    m2_x: f64,
+
+    /// Sum of squared deviations of y
+    m2_y: f64,
+
+    /// Sum of co-deviations of x and y
+    c_xy: f64,
+
+    /// Removals since the last reset
+    removals: u64,
+}
+
+impl RollingCovariance {
+    /// Removals after which an exact recompute is advised
+    pub const RECOMPUTE_INTERVAL: u64 = 1024;
+
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an observation
+    #[inline(always)]
+    pub fn add(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        let n = self.n as f64;
+
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / n;
+        self.mean_y += dy / n;
+
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    /// Remove an observation previously added
+    #[inline(always)]
+    pub fn remove(&mut self, x: f64, y: f64) {
+        self.removals += 1;
+        if self.n <= 1 {
+            *self = Self {
+                removals: self.removals,
+                ..Self::default()
+            };
+            return;
+        }
+
+        let n = (self.n - 1) as f64;
+        let mean_x = self.mean_x - (x - self.mean_x) / n;
+        let mean_y = self.mean_y - (y - self.mean_y) / n;
+
+        self.m2_x = (self.m2_x - (x - mean_x) * (x - self.mean_x)).max(0.0);
+        self.m2_y = (self.m2_y - (y - mean_y) * (y - self.mean_y)).max(0.0);
+        self.c_xy -= (x - mean_x) * (y - self.mean_y);
+
+        self.n -= 1;
+        self.mean_x = mean_x;
+        self.mean_y = mean_y;
+    }
+
+    /// Reset and add all observations again
+    pub fn recompute(&mut self, observations: impl IntoIterator<Item = (f64, f64)>) {
+        *self = Self::default();
+        for (x, y) in observations {
+            self.add(x, y);
+        }
+    }
+
+    /// Number of observations
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.n as usize
+    }
+
+    /// Check whether no observation was added
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Removals since the last reset or recompute
+    #[inline(always)]
+    pub fn removals(&self) -> u64 {
+        self.removals
+    }
+
+    /// Mean of x and y
+    #[inline(always)]
+    pub fn means(&self) -> (f64, f64) {
+        (self.mean_x, self.mean_y)
+    }
+
+    /// Sample variance of x (None below 2 observations)
+    #[inline(always)]
+    pub fn variance_x(&self) -> Option<f64> {
+        self.sample(self.m2_x)
+    }
+
+    /// Sample variance of y (None below 2 observations)
+    #[inline(always)]
+    pub fn variance_y(&self) -> Option<f64> {
+        self.sample(self.m2_y)
+    }
+
+    /// Sample covariance of x and y (None below 2 observations)
+    #[inline(always)]
+    pub fn covariance(&self) -> Option<f64> {
+        self.sample(self.c_xy)
+    }
+
+    /// Correlation of x and y (0 if either variance is zero)
+    pub fn correlation(&self) -> Option<f64> {
+        let (var_x, var_y) = (self.variance_x()?, self.variance_y()?);
+        if var_x > 0.0 && var_y > 0.0 {
+            Some(self.covariance()? / (var_x.sqrt() * var_y.sqrt()))
+        } else {
+            Some(0.0)
+        }
+    }
+
+    #[inline(always)]
+    fn sample(&self, sum: f64) -> Option<f64> {
+        (self.n >= 2).then(|| sum / (self.n - 1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exact(data: &[(f64, f64)]) -> (f64, f64, f64) {
+        let n = data.len() as f64;
+        let mx = data.iter().map(|p| p.0).sum::<f64>() / n;
+        let my = data.iter().map(|p| p.1).sum::<f64>() / n;
+        let var_x = data.iter().map(|p| (p.0 - mx).powi(2)).sum::<f64>() / (n - 1.0);
+        let var_y = data.iter().map(|p| (p.1 - my).powi(2)).sum::<f64>() / (n - 1.0);
+        let cov = data.iter().map(|p| (p.0 - mx) * (p.1 - my)).sum::<f64>() / (n - 1.0);
+        (var_x, var_y, cov)
+    }
+
+    #[test]
+    fn test_matches_two_pass() {
+        let data: Vec<(f64, f64)> = (0..200)
+            .map(|i| {
+                let x = ((i * 37) % 101) as f64 * 0.01 - 0.5;
+                (x, 0.8 * x + ((i * 13) % 7) as f64 * 0.002)
+            })
+            .collect();
+
+        // Rolling window of 50
+        let mut rolling = RollingCovariance::new();
+        for (i, &(x, y)) in data.iter().enumerate() {
+            rolling.add(x, y);
+            if i >= 50 {
+                let (old_x, old_y) = data[i - 50];
+                rolling.remove(old_x, old_y);
+            }
+        }
+
+        let (var_x, var_y, cov) = exact(&data[150..]);
+        assert_eq!(rolling.len(), 50);
+        assert!((rolling.variance_x().unwrap() - var_x).abs() < 1e-12);
+        assert!((rolling.variance_y().unwrap() - var_y).abs() < 1e-12);
+        assert!((rolling.covariance().unwrap() - cov).abs() < 1e-12);
+        assert_eq!(rolling.removals(), 150);
+
+        rolling.recompute(data[150..].iter().copied());
+        assert_eq!(rolling.removals(), 0);
+        assert!((rolling.covariance().unwrap() - cov).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_small_samples() {
+        let mut rolling = RollingCovariance::new();
+        assert!(rolling.covariance().is_none());
+
+        rolling.add(1.0, 2.0);
+        assert!(rolling.variance_x().is_none());
+
+        rolling.add(3.0, 6.0);
+        assert_eq!(rolling.covariance(), Some(4.0));
+        assert!((rolling.correlation().unwrap() - 1.0).abs() < 1e-12);
+
+        rolling.remove(1.0, 2.0);
+        rolling.remove(3.0, 6.0);
+        assert!(rolling.is_empty());
+        assert_eq!(rolling.means(), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,376 @@
+//! Time-bucketed sampling of price series for rolling statistics
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Handling of empty buckets (feed gaps, weekends, holidays)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GapPolicy {
+    /// Price changes span empty buckets (e.g. Friday close to Monday)
+    #[default]
+    Bridge,
+
+    /// Price changes across empty buckets are dropped
+    Skip,
+}
+
+/// How prices are sampled into statistics observations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SamplingConfig {
+    /// Bucket length in seconds; each bucket contributes its closing price
+    /// 0 = every price is an observation
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Handling of empty buckets
+    #[serde(default)]
+    pub gap_policy: GapPolicy,
+}
+
+fn default_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self::hourly()
+    }
+}
+
+impl SamplingConfig {
+    /// Maximum window length when sampling every price
+    pub const MAX_TICK_OBSERVATIONS: usize = 65_536;
+
+    /// Hourly closes
+    pub fn hourly() -> Self {
+        Self::every(3600)
+    }
+
+    /// Closes of `interval_secs` buckets
+    pub fn every(interval_secs: u64) -> Self {
+        Self {
+            interval_secs,
+            gap_policy: GapPolicy::Bridge,
+        }
+    }
+
+    /// Every price is an observation
+    pub fn every_tick() -> Self {
+        Self::every(0)
+    }
+
+    /// Set the gap policy
+    pub fn with_gap_policy(mut self, gap_policy: GapPolicy) -> Self {
+        self.gap_policy = gap_policy;
+        self
+    }
+
+    /// Bucket length in nanoseconds (0 = every price)
+    pub fn interval_ns(&self) -> u64 {
+        self.interval_secs * 1_000_000_000
+    }
+}
+
+/// Change to the pairs yielded by `SampledWindow::consecutive`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PairEvent<T> {
+    /// A pair (previous, current) entered the window
+    Added(T, T),
+
+    /// A pair (previous, current) left the window
+    Removed(T, T),
+}
+
+/// Rolling window of sampled observations
+///
+/// In bucketed mode, the last value recorded in a bucket is its close; it
+/// enters the window when the first value of a later bucket arrives.
+/// Values older than the current bucket are ignored.
+#[derive(Debug, Clone)]
+pub struct SampledWindow<T> {
+    /// Bucket length (0 = every value)
+    bucket_ns: u64,
+
+    /// Window duration (None = bounded by count only)
+    window_ns: Option<u64>,
+
+    /// Maximum number of observations
+    capacity: usize,
+
+    /// Handling of empty buckets
+    gap_policy: GapPolicy,
+
+    /// Committed observations (bucket start, value)
+    closes: VecDeque<(u64, T)>,
+
+    /// Open bucket and its latest value
+    pending: Option<(u64, T)>,
+}
+
+impl<T: Copy> SampledWindow<T> {
+    /// Window of the last `capacity` values, one per `record` call
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bucket_ns: 0,
+            window_ns: None,
+            capacity: capacity.max(1),
+            gap_policy: GapPolicy::Bridge,
+            closes: VecDeque::with_capacity(capacity),
+            pending: None,
+        }
+    }
+
+    /// Window covering `window_ns` of sampled observations
+    pub fn with_duration(window_ns: u64, sampling: SamplingConfig) -> Self {
+        let bucket_ns = sampling.interval_ns();
+        let capacity = match bucket_ns {
+            0 => SamplingConfig::MAX_TICK_OBSERVATIONS,
+            _ => (window_ns / bucket_ns).max(1) as usize,
+        };
+
+        Self {
+            bucket_ns,
+            window_ns: Some(window_ns),
+            capacity,
+            gap_policy: sampling.gap_policy,
+            closes: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// Record a value observed at `timestamp_ns`
+    pub fn record(&mut self, timestamp_ns: u64, value: T) {
+        self.record_with(timestamp_ns, value, |_| {});
+    }
+
+    /// Record a value, reporting pairs entering and leaving the window
+    ///
+    /// Lets callers maintain statistics over `consecutive` incrementally.
+    #[inline]
+    pub fn record_with<F: FnMut(PairEvent<T>)>(&mut self, timestamp_ns: u64, value: T, on_pair: F) {
+        if self.bucket_ns == 0 {
+            self.commit(timestamp_ns, value, on_pair);
+            return;
+        }
+
+        let bucket = timestamp_ns - timestamp_ns % self.bucket_ns;
+        match self.pending {
+            Some((open, _)) if bucket < open => {}
+            Some((open, close)) if bucket > open => {
+                self.commit(open, close, on_pair);
+                self.pending = Some((bucket, value));
+            }
+            _ => self.pending = Some((bucket, value)),
+        }
+    }
+
+    fn commit<F: FnMut(PairEvent<T>)>(&mut self, timestamp_ns: u64, value: T, mut on_pair: F) {
+        if let Some(&(last_ts, last)) = self.closes.back()
+            && self.contiguous(last_ts, timestamp_ns)
+        {
+            on_pair(PairEvent::Added(last, value));
+        }
+        self.closes.push_back((timestamp_ns, value));
+
+        if self.closes.len() > self.capacity {
+            self.evict_oldest(&mut on_pair);
+        }
+
+        if let Some(window_ns) = self.window_ns {
+            while let Some(&(oldest, _)) = self.closes.front() {
+                if oldest + window_ns > timestamp_ns {
+                    break;
+                }
+                self.evict_oldest(&mut on_pair);
+            }
+        }
+    }
+
+    fn evict_oldest<F: FnMut(PairEvent<T>)>(&mut self, on_pair: &mut F) {
+        if let Some((oldest_ts, oldest)) = self.closes.pop_front()
+            && let Some(&(next_ts, next)) = self.closes.front()
+            && self.contiguous(oldest_ts, next_ts)
+        {
+            on_pair(PairEvent::Removed(oldest, next));
+        }
+    }
+
+    /// Check whether a change between two observations is kept
+    #[inline(always)]
+    fn contiguous(&self, prev_ts: u64, timestamp_ns: u64) -> bool {
+        self.gap_policy == GapPolicy::Bridge
+            || self.bucket_ns == 0
+            || timestamp_ns.saturating_sub(prev_ts) <= self.bucket_ns
+    }
+
+    /// Number of observations in the window
+    pub fn len(&self) -> usize {
+        self.closes.len()
+    }
+
+    /// Check whether the window is empty
+    pub fn is_empty(&self) -> bool {
+        self.closes.is_empty()
+    }
+
+    /// Observations, oldest first
+    pub fn values(&self) -> impl ExactSizeIterator<Item = T> + '_ {
+        self.closes.iter().map(|&(_, value)| value)
+    }
+
+    /// Observations thinned to at most `max`, oldest first
+    ///
+    /// Every k-th observation back from the most recent, with the smallest
+    /// k that fits: the same span sampled at a coarser interval.
+    pub fn thinned(&self, max: usize) -> impl Iterator<Item = T> + '_ {
+        let step = self.closes.len().div_ceil(max.max(1)).max(1);
+        self.closes
+            .iter()
+            .skip(self.closes.len().saturating_sub(1) % step)
+            .step_by(step)
+            .map(|&(_, value)| value)
+    }
+
+    /// Most recent observation
+    pub fn latest(&self) -> Option<T> {
+        self.closes.back().map(|&(_, value)| value)
+    }
+
+    /// Pairs of consecutive observations (previous, current)
+    ///
+    /// With `GapPolicy::Skip`, pairs spanning empty buckets are left out.
+    pub fn consecutive(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.closes
+            .iter()
+            .zip(self.closes.iter().skip(1))
+            .filter(|((prev_ts, _), (ts, _))| self.contiguous(*prev_ts, *ts))
+            .map(|(&(_, prev), &(_, curr))| (prev, curr))
+    }
+
+    /// Pairs of consecutive observations with the time between them
+    ///
+    /// (elapsed nanoseconds, previous, current), filtered like
+    /// `consecutive`. Elapsed times are between bucket starts when
+    /// sampling by time.
+    pub fn consecutive_timed(&self) -> impl Iterator<Item = (u64, T, T)> + '_ {
+        self.closes
+            .iter()
+            .zip(self.closes.iter().skip(1))
+            .filter(|((prev_ts, _), (ts, _))| self.contiguous(*prev_ts, *ts))
+            .map(|(&(prev_ts, prev), &(ts, curr))| (ts.saturating_sub(prev_ts), prev, curr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3_600_000_000_000;
+
+    #[test]
+    fn test_hourly_closes() {
+        let mut window = SampledWindow::with_duration(24 * HOUR, SamplingConfig::hourly());
+
+        // Many ticks per hour: only the last one of each hour counts
+        for hour in 0..5 {
+            for i in 0..10 {
+                window.record(hour * HOUR + i * 1_000_000_000, (hour * 100 + i) as f64);
+            }
+        }
+
+        // The fifth hour is still open
+        assert_eq!(
+            window.values().collect::<Vec<_>>(),
+            [9.0, 109.0, 209.0, 309.0]
+        );
+
+        // Late tick for a closed hour is ignored
+        window.record(2 * HOUR, -1.0);
+        window.record(5 * HOUR, 500.0);
+        assert_eq!(window.len(), 5);
+        assert_eq!(window.latest(), Some(409.0));
+    }
+
+    #[test]
+    fn test_window_is_wall_clock_duration() {
+        let mut window = SampledWindow::with_duration(24 * HOUR, SamplingConfig::hourly());
+
+        for hour in 0..48 {
+            window.record(hour * HOUR, hour as f64);
+        }
+        assert_eq!(window.len(), 24);
+        assert_eq!(window.values().next(), Some(23.0));
+
+        // A weekend without ticks expires the whole window
+        window.record(100 * HOUR, 100.0);
+        window.record(101 * HOUR, 101.0);
+        assert_eq!(window.values().collect::<Vec<_>>(), [100.0]);
+    }
+
+    #[test]
+    fn test_gap_policy() {
+        let bridge = SamplingConfig::hourly();
+        let skip = bridge.with_gap_policy(GapPolicy::Skip);
+
+        let mut bridged = SampledWindow::with_duration(100 * HOUR, bridge);
+        let mut skipped = SampledWindow::with_duration(100 * HOUR, skip);
+        for hour in [0, 1, 2, 50, 51, 52] {
+            bridged.record(hour * HOUR, hour as f64);
+            skipped.record(hour * HOUR, hour as f64);
+        }
+
+        assert_eq!(bridged.consecutive().count(), 4);
+        assert_eq!(
+            skipped.consecutive().collect::<Vec<_>>(),
+            [(0.0, 1.0), (1.0, 2.0), (50.0, 51.0)]
+        );
+    }
+
+    #[test]
+    fn test_count_window() {
+        let mut window = SampledWindow::with_capacity(3);
+        for i in 0..5 {
+            window.record(0, i);
+        }
+        assert_eq!(window.values().collect::<Vec<_>>(), [2, 3, 4]);
+
+        let mut ticks = SampledWindow::with_duration(HOUR, SamplingConfig::every_tick());
+        ticks.record(0, 1);
+        ticks.record(1, 2);
+        assert_eq!(ticks.len(), 2);
+        ticks.record(HOUR, 3);
+        assert_eq!(ticks.values().collect::<Vec<_>>(), [2, 3]);
+    }
+
+    #[test]
+    fn test_pair_events_track_consecutive() {
+        let sampling = SamplingConfig::hourly().with_gap_policy(GapPolicy::Skip);
+        let mut window = SampledWindow::with_duration(10 * HOUR, sampling);
+        let mut pairs: Vec<(u64, u64)> = Vec::new();
+
+        for hour in (0..40).filter(|h| !(15..18).contains(h)) {
+            window.record_with(hour * HOUR, hour, |event| match event {
+                PairEvent::Added(prev, curr) => pairs.push((prev, curr)),
+                PairEvent::Removed(prev, curr) => {
+                    let pos = pairs.iter().position(|&p| p == (prev, curr)).unwrap();
+                    pairs.remove(pos);
+                }
+            });
+            assert_eq!(pairs, window.consecutive().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_thinned() {
+        let mut window = SampledWindow::with_capacity(10);
+        for i in 0..10 {
+            window.record(0, i);
+        }
+
+        // Every third value, ending at the latest
+        assert_eq!(window.thinned(4).collect::<Vec<_>>(), [0, 3, 6, 9]);
+        assert_eq!(window.thinned(3).collect::<Vec<_>>(), [1, 5, 9]);
+        assert_eq!(window.thinned(10).count(), 10);
+        assert_eq!(SampledWindow::<u64>::with_capacity(4).thinned(2).count(), 0);
+    }
+}
@@ -28,9 +28,12 @@
 //! println!("Spark Spread: €{:.2}/MWh", spread); // €63.84/MWh
 //! ```
 
-use crate::hedging::{HedgeRecommendation, Urgency};
+use crate::hedging::{
+    EngleGranger, HedgeRecommendation, SampledWindow, SamplingConfig, StationarityReport, Urgency,
+};
 use crate::market_data::{OrderBook, Side};
-use crate::utils::get_timestamp_ns;
+use crate::utils::{SharedClock, SystemClock};
+use parking_lot::RwLock;
 use std::sync::atomic::{AtomicI64, Ordering};
 
 /// Spark spread hedging strategy for gas-fired power plants
@@ -69,9 +72,18 @@ pub struct SparkSpreadHedge {
 
     /// Hedge threshold (only rehedge if spread changes by this much)
     rehedge_threshold_bps: i64,
+
+    /// Power, gas and CO2 prices for the cointegration test
+    price_history: RwLock<SampledWindow<(f64, f64, f64)>>,
+
+    /// Time source for recommendation timestamps
+    clock: SharedClock,
 }
 
 impl SparkSpreadHedge {
+    /// Prices kept by default, one per `add_prices` call
+    pub const DEFAULT_HISTORY: usize = 720;
+
     /// Create new spark spread hedging strategy
     ///
     /// # Arguments
@@ -103,7 +115,64 @@ impl SparkSpreadHedge {
             co2_hedge: AtomicI64::new(0),
             avg_spread: AtomicI64::new((target_spread * 10000.0) as i64),
             rehedge_threshold_bps: 500, // 5%
+            price_history: RwLock::new(SampledWindow::with_capacity(Self::DEFAULT_HISTORY)),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Use a custom time source (e.g. a simulated clock for replay)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sample prices into time buckets over `window_hours` hours
+    ///
+    /// Replaces any prices recorded so far.
+    pub fn with_sampling(self, window_hours: usize, sampling: SamplingConfig) -> Self {
+        let window_ns = window_hours as u64 * 3600 * 1_000_000_000;
+        *self.price_history.write() = SampledWindow::with_duration(window_ns, sampling);
+        self
+    }
+
+    /// Record power, gas and CO2 prices observed now
+    pub fn add_prices(&self, power_price: f64, gas_price: f64, co2_price: f64) {
+        self.add_prices_at(self.clock.now_ns(), power_price, gas_price, co2_price);
+    }
+
+    /// Record power, gas and CO2 prices observed at `timestamp_ns`
+    pub fn add_prices_at(
+        &self,
+        timestamp_ns: u64,
+        power_price: f64,
+        gas_price: f64,
+        co2_price: f64,
+    ) {
+        self.price_history
+            .write()
+            .record(timestamp_ns, (power_price, gas_price, co2_price));
+    }
+
+    /// Engle-Granger test of power against gas and CO2 price levels
+    ///
+    /// Whether the spread mean-reverts, with the fitted coefficients as
+    /// the market-implied heat rate and emission cost. Histories longer
+    /// than `StationarityReport::MAX_OBSERVATIONS` are thinned. None with
+    /// fewer than `AdfTest::MIN_OBSERVATIONS` prices.
+    ///
+    /// # Performance
+    /// O(n · lags), n bounded: cold path.
+    pub fn cointegration(&self) -> Option<EngleGranger> {
+        let history = self.price_history.read();
+        let prices = history.thinned(StationarityReport::MAX_OBSERVATIONS);
+        let (mut power, mut gas, mut co2) = (Vec::new(), Vec::new(), Vec::new());
+        for (p, g, c) in prices {
+            power.push(p);
+            gas.push(g);
+            co2.push(c);
         }
+        drop(history);
+        EngleGranger::new(&power, &[&gas, &co2])
     }
 
     /// Calculate spark spread
@@ -238,7 +307,7 @@ impl SparkSpreadHedge {
             Urgency::Normal
         };
 
-        let timestamp = get_timestamp_ns();
+        let timestamp = self.clock.now_ns();
 
         // Power recommendation (SELL)
         let power_rec: HedgeRecommendation = HedgeRecommendation::new(
@@ -395,6 +464,7 @@ pub struct SparkSpreadPositions {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hedging::test_rng::TestRng;
 
     #[test]
     fn test_spark_spread_calculation() {
@@ -471,6 +541,37 @@ mod tests {
         assert!(pnl.abs() < 2000.0); // Should be close to zero
     }
 
+    #[test]
+    fn test_cointegration() {
+        const HOUR: u64 = 3_600_000_000_000;
+        let hedge = SparkSpreadHedge::new(100.0, 2.0, 0.202, 50.0)
+            .with_sampling(24 * 365, SamplingConfig::hourly());
+
+        // Deterministic uniform draws in [-0.5, 0.5)
+        let mut rng = TestRng::new(7);
+        let mut uniform = || rng.uniform() - 0.5;
+
+        // Gas and CO2 random walks; power = gas / 2 + 0.202 CO2 + stationary margin
+        let (mut gas, mut co2) = (40.0, 80.0);
+        for hour in 0..3000 {
+            gas += uniform();
+            co2 += uniform();
+            let power = 30.0 + gas / 2.0 + 0.202 * co2 + uniform();
+            hedge.add_prices_at(hour * HOUR, power, gas, co2);
+
+            if hour == 10 {
+                assert!(hedge.cointegration().is_none());
+            }
+        }
+
+        // 2998 hourly closes, thinned
+        let test = hedge.cointegration().unwrap();
+        assert!(test.observations <= StationarityReport::MAX_OBSERVATIONS);
+        assert!(test.is_cointegrated(0.01));
+        assert!((test.coefficients[0] - 0.5).abs() < 0.05);
+        assert!((test.coefficients[1] - 0.202).abs() < 0.05);
+    }
+
     #[cfg(test)]
     mod integration_tests {
         use super::*;
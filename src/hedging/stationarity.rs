@@ -0,0 +1,624 @@
+//! Stationarity and cointegration tests for mean reversion and spreads
+//!
+//! Augmented Dickey-Fuller (unit root null), KPSS (stationarity null) and
+//! Engle-Granger (no cointegration null). p-values interpolate tabulated
+//! critical values; at the ends of a table they are bounds (e.g. 0.01
+//! means ≤ 0.01).
+
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
+/// Deterministic terms of a test regression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Deterministic {
+    /// No constant (series fluctuates around zero, e.g. residuals)
+    NoConstant,
+
+    /// Constant: stationary around a level
+    #[default]
+    Constant,
+
+    /// Constant and linear trend: stationary around a trend
+    ConstantTrend,
+}
+
+impl Deterministic {
+    /// Regressors added by the deterministic terms
+    fn terms(self) -> usize {
+        match self {
+            Self::NoConstant => 0,
+            Self::Constant => 1,
+            Self::ConstantTrend => 2,
+        }
+    }
+}
+
+/// Dickey-Fuller t distribution quantiles, Fuller (1976) via Hamilton
+/// (1994) Table B.6, at probabilities `DF_PROBABILITIES`
+///
+/// Rows: sample sizes 25, 50, 100, 250, 500, ∞.
+const DF_SAMPLE_SIZES: [f64; 6] = [25.0, 50.0, 100.0, 250.0, 500.0, f64::INFINITY];
+const DF_PROBABILITIES: [f64; 8] = [0.01, 0.025, 0.05, 0.10, 0.90, 0.95, 0.975, 0.99];
+const DF_NO_CONSTANT: [[f64; 8]; 6] = [
+    [-2.66, -2.26, -1.95, -1.60, 0.92, 1.33, 1.70, 2.16],
+    [-2.62, -2.25, -1.95, -1.61, 0.91, 1.31, 1.66, 2.08],
+    [-2.60, -2.24, -1.95, -1.61, 0.90, 1.29, 1.64, 2.03],
+    [-2.58, -2.23, -1.95, -1.62, 0.89, 1.29, 1.63, 2.01],
+    [-2.58, -2.23, -1.95, -1.62, 0.89, 1.28, 1.62, 2.00],
+    [-2.58, -2.23, -1.95, -1.62, 0.89, 1.28, 1.62, 2.00],
+];
+// -3.14 is a quantile, not π
+#[allow(clippy::approx_constant)]
+const DF_CONSTANT: [[f64; 8]; 6] = [
+    [-3.75, -3.33, -3.00, -2.63, -0.37, 0.00, 0.34, 0.72],
+    [-3.58, -3.22, -2.93, -2.60, -0.40, -0.03, 0.29, 0.66],
+    [-3.51, -3.17, -2.89, -2.58, -0.42, -0.05, 0.26, 0.63],
+    [-3.46, -3.14, -2.88, -2.57, -0.42, -0.06, 0.24, 0.62],
+    [-3.44, -3.13, -2.87, -2.57, -0.43, -0.07, 0.24, 0.61],
+    [-3.43, -3.12, -2.86, -2.57, -0.44, -0.07, 0.23, 0.60],
+];
+const DF_CONSTANT_TREND: [[f64; 8]; 6] = [
+    [-4.38, -3.95, -3.60, -3.24, -1.14, -0.80, -0.50, -0.15],
+    [-4.15, -3.80, -3.50, -3.18, -1.19, -0.87, -0.58, -0.24],
+    [-4.04, -3.73, -3.45, -3.15, -1.22, -0.90, -0.62, -0.28],
+    [-3.99, -3.69, -3.43, -3.13, -1.23, -0.92, -0.64, -0.31],
+    [-3.98, -3.68, -3.42, -3.13, -1.24, -0.93, -0.65, -0.32],
+    [-3.96, -3.66, -3.41, -3.12, -1.25, -0.94, -0.66, -0.33],
+];
+
+/// KPSS upper-tail critical values, Kwiatkowski et al. (1992) Table 1,
+/// at probabilities 10%, 5%, 2.5%, 1%
+const KPSS_PROBABILITIES: [f64; 4] = [0.10, 0.05, 0.025, 0.01];
+const KPSS_LEVEL: [f64; 4] = [0.347, 0.463, 0.574, 0.739];
+const KPSS_TREND: [f64; 4] = [0.119, 0.146, 0.176, 0.216];
+
+/// Engle-Granger response surfaces c(T) = β∞ + β₁/T + β₂/T², MacKinnon
+/// (1991) Table 1 with constant, at 1%, 5%, 10%
+///
+/// Rows: 2, 3, 4 variables (1 to 3 regressors).
+const EG_PROBABILITIES: [f64; 3] = [0.01, 0.05, 0.10];
+const EG_RESPONSE_SURFACES: [[[f64; 3]; 3]; 3] = [
+    [
+        [-3.9001, -10.534, -30.03],
+        [-3.3377, -5.967, -8.98],
+        [-3.0462, -4.069, -5.73],
+    ],
+    [
+        [-4.2981, -13.790, -46.37],
+        [-3.7429, -8.352, -13.41],
+        [-3.4518, -6.241, -2.79],
+    ],
+    [
+        [-4.6493, -17.188, -59.20],
+        [-4.1000, -10.745, -21.57],
+        [-3.8110, -8.317, -5.19],
+    ],
+];
+
+/// Linear interpolation of a probability between tabulated quantiles
+///
+/// `quantiles` increase; probabilities beyond the table are clamped.
+fn interpolate_p(statistic: f64, quantiles: &[f64], probabilities: &[f64]) -> f64 {
+    let last = quantiles.len() - 1;
+    if statistic <= quantiles[0] {
+        return probabilities[0];
+    }
+    if statistic >= quantiles[last] {
+        return probabilities[last];
+    }
+
+    let i = quantiles
+        .iter()
+        .position(|&q| q > statistic)
+        .unwrap_or(last);
+    let w = (statistic - quantiles[i - 1]) / (quantiles[i] - quantiles[i - 1]);
+    probabilities[i - 1] + w * (probabilities[i] - probabilities[i - 1])
+}
+
+/// Dickey-Fuller quantiles at `observations`, interpolated in 1/n
+fn df_quantiles(deterministic: Deterministic, observations: usize) -> [f64; 8] {
+    let table = match deterministic {
+        Deterministic::NoConstant => &DF_NO_CONSTANT,
+        Deterministic::Constant => &DF_CONSTANT,
+        Deterministic::ConstantTrend => &DF_CONSTANT_TREND,
+    };
+
+    let n = (observations as f64).max(DF_SAMPLE_SIZES[0]);
+    let i = DF_SAMPLE_SIZES
+        .iter()
+        .position(|&size| size >= n)
+        .unwrap_or(5);
+    if i == 0 {
+        return table[0];
+    }
+
+    let (lo, hi) = (1.0 / DF_SAMPLE_SIZES[i - 1], 1.0 / DF_SAMPLE_SIZES[i]);
+    let w = (lo - 1.0 / n) / (lo - hi);
+    std::array::from_fn(|j| table[i - 1][j] + w * (table[i][j] - table[i - 1][j]))
+}
+
+/// Least squares fit
+struct Regression {
+    coefficients: DVector<f64>,
+    standard_errors: DVector<f64>,
+    residuals: DVector<f64>,
+}
+
+impl Regression {
+    /// Fit y = Xβ + ε; None if X is rank deficient or has no residual df
+    fn fit(y: &DVector<f64>, x: &DMatrix<f64>) -> Option<Self> {
+        let (rows, k) = x.shape();
+        if rows <= k {
+            return None;
+        }
+
+        let cholesky = (x.transpose() * x).cholesky()?;
+        let coefficients = cholesky.solve(&(x.transpose() * y));
+        let residuals = y - x * &coefficients;
+        let variance = residuals.norm_squared() / (rows - k) as f64;
+        let inverse = cholesky.inverse();
+        let standard_errors = DVector::from_fn(k, |j, _| (variance * inverse[(j, j)]).sqrt());
+
+        Some(Self {
+            coefficients,
+            standard_errors,
+            residuals,
+        })
+    }
+
+    /// Sum of squared residuals
+    fn sse(&self) -> f64 {
+        self.residuals.norm_squared()
+    }
+}
+
+/// Augmented Dickey-Fuller unit root test
+///
+/// Δy_t = [α + δt] + γ y_{t-1} + Σ φ_i Δy_{t-i} + ε_t; the null γ = 0 is a
+/// unit root (no mean reversion).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdfTest {
+    /// t statistic of γ
+    pub statistic: f64,
+
+    /// Probability of a statistic this low under a unit root, in [0.01, 0.99]
+    pub p_value: f64,
+
+    /// Critical values at 1%, 5% and 10%
+    pub critical_values: [f64; 3],
+
+    /// Lagged differences in the regression
+    pub lags: usize,
+
+    /// Regression observations
+    pub observations: usize,
+
+    /// Deterministic terms
+    pub deterministic: Deterministic,
+}
+
+impl AdfTest {
+    /// Series length required
+    pub const MIN_OBSERVATIONS: usize = 20;
+
+    /// Test with the lag count minimising AIC, up to ⌊12 (n/100)^¼⌋
+    ///
+    /// None for a short or constant series.
+    pub fn new(series: &[f64], deterministic: Deterministic) -> Option<Self> {
+        if series.len() < Self::MIN_OBSERVATIONS {
+            return None;
+        }
+
+        let max_lags = Self::max_lags(series.len(), deterministic);
+        // Compare lags over the same sample
+        let lags = (0..=max_lags)
+            .filter_map(|lags| {
+                let (y, x) = Self::design(series, deterministic, lags, max_lags);
+                let fit = Regression::fit(&y, &x)?;
+                let n = y.len() as f64;
+                Some((lags, n * (fit.sse() / n).ln() + 2.0 * x.ncols() as f64))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))?
+            .0;
+
+        Self::with_lags(series, deterministic, lags)
+    }
+
+    /// Test with a fixed number of lagged differences
+    pub fn with_lags(series: &[f64], deterministic: Deterministic, lags: usize) -> Option<Self> {
+        let statistic = Self::statistic(series, deterministic, lags)?;
+        let observations = series.len() - 1 - lags;
+        let quantiles = df_quantiles(deterministic, observations);
+
+        Some(Self {
+            statistic,
+            p_value: interpolate_p(statistic, &quantiles, &DF_PROBABILITIES),
+            critical_values: [quantiles[0], quantiles[2], quantiles[3]],
+            lags,
+            observations,
+            deterministic,
+        })
+    }
+
+    /// Check whether the unit root is rejected at `significance`
+    pub fn is_stationary(&self, significance: f64) -> bool {
+        self.p_value <= significance
+    }
+
+    /// Schwert's maximum lag, bounded by the degrees of freedom
+    fn max_lags(len: usize, deterministic: Deterministic) -> usize {
+        let schwert = (12.0 * (len as f64 / 100.0).powf(0.25)) as usize;
+        let dof_bound = (len - 1).saturating_sub(deterministic.terms() + 2) / 2;
+        schwert.min(dof_bound)
+    }
+
+    /// t statistic of γ, or None if the regression is degenerate
+    fn statistic(series: &[f64], deterministic: Deterministic, lags: usize) -> Option<f64> {
+        if series.len() < lags + 2 {
+            return None;
+        }
+
+        let (y, x) = Self::design(series, deterministic, lags, lags);
+        let fit = Regression::fit(&y, &x)?;
+        let se = fit.standard_errors[0];
+        (se > 0.0 && se.is_finite()).then(|| fit.coefficients[0] / se)
+    }
+
+    /// Regression of Δy on [y_{t-1}, Δy_{t-1..t-lags}, deterministic],
+    /// starting after `skip` differences
+    fn design(
+        series: &[f64],
+        deterministic: Deterministic,
+        lags: usize,
+        skip: usize,
+    ) -> (DVector<f64>, DMatrix<f64>) {
+        let diffs: Vec<f64> = series.windows(2).map(|w| w[1] - w[0]).collect();
+        let rows = diffs.len() - skip;
+        let k = 1 + lags + deterministic.terms();
+
+        let y = DVector::from_fn(rows, |r, _| diffs[skip + r]);
+        let x = DMatrix::from_fn(rows, k, |r, c| {
+            let t = skip + r;
+            match c {
+                0 => series[t],
+                c if c <= lags => diffs[t - c],
+                c if c == lags + 1 => 1.0,
+                _ => t as f64,
+            }
+        });
+        (y, x)
+    }
+}
+
+/// KPSS stationarity test
+///
+/// η = Σ S_t² / (n² σ̂²), with S_t the partial sums of the residuals from
+/// the level (or trend) and σ̂² their Bartlett long-run variance. The null
+/// is stationarity, so it complements the ADF test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KpssTest {
+    /// η statistic
+    pub statistic: f64,
+
+    /// Probability of a statistic this high if stationary, in [0.01, 0.10]
+    pub p_value: f64,
+
+    /// Critical values at 1%, 5% and 10%
+    pub critical_values: [f64; 3],
+
+    /// Bartlett window lags, ⌊12 (n/100)^¼⌋
+    pub lags: usize,
+
+    /// Series length
+    pub observations: usize,
+
+    /// Level (Constant) or trend (ConstantTrend) stationarity
+    pub deterministic: Deterministic,
+}
+
+impl KpssTest {
+    /// Series length required
+    pub const MIN_OBSERVATIONS: usize = 20;
+
+    /// Test level or trend stationarity
+    ///
+    /// None for a short or constant series, or `NoConstant`.
+    pub fn new(series: &[f64], deterministic: Deterministic) -> Option<Self> {
+        let n = series.len();
+        if n < Self::MIN_OBSERVATIONS {
+            return None;
+        }
+
+        let critical = match deterministic {
+            Deterministic::NoConstant => return None,
+            Deterministic::Constant => &KPSS_LEVEL,
+            Deterministic::ConstantTrend => &KPSS_TREND,
+        };
+
+        let y = DVector::from_column_slice(series);
+        let x = DMatrix::from_fn(n, deterministic.terms(), |t, c| match c {
+            0 => 1.0,
+            _ => t as f64,
+        });
+        let residuals = Regression::fit(&y, &x)?.residuals;
+
+        let lags = ((12.0 * (n as f64 / 100.0).powf(0.25)) as usize).min(n - 1);
+        let mut long_run = residuals.norm_squared() / n as f64;
+        for s in 1..=lags {
+            let autocovariance: f64 = (s..n).map(|t| residuals[t] * residuals[t - s]).sum();
+            long_run += 2.0 * (1.0 - s as f64 / (lags + 1) as f64) * autocovariance / n as f64;
+        }
+        if long_run <= 0.0 {
+            return None;
+        }
+
+        let mut partial = 0.0;
+        let sum_squares: f64 = residuals
+            .iter()
+            .map(|e| {
+                partial += e;
+                partial * partial
+            })
+            .sum();
+        let statistic = sum_squares / (n as f64 * n as f64 * long_run);
+
+        Some(Self {
+            statistic,
+            p_value: interpolate_p(statistic, critical, &KPSS_PROBABILITIES),
+            critical_values: [critical[3], critical[1], critical[0]],
+            lags,
+            observations: n,
+            deterministic,
+        })
+    }
+
+    /// Check whether stationarity is not rejected at `significance`
+    pub fn is_stationary(&self, significance: f64) -> bool {
+        self.p_value >= significance
+    }
+}
+
+/// Engle-Granger two-step cointegration test
+///
+/// Regresses y on x₁..x_k with a constant, then runs an ADF test (no
+/// constant, AIC lags) on the residual spread. The null is no
+/// cointegration: the spread has a unit root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngleGranger {
+    /// ADF t statistic of the residual spread
+    pub statistic: f64,
+
+    /// Probability of a statistic this low without cointegration, in
+    /// [0.01, 0.10]
+    pub p_value: f64,
+
+    /// Critical values at 1%, 5% and 10%
+    pub critical_values: [f64; 3],
+
+    /// Constant of the cointegrating regression
+    pub intercept: f64,
+
+    /// Coefficient per regressor: y ≈ intercept + Σ βᵢ xᵢ
+    pub coefficients: Vec<f64>,
+
+    /// Lagged differences in the residual ADF regression
+    pub lags: usize,
+
+    /// Series length
+    pub observations: usize,
+}
+
+impl EngleGranger {
+    /// Regressors supported by the tabulated response surfaces
+    pub const MAX_REGRESSORS: usize = 3;
+
+    /// Test whether `y` is cointegrated with `regressors`
+    ///
+    /// E.g. spot on futures, or power on gas and CO2. None with 0 or more
+    /// than `MAX_REGRESSORS` regressors, series of unequal or too short
+    /// length, or collinear regressors.
+    pub fn new(y: &[f64], regressors: &[&[f64]]) -> Option<Self> {
+        let n = y.len();
+        let k = regressors.len();
+        let valid = (1..=Self::MAX_REGRESSORS).contains(&k)
+            && n >= AdfTest::MIN_OBSERVATIONS
+            && regressors.iter().all(|x| x.len() == n);
+        if !valid {
+            return None;
+        }
+
+        let x = DMatrix::from_fn(n, k + 1, |t, c| match c {
+            0 => 1.0,
+            c => regressors[c - 1][t],
+        });
+        let fit = Regression::fit(&DVector::from_column_slice(y), &x)?;
+        let spread: Vec<f64> = fit.residuals.iter().copied().collect();
+        let adf = AdfTest::new(&spread, Deterministic::NoConstant)?;
+
+        let t = n as f64;
+        let critical_values =
+            EG_RESPONSE_SURFACES[k - 1].map(|[inf, b1, b2]| inf + b1 / t + b2 / (t * t));
+
+        Some(Self {
+            statistic: adf.statistic,
+            p_value: interpolate_p(adf.statistic, &critical_values, &EG_PROBABILITIES),
+            critical_values,
+            intercept: fit.coefficients[0],
+            coefficients: fit.coefficients.iter().skip(1).copied().collect(),
+            lags: adf.lags,
+            observations: n,
+        })
+    }
+
+    /// Check whether no cointegration is rejected at `significance`
+    pub fn is_cointegrated(&self, significance: f64) -> bool {
+        self.p_value <= significance
+    }
+}
+
+/// Stationarity and cointegration tests of a hedged exposure
+///
+/// Each test is None when disabled or without enough data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StationarityReport {
+    /// ADF test of the price series (unit root null)
+    pub adf: Option<AdfTest>,
+
+    /// KPSS test of the price series (stationarity null)
+    pub kpss: Option<KpssTest>,
+
+    /// Engle-Granger test of spot against futures
+    pub cointegration: Option<EngleGranger>,
+}
+
+impl StationarityReport {
+    /// Series length tested by the strategies; longer windows are thinned
+    ///
+    /// Bounds the AIC lag search when sampling every tick.
+    pub const MAX_OBSERVATIONS: usize = 2048;
+
+    /// ADF and KPSS tests of a price series
+    pub fn of_series(series: &[f64], deterministic: Deterministic) -> Self {
+        Self {
+            adf: AdfTest::new(series, deterministic),
+            kpss: KpssTest::new(series, deterministic),
+            cointegration: None,
+        }
+    }
+
+    /// Check whether both tests agree on stationarity at `significance`
+    ///
+    /// False if either test is missing.
+    pub fn is_stationary(&self, significance: f64) -> bool {
+        self.adf.is_some_and(|adf| adf.is_stationary(significance))
+            && self
+                .kpss
+                .is_some_and(|kpss| kpss.is_stationary(significance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hedging::test_rng::TestRng;
+
+    fn ar1(phi: f64, n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = TestRng::new(seed);
+        let mut y = 0.0;
+        (0..n)
+            .map(|_| {
+                y = phi * y + rng.normal();
+                45.0 + y
+            })
+            .collect()
+    }
+
+    fn random_walk(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = TestRng::new(seed);
+        let mut y = 45.0;
+        (0..n)
+            .map(|_| {
+                y += rng.normal();
+                y
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_p_value_interpolation() {
+        let quantiles = [-3.0, -2.0, -1.0];
+        let probabilities = [0.01, 0.05, 0.10];
+        assert_eq!(interpolate_p(-4.0, &quantiles, &probabilities), 0.01);
+        assert_eq!(interpolate_p(0.0, &quantiles, &probabilities), 0.10);
+        assert!((interpolate_p(-2.5, &quantiles, &probabilities) - 0.03).abs() < 1e-12);
+
+        // Asymptotic row at large samples, first row below 25
+        assert!((df_quantiles(Deterministic::Constant, 1_000_000)[2] + 2.86).abs() < 1e-4);
+        assert_eq!(df_quantiles(Deterministic::Constant, 10)[2], -3.00);
+        let q = df_quantiles(Deterministic::Constant, 75)[2];
+        assert!(q < -2.89 && q > -2.93);
+    }
+
+    #[test]
+    fn test_adf() {
+        let stationary = AdfTest::new(&ar1(0.5, 500, 3), Deterministic::Constant).unwrap();
+        assert!(stationary.is_stationary(0.01));
+        assert!(stationary.statistic < stationary.critical_values[0]);
+        assert!(stationary.lags <= 12);
+
+        let walk = AdfTest::new(&random_walk(500, 5), Deterministic::Constant).unwrap();
+        assert!(!walk.is_stationary(0.05));
+        assert!(walk.p_value > 0.05);
+
+        // Trend stationary: a unit root around a level, not around the trend
+        let trending: Vec<f64> = ar1(0.5, 500, 9)
+            .iter()
+            .enumerate()
+            .map(|(t, y)| y + 0.05 * t as f64)
+            .collect();
+        assert!(
+            AdfTest::new(&trending, Deterministic::ConstantTrend)
+                .unwrap()
+                .is_stationary(0.01)
+        );
+
+        assert!(AdfTest::new(&[45.0; 10], Deterministic::Constant).is_none());
+        assert!(AdfTest::new(&[45.0; 50], Deterministic::Constant).is_none());
+    }
+
+    #[test]
+    fn test_kpss() {
+        let stationary = KpssTest::new(&ar1(0.5, 500, 3), Deterministic::Constant).unwrap();
+        assert!(stationary.is_stationary(0.05));
+        assert_eq!(stationary.p_value, 0.10);
+
+        let walk = KpssTest::new(&random_walk(500, 5), Deterministic::Constant).unwrap();
+        assert!(!walk.is_stationary(0.05));
+        assert!(walk.statistic > walk.critical_values[1]);
+
+        assert!(KpssTest::new(&ar1(0.5, 500, 3), Deterministic::NoConstant).is_none());
+
+        let report = StationarityReport::of_series(&ar1(0.5, 500, 3), Deterministic::Constant);
+        assert!(report.is_stationary(0.05));
+        let report = StationarityReport::of_series(&random_walk(500, 5), Deterministic::Constant);
+        assert!(!report.is_stationary(0.05));
+    }
+
+    #[test]
+    fn test_engle_granger() {
+        // Futures follow a random walk; spot = 2 + 0.9 F + stationary basis
+        let futures = random_walk(500, 7);
+        let basis = ar1(0.6, 500, 11);
+        let spot: Vec<f64> = futures
+            .iter()
+            .zip(&basis)
+            .map(|(f, b)| 2.0 + 0.9 * f + (b - 45.0))
+            .collect();
+
+        let test = EngleGranger::new(&spot, &[&futures]).unwrap();
+        assert!(test.is_cointegrated(0.01));
+        assert!((test.coefficients[0] - 0.9).abs() < 0.05);
+        assert!(test.critical_values[0] < test.critical_values[1]);
+
+        // Power against gas and CO2
+        let gas = random_walk(500, 13);
+        let co2 = random_walk(500, 17);
+        let power: Vec<f64> = gas
+            .iter()
+            .zip(&co2)
+            .zip(&basis)
+            .map(|((g, c), b)| 2.0 * g + 0.4 * c + (b - 45.0))
+            .collect();
+        let test = EngleGranger::new(&power, &[&gas, &co2]).unwrap();
+        assert!(test.is_cointegrated(0.01));
+        assert_eq!(test.coefficients.len(), 2);
+
+        // Independent random walks
+        let unrelated = random_walk(500, 19);
+        let test = EngleGranger::new(&unrelated, &[&futures]).unwrap();
+        assert!(!test.is_cointegrated(0.05));
+
+        assert!(EngleGranger::new(&spot, &[]).is_none());
+        assert!(EngleGranger::new(&spot, &[&futures[..100]]).is_none());
+    }
+}
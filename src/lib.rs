@@ -31,7 +31,7 @@ pub mod utils;
 pub use hedging::{
     DeltaHedge, HedgeConfig, HedgeEngine, HedgeRecommendation, MVHRStrategy, MeanReversionHedge,
 };
-pub use market_data::{MarketTick, OrderBook, Side};
+pub use market_data::{Instrument, InstrumentRegistry, MarketTick, OrderBook, Side};
 pub use network::{NetworkConfig, TcpMarketDataFeed, TcpOrderSubmitter};
 pub use strategy::HedgingStrategy;
 pub use utils::{LockFreeQueue, MPSCQueue, Metrics, get_timestamp_ns};
@@ -56,4 +56,10 @@ pub enum Error {
 
     #[error("Network error: {0}")]
     Network(String),
+
+    #[error("Unhealthy market data for symbol {symbol_id}: {issue}")]
+    UnhealthyBook {
+        symbol_id: u8,
+        issue: market_data::BookIssue,
+    },
 }
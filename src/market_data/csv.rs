@@ -0,0 +1,612 @@
+//! Streaming CSV tick reader and writer
+
+use crate::Error;
+use crate::market_data::{
+    Instrument, InstrumentRegistry, MarketTick, Side, TickEvent, to_fixed_price,
+};
+use ahash::AHashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Tick field held by a CSV column
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// Exchange timestamp (nanoseconds)
+    Timestamp,
+    /// Symbol ID or instrument name
+    Symbol,
+    /// Price (decimal)
+    Price,
+    /// Quantity (lots)
+    Quantity,
+    /// Side ("bid"/"ask", "b"/"a" or 0/1)
+    Side,
+    /// Event ("quote", "trade", "settlement", "clear", "halt" or 0-4)
+    Event,
+    /// Feed channel
+    Channel,
+    /// Feed sequence number
+    Sequence,
+    /// Column ignored on read, written empty
+    Skip,
+}
+
+impl CsvColumn {
+    /// Resolve a header name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "timestamp_ns" | "timestamp" | "ts" | "exchange_ns" => Some(CsvColumn::Timestamp),
+            "symbol_id" | "symbol" | "instrument" => Some(CsvColumn::Symbol),
+            "price" => Some(CsvColumn::Price),
+            "quantity" | "qty" | "size" => Some(CsvColumn::Quantity),
+            "side" => Some(CsvColumn::Side),
+            "event" | "type" => Some(CsvColumn::Event),
+            "channel" => Some(CsvColumn::Channel),
+            "sequence" | "seq" => Some(CsvColumn::Sequence),
+            _ => None,
+        }
+    }
+
+    /// Header name written by `TickCsvWriter`
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvColumn::Timestamp => "timestamp_ns",
+            CsvColumn::Symbol => "symbol_id",
+            CsvColumn::Price => "price",
+            CsvColumn::Quantity => "quantity",
+            CsvColumn::Side => "side",
+            CsvColumn::Event => "event",
+            CsvColumn::Channel => "channel",
+            CsvColumn::Sequence => "sequence",
+            CsvColumn::Skip => "",
+        }
+    }
+}
+
+/// Header handling
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum HeaderMode {
+    /// Treat the first line as a header if its timestamp field is not a number
+    #[default]
+    Detect,
+    /// First line is always a header
+    Present,
+    /// No header line
+    Absent,
+}
+
+/// CSV layout of a tick file
+///
+/// When a header is read, its column names replace `columns`; unknown
+/// names are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvFormat {
+    /// Column order
+    pub columns: Vec<CsvColumn>,
+
+    /// Field delimiter
+    pub delimiter: char,
+
+    /// Header handling
+    pub header: HeaderMode,
+}
+
+impl CsvFormat {
+    /// Create a format with the given column order
+    pub fn new(columns: Vec<CsvColumn>) -> Self {
+        Self {
+            columns,
+            ..Default::default()
+        }
+    }
+
+    /// Set the delimiter
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the header handling
+    pub fn with_header(mut self, header: HeaderMode) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+impl Default for CsvFormat {
+    /// `timestamp_ns,symbol_id,price,quantity,side` as in `data/sample_ticks.csv`
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                CsvColumn::Timestamp,
+                CsvColumn::Symbol,
+                CsvColumn::Price,
+                CsvColumn::Quantity,
+                CsvColumn::Side,
+            ],
+            delimiter: ',',
+            header: HeaderMode::Detect,
+        }
+    }
+}
+
+/// Streaming CSV tick reader
+///
+/// Yields one `Result<MarketTick>` per data line, reusing a single line
+/// buffer, so files of any size are read in constant memory. Errors name
+/// the source and line number; reading continues with the next line.
+pub struct TickCsvReader<R: BufRead> {
+    reader: R,
+    format: CsvFormat,
+    symbols: AHashMap<String, u8>,
+    instruments: Vec<Option<Instrument>>,
+    source: String,
+    line: String,
+    line_no: usize,
+}
+
+impl TickCsvReader<BufReader<File>> {
+    /// Open a CSV file with the default format
+    pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| Error::MarketData(format!("Failed to open {}: {}", path.display(), e)))?;
+
+        Ok(Self::new(BufReader::new(file)).with_source(&path.display().to_string()))
+    }
+}
+
+impl<R: BufRead> TickCsvReader<R> {
+    /// Create a reader with the default format
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            format: CsvFormat::default(),
+            symbols: AHashMap::new(),
+            instruments: vec![None; 256],
+            source: "csv".to_string(),
+            line: String::new(),
+            line_no: 0,
+        }
+    }
+
+    /// Set the CSV format
+    pub fn with_format(mut self, format: CsvFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Resolve instrument names in the symbol column through a registry
+    ///
+    /// Prices of registered instruments are converted with their own
+    /// `price_scale`.
+    pub fn with_registry(mut self, registry: &InstrumentRegistry) -> Self {
+        for instrument in registry.iter() {
+            self.symbols
+                .insert(instrument.name.clone(), instrument.symbol_id);
+            self.instruments[instrument.symbol_id as usize] = Some(instrument.clone());
+        }
+        self
+    }
+
+    /// Map a symbol name to an ID
+    pub fn with_symbol(mut self, name: &str, symbol_id: u8) -> Self {
+        self.symbols.insert(name.to_string(), symbol_id);
+        self
+    }
+
+    /// Set the source name used in error messages
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.to_string();
+        self
+    }
+
+    /// Get the active format (columns reflect the header once read)
+    pub fn format(&self) -> &CsvFormat {
+        &self.format
+    }
+
+    /// Number of lines read so far
+    pub fn line_number(&self) -> usize {
+        self.line_no
+    }
+
+    fn error(&self, message: String) -> Error {
+        Error::MarketData(format!("{}:{}: {}", self.source, self.line_no, message))
+    }
+
+    /// Check whether the first line is a header, adopting its columns
+    fn take_header(&mut self) -> bool {
+        let line = self.line.trim_end_matches(['\r', '\n']);
+        let fields: Vec<&str> = line.split(self.format.delimiter).collect();
+
+        let is_header = match self.format.header {
+            HeaderMode::Present => true,
+            HeaderMode::Absent => false,
+            HeaderMode::Detect => {
+                let ts = self
+                    .format
+                    .columns
+                    .iter()
+                    .position(|c| *c == CsvColumn::Timestamp);
+                match ts.and_then(|i| fields.get(i)) {
+                    Some(field) => field.trim().parse::<u64>().is_err(),
+                    None => fields.iter().any(|f| CsvColumn::from_name(f).is_some()),
+                }
+            }
+        };
+
+        if is_header {
+            self.format.columns = fields
+                .iter()
+                .map(|f| CsvColumn::from_name(f).unwrap_or(CsvColumn::Skip))
+                .collect();
+        }
+
+        is_header
+    }
+
+    fn parse_line(&self) -> crate::Result<MarketTick> {
+        let line = self.line.trim_end_matches(['\r', '\n']);
+        let mut tick = MarketTick::bid(0, 0.0, 0, 0);
+        let mut price = None;
+        let mut fields = line.split(self.format.delimiter);
+
+        for column in &self.format.columns {
+            let Some(field) = fields.next() else {
+                return Err(self.error(format!("missing {} column", column.name())));
+            };
+            let field = field.trim();
+
+            match column {
+                CsvColumn::Timestamp => tick.timestamp_ns = self.number(field, "timestamp")?,
+                CsvColumn::Symbol => {
+                    tick.symbol_id = match field.parse::<u8>() {
+                        Ok(id) => id,
+                        Err(_) => *self
+                            .symbols
+                            .get(field)
+                            .ok_or_else(|| self.error(format!("unknown symbol {:?}", field)))?,
+                    }
+                }
+                CsvColumn::Price => price = Some(self.number::<f64>(field, "price")?),
+                CsvColumn::Quantity => tick.quantity = self.number(field, "quantity")?,
+                CsvColumn::Side => {
+                    tick.side = match field.to_ascii_lowercase().as_str() {
+                        "bid" | "b" | "0" => Side::Bid as u8,
+                        "ask" | "a" | "1" => Side::Ask as u8,
+                        _ => return Err(self.error(format!("invalid side {:?}", field))),
+                    }
+                }
+                CsvColumn::Event => {
+                    let event = match field.to_ascii_lowercase().as_str() {
+                        "quote" => Some(TickEvent::Quote),
+                        "trade" => Some(TickEvent::Trade),
+                        "settlement" | "settle" => Some(TickEvent::Settlement),
+                        "clear" | "book_clear" => Some(TickEvent::BookClear),
+                        "halt" => Some(TickEvent::Halt),
+                        other => other.parse::<u8>().ok().and_then(TickEvent::from_u8),
+                    };
+                    tick.event = event
+                        .ok_or_else(|| self.error(format!("invalid event {:?}", field)))?
+                        as u8;
+                }
+                CsvColumn::Channel => tick.channel = self.number(field, "channel")?,
+                CsvColumn::Sequence => tick.sequence = self.number(field, "sequence")?,
+                CsvColumn::Skip => {}
+            }
+        }
+
+        // The symbol may follow the price column
+        if let Some(price) = price {
+            tick.price = match self.instruments[tick.symbol_id as usize] {
+                Some(ref instrument) => instrument.to_fixed_price(price),
+                None => to_fixed_price(price),
+            };
+        }
+
+        Ok(tick)
+    }
+
+    fn number<T: std::str::FromStr>(&self, field: &str, what: &str) -> crate::Result<T> {
+        field
+            .parse()
+            .map_err(|_| self.error(format!("invalid {} {:?}", what, field)))
+    }
+}
+
+impl<R: BufRead> Iterator for TickCsvReader<R> {
+    type Item = crate::Result<MarketTick>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.line_no += 1,
+                Err(e) => {
+                    self.line_no += 1;
+                    return Some(Err(self.error(format!("read error: {}", e))));
+                }
+            }
+
+            if self.line.trim().is_empty() {
+                continue;
+            }
+
+            if self.line_no == 1 && self.take_header() {
+                continue;
+            }
+
+            return Some(self.parse_line());
+        }
+    }
+}
+
+/// Streaming CSV tick writer
+pub struct TickCsvWriter<W: Write> {
+    writer: W,
+    format: CsvFormat,
+    instruments: Vec<Option<Instrument>>,
+    header_written: bool,
+}
+
+impl TickCsvWriter<BufWriter<File>> {
+    /// Create a CSV file with the default format
+    pub fn create<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| {
+            Error::MarketData(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> TickCsvWriter<W> {
+    /// Create a writer with the default format
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            format: CsvFormat::default(),
+            instruments: vec![None; 256],
+            header_written: false,
+        }
+    }
+
+    /// Set the CSV format
+    pub fn with_format(mut self, format: CsvFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Write instrument names instead of symbol IDs
+    ///
+    /// Prices of registered instruments are decoded with their own
+    /// `price_scale`.
+    pub fn with_registry(mut self, registry: &InstrumentRegistry) -> Self {
+        for instrument in registry.iter() {
+            self.instruments[instrument.symbol_id as usize] = Some(instrument.clone());
+        }
+        self
+    }
+
+    /// Write one tick (and the header before the first)
+    pub fn write_tick(&mut self, tick: &MarketTick) -> crate::Result<()> {
+        let delimiter = self.format.delimiter;
+
+        if !self.header_written {
+            self.header_written = true;
+            if self.format.header != HeaderMode::Absent {
+                let names: Vec<&str> = self.format.columns.iter().map(|c| c.name()).collect();
+                let header = names.join(&delimiter.to_string());
+                writeln!(self.writer, "{}", header).map_err(Self::io_error)?;
+            }
+        }
+
+        let instrument = self.instruments[tick.symbol_id as usize].as_ref();
+
+        for (i, column) in self.format.columns.iter().enumerate() {
+            if i > 0 {
+                write!(self.writer, "{}", delimiter).map_err(Self::io_error)?;
+            }
+
+            let result = match column {
+                CsvColumn::Timestamp => write!(self.writer, "{}", tick.timestamp_ns),
+                CsvColumn::Symbol => match instrument {
+                    Some(instrument) => write!(self.writer, "{}", instrument.name),
+                    None => write!(self.writer, "{}", tick.symbol_id),
+                },
+                CsvColumn::Price => match instrument {
+                    Some(instrument) => write!(self.writer, "{}", instrument.price_f64(tick.price)),
+                    None => write!(self.writer, "{}", tick.price_f64()),
+                },
+                CsvColumn::Quantity => write!(self.writer, "{}", tick.quantity),
+                CsvColumn::Side => {
+                    write!(self.writer, "{}", if tick.is_bid() { "bid" } else { "ask" })
+                }
+                CsvColumn::Event => match tick.event() {
+                    Some(TickEvent::Quote) => write!(self.writer, "quote"),
+                    Some(TickEvent::Trade) => write!(self.writer, "trade"),
+                    Some(TickEvent::Settlement) => write!(self.writer, "settlement"),
+                    Some(TickEvent::BookClear) => write!(self.writer, "clear"),
+                    Some(TickEvent::Halt) => write!(self.writer, "halt"),
+                    None => write!(self.writer, "{}", tick.event),
+                },
+                CsvColumn::Channel => write!(self.writer, "{}", tick.channel),
+                CsvColumn::Sequence => write!(self.writer, "{}", tick.sequence),
+                CsvColumn::Skip => Ok(()),
+            };
+            result.map_err(Self::io_error)?;
+        }
+
+        writeln!(self.writer).map_err(Self::io_error)
+    }
+
+    /// Flush buffered output
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.writer.flush().map_err(Self::io_error)
+    }
+
+    /// Flush and return the underlying writer
+    pub fn into_inner(mut self) -> crate::Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+
+    fn io_error(e: std::io::Error) -> Error {
+        Error::MarketData(format!("Failed to write CSV: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::{Commodity, Instrument};
+
+    #[test]
+    fn test_read_sample_file() {
+        let ticks: Vec<MarketTick> = TickCsvReader::open("data/sample_ticks.csv")
+            .unwrap()
+            .collect::<crate::Result<_>>()
+            .unwrap();
+
+        assert_eq!(ticks.len(), 101);
+        assert_eq!(ticks[0].timestamp_ns, 1_000_000_000);
+        assert_eq!(ticks[0].symbol_id, 1);
+        assert_eq!(ticks[0].price_f64(), 48.20);
+        assert_eq!(ticks[0].quantity, 150);
+        assert!(ticks[0].is_bid());
+        assert!(ticks[1].is_ask());
+    }
+
+    #[test]
+    fn test_header_columns_and_symbol_names() {
+        let data = "\
+            seq;instrument;side;price;qty;ts;note\n\
+            7;DE Base Jan-26;ask;-12.5;30;1000;x\n\
+            \n\
+            8;3;b;48.25;10;2000;y\n";
+
+        let registry = {
+            let mut r = InstrumentRegistry::new();
+            r.register(Instrument::new(
+                2,
+                "DE Base Jan-26",
+                "EEX",
+                Commodity::Power,
+            ))
+            .unwrap();
+            r
+        };
+
+        let format = CsvFormat::default().with_delimiter(';');
+        let mut reader = TickCsvReader::new(data.as_bytes())
+            .with_format(format)
+            .with_registry(&registry);
+
+        let first = reader.next().unwrap().unwrap();
+        assert_eq!(first.sequence, 7);
+        assert_eq!(first.symbol_id, 2);
+        assert_eq!(first.price_f64(), -12.5);
+        assert_eq!(first.timestamp_ns, 1000);
+        assert!(first.is_ask());
+
+        let second = reader.next().unwrap().unwrap();
+        assert_eq!(second.symbol_id, 3);
+        assert!(second.is_bid());
+        assert!(reader.next().is_none());
+        assert_eq!(reader.format().columns[6], CsvColumn::Skip);
+    }
+
+    #[test]
+    fn test_registry_price_scale() {
+        let registry = {
+            let mut r = InstrumentRegistry::new();
+            let mut eua = Instrument::new(4, "EUA Dec-26", "EEX", Commodity::Emissions);
+            eua.price_scale = 100.0;
+            r.register(eua).unwrap();
+            r
+        };
+
+        let data = "timestamp_ns,price,symbol,quantity,side\n1000,72.35,EUA Dec-26,10,bid\n2000,45.5,1,10,ask\n";
+        let ticks: Vec<MarketTick> = TickCsvReader::new(data.as_bytes())
+            .with_registry(&registry)
+            .collect::<crate::Result<_>>()
+            .unwrap();
+
+        // Registered symbols use their own scale, others the default
+        assert_eq!(ticks[0].price, 7235);
+        assert_eq!(ticks[1].price, 455000);
+
+        let book = crate::market_data::OrderBook::for_instrument(registry.get(4).unwrap());
+        book.update_bid(0, ticks[0].price, 10, 1000);
+        assert_eq!(book.best_bid(), (72.35, 10));
+
+        let mut writer = TickCsvWriter::new(Vec::new()).with_registry(&registry);
+        for tick in &ticks {
+            writer.write_tick(tick).unwrap();
+        }
+        let text = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(text.contains("1000,EUA Dec-26,72.35,10,bid"));
+        assert!(text.contains("2000,1,45.5,10,ask"));
+    }
+
+    #[test]
+    fn test_line_errors() {
+        let data = "1000,1,45.0,100,bid\n2000,X,45.0,100,bid\n3000,1,abc,100,ask\n4000,1\n5000,1,45.0,100,ask\n";
+        let results: Vec<_> = TickCsvReader::new(data.as_bytes()).collect();
+
+        assert_eq!(results.len(), 5);
+        assert!(results[0].is_ok());
+        assert!(results[4].is_ok());
+
+        let messages: Vec<String> = results[1..4]
+            .iter()
+            .map(|r| r.as_ref().unwrap_err().to_string())
+            .collect();
+        assert!(messages[0].contains("csv:2: unknown symbol"));
+        assert!(messages[1].contains("csv:3: invalid price"));
+        assert!(messages[2].contains("csv:4: missing price column"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let format = CsvFormat::new(vec![
+            CsvColumn::Timestamp,
+            CsvColumn::Symbol,
+            CsvColumn::Price,
+            CsvColumn::Quantity,
+            CsvColumn::Side,
+            CsvColumn::Event,
+            CsvColumn::Channel,
+            CsvColumn::Sequence,
+        ]);
+
+        let ticks = vec![
+            MarketTick::bid(1000, 45.5, 100, 1).with_sequence(2, 10),
+            MarketTick::trade(2000, -3.25, 5, Side::Ask, 2).with_sequence(2, 11),
+            MarketTick::halt(3000, 2),
+        ];
+
+        let mut writer = TickCsvWriter::new(Vec::new()).with_format(format.clone());
+        for tick in &ticks {
+            writer.write_tick(tick).unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert!(text.starts_with("timestamp_ns,symbol_id,price,quantity,side,event"));
+
+        let decoded: Vec<MarketTick> = TickCsvReader::new(bytes.as_slice())
+            .with_format(format)
+            .collect::<crate::Result<_>>()
+            .unwrap();
+
+        assert_eq!(decoded.len(), ticks.len());
+        for (a, b) in decoded.iter().zip(&ticks) {
+            assert_eq!(a.as_bytes(), b.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Book health checks gating hedge recommendations
+
+use crate::market_data::{InstrumentRegistry, OrderBook, Side};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Reason a book is unfit to price a hedge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookIssue {
+    /// Trading is halted
+    Halted,
+
+    /// Feed gap detected and not yet recovered
+    FeedGap,
+
+    /// No update within the instrument's staleness limit
+    Stale { age_ns: u64 },
+
+    /// The side needed for execution has no levels
+    EmptySide(Side),
+
+    /// Best bid above best ask
+    Crossed { bid: f64, ask: f64 },
+
+    /// Best bid equal to best ask
+    Locked { price: f64 },
+
+    /// Spread wider than the instrument's limit
+    WideSpread { spread_bps: f64 },
+}
+
+impl fmt::Display for BookIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookIssue::Halted => write!(f, "trading halted"),
+            BookIssue::FeedGap => write!(f, "feed gap not recovered"),
+            BookIssue::Stale { age_ns } => {
+                write!(f, "no update for {:.1}ms", *age_ns as f64 / 1e6)
+            }
+            BookIssue::EmptySide(side) => write!(f, "empty {:?} side", side),
+            BookIssue::Crossed { bid, ask } => write!(f, "crossed book ({} > {})", bid, ask),
+            BookIssue::Locked { price } => write!(f, "locked book at {}", price),
+            BookIssue::WideSpread { spread_bps } => {
+                write!(f, "abnormal spread ({:.1} bps)", spread_bps)
+            }
+        }
+    }
+}
+
+/// Health limits of one instrument
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct HealthLimits {
+    /// Maximum time since the last book update (None = unchecked)
+    #[serde(default)]
+    pub max_staleness_ms: Option<u64>,
+
+    /// Maximum bid/ask spread in basis points (None = unchecked)
+    #[serde(default)]
+    pub max_spread_bps: Option<f64>,
+
+    /// Accept books with best bid equal to best ask
+    #[serde(default)]
+    pub allow_locked: bool,
+}
+
+/// Checks orderbooks against per-instrument health limits
+///
+/// Halted, gapped and crossed books always fail; staleness, spread and
+/// locked-book checks follow the limits of the book's symbol.
+#[derive(Debug, Clone)]
+pub struct BookHealthChecker {
+    /// Limits indexed by symbol ID
+    limits: Vec<HealthLimits>,
+}
+
+impl BookHealthChecker {
+    /// Create a checker with default limits for every symbol
+    pub fn new() -> Self {
+        Self {
+            limits: vec![HealthLimits::default(); 256],
+        }
+    }
+
+    /// Create a checker using the limits of registered instruments
+    pub fn from_registry(registry: &InstrumentRegistry) -> Self {
+        let mut checker = Self::new();
+        for instrument in registry.iter() {
+            checker.set_limits(instrument.symbol_id, instrument.health);
+        }
+        checker
+    }
+
+    /// Set the limits of a symbol
+    pub fn set_limits(&mut self, symbol_id: u8, limits: HealthLimits) {
+        self.limits[symbol_id as usize] = limits;
+    }
+
+    /// Get the limits of a symbol
+    #[inline(always)]
+    pub fn limits(&self, symbol_id: u8) -> &HealthLimits {
+        &self.limits[symbol_id as usize]
+    }
+
+    /// Check a book
+    ///
+    /// `side` is the side a hedge would execute against; it must not be
+    /// empty. Crossed, locked and spread checks need both sides populated.
+    pub fn check(
+        &self,
+        orderbook: &OrderBook,
+        side: Option<Side>,
+        now_ns: u64,
+    ) -> Result<(), BookIssue> {
+        if orderbook.is_halted() {
+            return Err(BookIssue::Halted);
+        }
+
+        if orderbook.is_stale() {
+            return Err(BookIssue::FeedGap);
+        }
+
+        let limits = self.limits(orderbook.symbol_id());
+        let top = orderbook.snapshot(1);
+
+        if let Some(max_ms) = limits.max_staleness_ms {
+            let age_ns = now_ns.saturating_sub(top.timestamp_ns);
+            if age_ns > max_ms.saturating_mul(1_000_000) {
+                return Err(BookIssue::Stale { age_ns });
+            }
+        }
+
+        match side {
+            Some(Side::Bid) if top.best_bid().is_none() => {
+                return Err(BookIssue::EmptySide(Side::Bid));
+            }
+            Some(Side::Ask) if top.best_ask().is_none() => {
+                return Err(BookIssue::EmptySide(Side::Ask));
+            }
+            _ => {}
+        }
+
+        if let (Some((bid, _)), Some((ask, _))) = (top.best_bid(), top.best_ask()) {
+            if bid > ask {
+                return Err(BookIssue::Crossed { bid, ask });
+            }
+
+            if bid == ask && !limits.allow_locked {
+                return Err(BookIssue::Locked { price: bid });
+            }
+
+            if let (Some(max_bps), Some(spread_bps)) = (limits.max_spread_bps, top.spread_bps())
+                && spread_bps > max_bps
+            {
+                return Err(BookIssue::WideSpread { spread_bps });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for BookHealthChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(bid: i64, ask: i64, ts: u64) -> OrderBook {
+        let ob = OrderBook::new(1);
+        ob.update_bid(0, bid, 100, ts);
+        ob.update_ask(0, ask, 100, ts);
+        ob
+    }
+
+    #[test]
+    fn test_healthy_book() {
+        let checker = BookHealthChecker::new();
+        let ob = book(450000, 451000, 1000);
+
+        assert_eq!(checker.check(&ob, Some(Side::Ask), 1000), Ok(()));
+        assert_eq!(checker.check(&ob, None, u64::MAX), Ok(()));
+    }
+
+    #[test]
+    fn test_empty_crossed_and_locked() {
+        let checker = BookHealthChecker::new();
+
+        let ob = OrderBook::new(1);
+        ob.update_bid(0, 450000, 100, 1000);
+        assert_eq!(checker.check(&ob, None, 1000), Ok(()));
+        assert_eq!(
+            checker.check(&ob, Some(Side::Ask), 1000),
+            Err(BookIssue::EmptySide(Side::Ask))
+        );
+
+        let crossed = book(452000, 451000, 1000);
+        assert_eq!(
+            checker.check(&crossed, None, 1000),
+            Err(BookIssue::Crossed {
+                bid: 45.2,
+                ask: 45.1
+            })
+        );
+
+        let locked = book(-50000, -50000, 1000);
+        assert_eq!(
+            checker.check(&locked, None, 1000),
+            Err(BookIssue::Locked { price: -5.0 })
+        );
+
+        let mut checker = checker;
+        checker.set_limits(
+            1,
+            HealthLimits {
+                allow_locked: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(checker.check(&locked, None, 1000), Ok(()));
+    }
+
+    #[test]
+    fn test_staleness_and_spread_limits() {
+        let mut checker = BookHealthChecker::new();
+        checker.set_limits(
+            1,
+            HealthLimits {
+                max_staleness_ms: Some(500),
+                max_spread_bps: Some(50.0),
+                allow_locked: false,
+            },
+        );
+
+        let ob = book(450000, 451000, 1_000_000_000);
+        assert_eq!(checker.check(&ob, None, 1_400_000_000), Ok(()));
+        assert_eq!(
+            checker.check(&ob, None, 1_600_000_000),
+            Err(BookIssue::Stale {
+                age_ns: 600_000_000
+            })
+        );
+
+        let wide = book(450000, 460000, 1_000_000_000);
+        let issue = checker.check(&wide, None, 1_000_000_000).unwrap_err();
+        assert!(matches!(issue, BookIssue::WideSpread { spread_bps } if spread_bps > 200.0));
+    }
+
+    #[test]
+    fn test_halted_and_feed_gap() {
+        let checker = BookHealthChecker::new();
+        let ob = book(450000, 451000, 1000);
+
+        ob.set_stale(true);
+        assert_eq!(checker.check(&ob, None, 1000), Err(BookIssue::FeedGap));
+
+        ob.set_halted(true, 2000);
+        assert_eq!(checker.check(&ob, None, 2000), Err(BookIssue::Halted));
+        assert_eq!(BookIssue::Halted.to_string(), "trading halted");
+    }
+}
@@ -0,0 +1,467 @@
+//! Instrument registry mapping symbol IDs to contract metadata
+
+use crate::Error;
+use crate::market_data::{HealthLimits, MarketTick, Side};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Commodity class of an instrument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Commodity {
+    Power,
+    Gas,
+    Emissions,
+    Coal,
+    Other,
+}
+
+/// Contract metadata for a tradable instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instrument {
+    /// Symbol identifier carried in `MarketTick::symbol_id`
+    pub symbol_id: u8,
+
+    /// Instrument name (e.g., "DE Base Cal-26")
+    pub name: String,
+
+    /// Trading venue (e.g., "EEX")
+    pub venue: String,
+
+    /// Commodity class
+    pub commodity: Commodity,
+
+    /// Minimum price increment (currency per unit)
+    pub tick_size: f64,
+
+    /// Fixed-point price scale (raw price = price * scale)
+    pub price_scale: f64,
+
+    /// Quantity per lot (units per contract)
+    /// e.g., 1 MW baseload for a month ≈ 744 MWh
+    pub contract_size: f64,
+
+    /// Quantity unit (e.g., "MWh", "tCO2")
+    pub unit: String,
+
+    /// First delivery day (None for spot)
+    #[serde(default)]
+    pub delivery_start: Option<NaiveDate>,
+
+    /// Last delivery day (None for spot)
+    #[serde(default)]
+    pub delivery_end: Option<NaiveDate>,
+
+    /// Price currency (e.g., "EUR")
+    pub currency: String,
+
+    /// Book health limits (staleness, spread)
+    #[serde(default)]
+    pub health: HealthLimits,
+}
+
+impl Instrument {
+    /// Create an instrument with power-market defaults
+    ///
+    /// Tick size 0.01 EUR/MWh, price scale 10000, 1 MWh per lot, no
+    /// delivery period.
+    pub fn new(symbol_id: u8, name: &str, venue: &str, commodity: Commodity) -> Self {
+        Self {
+            symbol_id,
+            name: name.to_string(),
+            venue: venue.to_string(),
+            commodity,
+            tick_size: 0.01,
+            price_scale: crate::market_data::PRICE_SCALE,
+            contract_size: 1.0,
+            unit: "MWh".to_string(),
+            delivery_start: None,
+            delivery_end: None,
+            currency: "EUR".to_string(),
+            health: HealthLimits::default(),
+        }
+    }
+
+    /// Convert a raw fixed-point price to f64
+    #[inline(always)]
+    pub fn price_f64(&self, raw_price: i64) -> f64 {
+        (raw_price as f64) / self.price_scale
+    }
+
+    /// Convert a price to this instrument's fixed-point representation
+    #[inline(always)]
+    pub fn to_fixed_price(&self, price: f64) -> i64 {
+        (price * self.price_scale).round() as i64
+    }
+
+    /// Create a BID tick for this instrument, converting with its price scale
+    #[inline]
+    pub fn bid(&self, timestamp_ns: u64, price: f64, quantity: u32) -> MarketTick {
+        MarketTick::bid_raw(
+            timestamp_ns,
+            self.to_fixed_price(price),
+            quantity,
+            self.symbol_id,
+        )
+    }
+
+    /// Create an ASK tick for this instrument, converting with its price scale
+    #[inline]
+    pub fn ask(&self, timestamp_ns: u64, price: f64, quantity: u32) -> MarketTick {
+        MarketTick::ask_raw(
+            timestamp_ns,
+            self.to_fixed_price(price),
+            quantity,
+            self.symbol_id,
+        )
+    }
+
+    /// Create a last-trade print for this instrument
+    #[inline]
+    pub fn trade(&self, timestamp_ns: u64, price: f64, quantity: u32, side: Side) -> MarketTick {
+        MarketTick::trade_raw(
+            timestamp_ns,
+            self.to_fixed_price(price),
+            quantity,
+            side,
+            self.symbol_id,
+        )
+    }
+
+    /// Create a settlement price event for this instrument
+    #[inline]
+    pub fn settlement(&self, timestamp_ns: u64, price: f64) -> MarketTick {
+        MarketTick::settlement_raw(timestamp_ns, self.to_fixed_price(price), self.symbol_id)
+    }
+
+    /// Round a price to the nearest valid tick
+    #[inline]
+    pub fn round_to_tick(&self, price: f64) -> f64 {
+        if self.tick_size > 0.0 {
+            (price / self.tick_size).round() * self.tick_size
+        } else {
+            price
+        }
+    }
+
+    /// Convert a quantity (in `unit`) to lots
+    #[inline]
+    pub fn quantity_to_lots(&self, quantity: f64) -> f64 {
+        quantity / self.contract_size
+    }
+
+    /// Convert lots to a quantity (in `unit`)
+    #[inline]
+    pub fn lots_to_quantity(&self, lots: f64) -> f64 {
+        lots * self.contract_size
+    }
+
+    /// Number of delivery days, if the instrument has a delivery period
+    pub fn delivery_days(&self) -> Option<i64> {
+        match (self.delivery_start, self.delivery_end) {
+            (Some(start), Some(end)) => Some((end - start).num_days() + 1),
+            _ => None,
+        }
+    }
+
+    /// Validate metadata
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.tick_size < 0.0 {
+            return Err(Error::Config(format!(
+                "Instrument {}: tick size must be non-negative",
+                self.name
+            )));
+        }
+
+        if self.price_scale <= 0.0 {
+            return Err(Error::Config(format!(
+                "Instrument {}: price scale must be positive",
+                self.name
+            )));
+        }
+
+        if self.contract_size <= 0.0 {
+            return Err(Error::Config(format!(
+                "Instrument {}: contract size must be positive",
+                self.name
+            )));
+        }
+
+        if let (Some(start), Some(end)) = (self.delivery_start, self.delivery_end)
+            && end < start
+        {
+            return Err(Error::Config(format!(
+                "Instrument {}: delivery end before start",
+                self.name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} ({}, {:?}, {} {}/{})",
+            self.symbol_id,
+            self.name,
+            self.venue,
+            self.commodity,
+            self.tick_size,
+            self.currency,
+            self.unit
+        )
+    }
+}
+
+/// Registry of instruments indexed by symbol ID
+///
+/// Lookups are a direct array index on the `u8` symbol ID, so resolving
+/// an instrument on the hot path needs no hashing.
+#[derive(Debug, Clone)]
+pub struct InstrumentRegistry {
+    instruments: Vec<Option<Instrument>>,
+}
+
+impl InstrumentRegistry {
+    /// Symbol ID of the default spot instrument
+    pub const DEFAULT_SPOT_ID: u8 = 1;
+
+    /// Symbol ID of the default futures instrument
+    pub const DEFAULT_FUTURES_ID: u8 = 2;
+
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            instruments: vec![None; 256],
+        }
+    }
+
+    /// Register an instrument
+    ///
+    /// Fails if the metadata is invalid or the symbol ID is already taken.
+    pub fn register(&mut self, instrument: Instrument) -> crate::Result<()> {
+        instrument.validate()?;
+
+        let slot = &mut self.instruments[instrument.symbol_id as usize];
+        if let Some(existing) = slot {
+            return Err(Error::Config(format!(
+                "Symbol ID {} already registered to {}",
+                existing.symbol_id, existing.name
+            )));
+        }
+
+        *slot = Some(instrument);
+        Ok(())
+    }
+
+    /// Look up an instrument by symbol ID
+    #[inline(always)]
+    pub fn get(&self, symbol_id: u8) -> Option<&Instrument> {
+        self.instruments[symbol_id as usize].as_ref()
+    }
+
+    /// Look up an instrument by name
+    pub fn by_name(&self, name: &str) -> Option<&Instrument> {
+        self.iter().find(|i| i.name == name)
+    }
+
+    /// Check whether a symbol ID is registered
+    #[inline(always)]
+    pub fn contains(&self, symbol_id: u8) -> bool {
+        self.instruments[symbol_id as usize].is_some()
+    }
+
+    /// Iterate over registered instruments in symbol ID order
+    pub fn iter(&self) -> impl Iterator<Item = &Instrument> {
+        self.instruments.iter().flatten()
+    }
+
+    /// Number of registered instruments
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Check whether the registry is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Parse a registry from a JSON array of instruments
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        let instruments: Vec<Instrument> = serde_json::from_str(json)
+            .map_err(|e| Error::Config(format!("Invalid instrument file: {}", e)))?;
+
+        let mut registry = Self::new();
+        for instrument in instruments {
+            registry.register(instrument)?;
+        }
+
+        Ok(registry)
+    }
+
+    /// Load a registry from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read instrument file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        Self::from_json(&json)
+    }
+
+    /// Serialize the registry to a JSON array
+    pub fn to_json(&self) -> crate::Result<String> {
+        let instruments: Vec<&Instrument> = self.iter().collect();
+        serde_json::to_string_pretty(&instruments)
+            .map_err(|e| Error::Config(format!("Failed to serialize instruments: {}", e)))
+    }
+}
+
+impl Default for InstrumentRegistry {
+    /// Spot (symbol 1) and futures (symbol 2) power instruments
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.instruments[Self::DEFAULT_SPOT_ID as usize] = Some(Instrument::new(
+            Self::DEFAULT_SPOT_ID,
+            "Spot",
+            "Default",
+            Commodity::Power,
+        ));
+        registry.instruments[Self::DEFAULT_FUTURES_ID as usize] = Some(Instrument::new(
+            Self::DEFAULT_FUTURES_ID,
+            "Futures",
+            "Default",
+            Commodity::Power,
+        ));
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::TickEvent;
+
+    const SAMPLE: &str = r#"[
+        {
+            "symbol_id": 3,
+            "name": "DE Base Jan-26",
+            "venue": "EEX",
+            "commodity": "Power",
+            "tick_size": 0.01,
+            "price_scale": 10000.0,
+            "contract_size": 744.0,
+            "unit": "MWh",
+            "delivery_start": "2026-01-01",
+            "delivery_end": "2026-01-31",
+            "currency": "EUR"
+        },
+        {
+            "symbol_id": 7,
+            "name": "EUA Dec-26",
+            "venue": "ICE",
+            "commodity": "Emissions",
+            "tick_size": 0.01,
+            "price_scale": 100.0,
+            "contract_size": 1000.0,
+            "unit": "tCO2",
+            "currency": "EUR"
+        }
+    ]"#;
+
+    #[test]
+    fn test_registry_from_json() {
+        let registry = InstrumentRegistry::from_json(SAMPLE).unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert!(!registry.contains(1));
+
+        let power = registry.get(3).unwrap();
+        assert_eq!(power.name, "DE Base Jan-26");
+        assert_eq!(power.delivery_days(), Some(31));
+        assert_eq!(power.quantity_to_lots(7440.0), 10.0);
+
+        let eua = registry.by_name("EUA Dec-26").unwrap();
+        assert_eq!(eua.symbol_id, 7);
+        assert_eq!(eua.price_f64(8_150), 81.5);
+        assert_eq!(eua.delivery_days(), None);
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let registry = InstrumentRegistry::from_json(SAMPLE).unwrap();
+        let json = registry.to_json().unwrap();
+        let reloaded = InstrumentRegistry::from_json(&json).unwrap();
+
+        assert_eq!(reloaded.get(3), registry.get(3));
+        assert_eq!(reloaded.get(7), registry.get(7));
+    }
+
+    #[test]
+    fn test_registry_rejects_duplicates_and_invalid() {
+        let mut registry = InstrumentRegistry::default();
+
+        let dup = Instrument::new(1, "Other", "EEX", Commodity::Gas);
+        assert!(registry.register(dup).is_err());
+
+        let mut bad = Instrument::new(9, "Bad", "EEX", Commodity::Gas);
+        bad.contract_size = 0.0;
+        assert!(registry.register(bad).is_err());
+
+        assert!(InstrumentRegistry::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_tick_rounding() {
+        let mut inst = Instrument::new(1, "TTF", "ICE", Commodity::Gas);
+        inst.tick_size = 0.005;
+
+        assert!((inst.round_to_tick(31.2374) - 31.235).abs() < 1e-9);
+        assert!((inst.round_to_tick(-4.2226) - -4.225).abs() < 1e-9);
+        assert_eq!(inst.to_fixed_price(-4.225), -42250);
+    }
+
+    #[test]
+    fn test_scaled_ticks() {
+        let registry = InstrumentRegistry::from_json(SAMPLE).unwrap();
+        let eua = registry.get(7).unwrap();
+
+        let bid = eua.bid(1_000, 81.5, 10);
+        assert_eq!(bid.price, 8_150);
+        assert_eq!(bid.symbol_id, 7);
+        assert!(bid.is_bid());
+        assert_eq!(eua.price_f64(bid.price), 81.5);
+
+        let ask = eua.ask(1_000, 81.52, 5);
+        assert_eq!(ask.price, 8_152);
+        assert!(ask.is_ask());
+
+        let trade = eua.trade(2_000, 81.51, 3, Side::Ask);
+        assert_eq!(trade.price, 8_151);
+        assert!(trade.is_trade());
+
+        let settle = eua.settlement(3_000, 81.4);
+        assert_eq!(settle.price, 8_140);
+        assert_eq!(settle.event(), Some(TickEvent::Settlement));
+
+        // Raw constructors take the price as-is
+        assert_eq!(MarketTick::bid_raw(1_000, 8_150, 10, 7).price, bid.price);
+    }
+
+    #[test]
+    fn test_load_sample_file() {
+        let registry = InstrumentRegistry::load("data/instruments.json").unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get(2).unwrap().contract_size, 744.0);
+        assert!(InstrumentRegistry::load("data/missing.json").is_err());
+    }
+}
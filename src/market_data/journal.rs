@@ -0,0 +1,863 @@
+//! Append-only binary tick journal with memory-mapped replay
+//!
+//! # File layout
+//!
+//! ```text
+//! FileHeader (64 bytes)
+//! Block*     BlockHeader (32 bytes) + count * MarketTick (raw 32 bytes each)
+//!            + count * receive_ns (u64, 0 = unknown)
+//! ```
+//!
+//! Each block header carries the tick count, the min/max exchange
+//! timestamp and a CRC32 of the tick and receive time bytes. The reader
+//! builds its time index from the block headers when opening, so no footer
+//! is needed and a journal cut short by a crash (a partial or zero-filled
+//! tail) stays readable up to its last full block. Header fields and
+//! receive times are little-endian; ticks use the in-memory layout.
+
+use crate::Error;
+use crate::market_data::{MarketTick, ReceivedTick};
+use crate::utils::LockFreeQueue;
+use memmap2::Mmap;
+use parking_lot::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const FILE_MAGIC: [u8; 8] = *b"HEDGEJNL";
+const BLOCK_MAGIC: u32 = 0x4B4C_4254; // "TBLK"
+const VERSION: u32 = 1;
+const FILE_HEADER_SIZE: usize = 64;
+const BLOCK_HEADER_SIZE: usize = 32;
+
+/// Bytes per journaled tick: the tick and its receive time
+const ENTRY_SIZE: usize = MarketTick::SIZE + 8;
+
+/// Parsed block header plus its position in the file
+#[derive(Debug, Copy, Clone)]
+struct BlockIndex {
+    /// Offset of the first tick
+    offset: usize,
+    /// Number of ticks
+    count: usize,
+    /// Lowest timestamp in the block
+    min_ts: u64,
+    /// Highest timestamp up to and including this block
+    running_max_ts: u64,
+    /// CRC32 of the tick and receive time bytes
+    crc: u32,
+}
+
+/// Append-only tick journal writer
+///
+/// Ticks are buffered into blocks and each full block is written with a
+/// single syscall. Call `flush` (or drop the writer) to write a partial
+/// block. With `in_background`, full blocks are handed to a writer thread
+/// instead, so `append` never blocks on disk I/O. After a write error, no
+/// further block is written, so the journal stays readable up to the last
+/// good block.
+pub struct TickJournalWriter {
+    sink: Sink,
+    block: Vec<u8>,
+    receive: Vec<u8>,
+    block_ticks: usize,
+    count: usize,
+    min_ts: u64,
+    max_ts: u64,
+    ticks_written: u64,
+    failed: bool,
+}
+
+/// Destination of full blocks
+enum Sink {
+    File(File),
+    Background(BackgroundWriter),
+}
+
+/// Block header, tick and receive time bytes queued for the writer thread
+struct Block {
+    header: [u8; BLOCK_HEADER_SIZE],
+    ticks: Vec<u8>,
+    receive: Vec<u8>,
+}
+
+/// State shared with the writer thread
+struct WriterShared {
+    /// Full blocks (journal writer -> thread)
+    blocks: LockFreeQueue<Block>,
+    /// Emptied tick and receive time buffers for reuse (thread -> journal writer)
+    spare: LockFreeQueue<(Vec<u8>, Vec<u8>)>,
+    /// Blocks written by the thread
+    written: AtomicU64,
+    /// First write error, reported by the next `append` or `flush`; later
+    /// blocks are dropped
+    error: Mutex<Option<String>>,
+    failed: AtomicBool,
+    stop: AtomicBool,
+}
+
+/// Writer thread plus the file handle kept for `sync`
+struct BackgroundWriter {
+    shared: Arc<WriterShared>,
+    handle: Option<JoinHandle<()>>,
+    file: File,
+    submitted: u64,
+}
+
+impl TickJournalWriter {
+    /// Default ticks per block (128 KiB of ticks, 32 KiB of receive times)
+    pub const DEFAULT_BLOCK_TICKS: usize = 4096;
+
+    /// Blocks the background writer can fall behind before `append` waits
+    pub const BACKGROUND_QUEUE_BLOCKS: usize = 64;
+
+    /// Create a new journal
+    ///
+    /// Fails if the file exists: readers map the journal and rely on
+    /// written bytes never changing, so it is never truncated.
+    pub fn create<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::with_block_ticks(path, Self::DEFAULT_BLOCK_TICKS)
+    }
+
+    /// Create a new journal with a custom block size
+    pub fn with_block_ticks<P: AsRef<Path>>(path: P, block_ticks: usize) -> crate::Result<Self> {
+        let path = path.as_ref();
+        if block_ticks == 0 {
+            return Err(Error::Config(
+                "Journal block size must be positive".to_string(),
+            ));
+        }
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|e| {
+                Error::MarketData(format!(
+                    "Failed to create journal {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        header[0..8].copy_from_slice(&FILE_MAGIC);
+        header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        header[12..16].copy_from_slice(&(MarketTick::SIZE as u32).to_le_bytes());
+        header[16..20].copy_from_slice(&(block_ticks as u32).to_le_bytes());
+        header[24..32].copy_from_slice(&crate::utils::get_timestamp_ns().to_le_bytes());
+        file.write_all(&header).map_err(Self::io_error)?;
+
+        Ok(Self {
+            sink: Sink::File(file),
+            block: Vec::with_capacity(block_ticks * MarketTick::SIZE),
+            receive: Vec::with_capacity(block_ticks * 8),
+            block_ticks,
+            count: 0,
+            min_ts: u64::MAX,
+            max_ts: 0,
+            ticks_written: 0,
+            failed: false,
+        })
+    }
+
+    /// Write full blocks on a background thread
+    ///
+    /// Tick buffers are recycled through a second queue, so steady-state
+    /// appends neither allocate nor touch the file. If the thread falls
+    /// `BACKGROUND_QUEUE_BLOCKS` behind, `append` waits for it.
+    pub fn in_background(mut self) -> crate::Result<Self> {
+        let file = match self.sink {
+            Sink::File(ref file) => file.try_clone().map_err(Self::io_error)?,
+            Sink::Background(_) => return Ok(self),
+        };
+        let sync_file = file.try_clone().map_err(Self::io_error)?;
+
+        let shared = Arc::new(WriterShared {
+            blocks: LockFreeQueue::new(Self::BACKGROUND_QUEUE_BLOCKS),
+            spare: LockFreeQueue::new(Self::BACKGROUND_QUEUE_BLOCKS),
+            written: AtomicU64::new(0),
+            error: Mutex::new(None),
+            failed: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+        });
+
+        let thread_shared = shared.clone();
+        let handle = std::thread::Builder::new()
+            .name("tick-journal".to_string())
+            .spawn(move || write_blocks(file, &thread_shared))
+            .map_err(|e| Error::InvalidState(format!("Cannot spawn journal thread: {}", e)))?;
+
+        self.sink = Sink::Background(BackgroundWriter {
+            shared,
+            handle: Some(handle),
+            file: sync_file,
+            submitted: 0,
+        });
+        Ok(self)
+    }
+
+    /// Append a tick without a receive time
+    #[inline]
+    pub fn append(&mut self, tick: &MarketTick) -> crate::Result<()> {
+        self.append_received(&ReceivedTick::new(*tick, 0))
+    }
+
+    /// Append a tick with its feed receive time
+    #[inline]
+    pub fn append_received(&mut self, received: &ReceivedTick) -> crate::Result<()> {
+        let tick = &received.tick;
+        self.block.extend_from_slice(tick.as_bytes());
+        self.receive
+            .extend_from_slice(&received.receive_ns.to_le_bytes());
+        self.min_ts = self.min_ts.min(tick.timestamp_ns);
+        self.max_ts = self.max_ts.max(tick.timestamp_ns);
+        self.count += 1;
+
+        if self.count == self.block_ticks {
+            self.write_block()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write buffered ticks as a (possibly partial) block
+    ///
+    /// With a background writer, waits until every queued block is on
+    /// file.
+    pub fn flush(&mut self) -> crate::Result<()> {
+        if self.count > 0 {
+            self.write_block()?;
+        }
+
+        match self.sink {
+            Sink::File(ref mut file) => file.flush().map_err(Self::io_error),
+            Sink::Background(ref background) => {
+                while background.shared.written.load(Ordering::Acquire) < background.submitted {
+                    background.wake();
+                    std::thread::sleep(Duration::from_micros(100));
+                }
+                background.take_error()
+            }
+        }
+    }
+
+    /// Flush and sync the journal to disk
+    pub fn sync(&mut self) -> crate::Result<()> {
+        self.flush()?;
+        let file = match self.sink {
+            Sink::File(ref file) => file,
+            Sink::Background(ref background) => &background.file,
+        };
+        file.sync_data().map_err(Self::io_error)
+    }
+
+    /// Number of ticks appended (including buffered ones)
+    pub fn len(&self) -> u64 {
+        self.ticks_written + self.count as u64
+    }
+
+    /// Check whether no tick was appended
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn write_block(&mut self) -> crate::Result<()> {
+        if self.failed {
+            self.discard_block();
+            return Err(Error::MarketData(
+                "Journal stopped after a write error".to_string(),
+            ));
+        }
+
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(&self.block);
+        crc.update(&self.receive);
+
+        let mut header = [0u8; BLOCK_HEADER_SIZE];
+        header[0..4].copy_from_slice(&BLOCK_MAGIC.to_le_bytes());
+        header[4..8].copy_from_slice(&(self.count as u32).to_le_bytes());
+        header[8..16].copy_from_slice(&self.min_ts.to_le_bytes());
+        header[16..24].copy_from_slice(&self.max_ts.to_le_bytes());
+        header[24..28].copy_from_slice(&crc.finalize().to_le_bytes());
+
+        match self.sink {
+            Sink::File(ref mut file) => {
+                let result = file
+                    .write_all(&header)
+                    .and_then(|_| file.write_all(&self.block))
+                    .and_then(|_| file.write_all(&self.receive));
+                if let Err(e) = result {
+                    // A partial block may be on file: never write after it
+                    self.failed = true;
+                    self.discard_block();
+                    return Err(Self::io_error(e));
+                }
+                self.block.clear();
+                self.receive.clear();
+            }
+            Sink::Background(ref mut background) => {
+                if let Err(e) = background.take_error() {
+                    self.failed = true;
+                    self.discard_block();
+                    return Err(e);
+                }
+
+                let (ticks, receive) = background.shared.spare.try_pop().unwrap_or_else(|| {
+                    (
+                        Vec::with_capacity(self.block_ticks * MarketTick::SIZE),
+                        Vec::with_capacity(self.block_ticks * 8),
+                    )
+                });
+                let mut block = Block {
+                    header,
+                    ticks: std::mem::replace(&mut self.block, ticks),
+                    receive: std::mem::replace(&mut self.receive, receive),
+                };
+
+                // Queue full: the thread is behind, wait for a slot
+                while let Err(rejected) = background.shared.blocks.try_push(block) {
+                    block = rejected;
+                    background.wake();
+                    std::thread::yield_now();
+                }
+                background.submitted += 1;
+                background.wake();
+            }
+        }
+
+        self.ticks_written += self.count as u64;
+        self.count = 0;
+        self.min_ts = u64::MAX;
+        self.max_ts = 0;
+        Ok(())
+    }
+
+    fn discard_block(&mut self) {
+        self.block.clear();
+        self.receive.clear();
+        self.count = 0;
+        self.min_ts = u64::MAX;
+        self.max_ts = 0;
+    }
+
+    fn io_error(e: std::io::Error) -> Error {
+        Error::MarketData(format!("Failed to write journal: {}", e))
+    }
+}
+
+impl Drop for TickJournalWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+
+        if let Sink::Background(ref mut background) = self.sink {
+            background.shared.stop.store(true, Ordering::Release);
+            background.wake();
+            if let Some(handle) = background.handle.take()
+                && handle.join().is_err()
+            {
+                log::error!("Journal thread panicked");
+            }
+        }
+    }
+}
+
+impl BackgroundWriter {
+    #[inline]
+    fn wake(&self) {
+        if let Some(ref handle) = self.handle {
+            handle.thread().unpark();
+        }
+    }
+
+    fn take_error(&self) -> crate::Result<()> {
+        match self.shared.error.lock().take() {
+            Some(message) => Err(Error::MarketData(format!(
+                "Failed to write journal: {}",
+                message
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writer thread: drain queued blocks until stopped
+///
+/// After the first error, blocks are dropped unwritten: a partial block
+/// may be on file, and anything after it would be unreadable.
+fn write_blocks(mut file: File, shared: &WriterShared) {
+    loop {
+        match shared.blocks.try_pop() {
+            Some(mut block) => {
+                if !shared.failed.load(Ordering::Relaxed) {
+                    let result = file
+                        .write_all(&block.header)
+                        .and_then(|_| file.write_all(&block.ticks))
+                        .and_then(|_| file.write_all(&block.receive));
+                    if let Err(e) = result {
+                        shared.failed.store(true, Ordering::Relaxed);
+                        shared.error.lock().get_or_insert(e.to_string());
+                    }
+                }
+
+                block.ticks.clear();
+                block.receive.clear();
+                let _ = shared.spare.try_push((block.ticks, block.receive));
+                shared.written.fetch_add(1, Ordering::Release);
+            }
+            None if shared.stop.load(Ordering::Acquire) => return,
+            None => std::thread::park_timeout(Duration::from_millis(10)),
+        }
+    }
+}
+
+/// Memory-mapped tick journal reader
+pub struct TickJournalReader {
+    mmap: Mmap,
+    index: Vec<BlockIndex>,
+    created_ns: u64,
+    truncated: bool,
+}
+
+impl TickJournalReader {
+    /// Open a journal and index its blocks
+    pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let corrupt =
+            |what: &str| Error::MarketData(format!("Corrupt journal {}: {}", path.display(), what));
+
+        let file = File::open(path).map_err(|e| {
+            Error::MarketData(format!("Failed to open journal {}: {}", path.display(), e))
+        })?;
+
+        // SAFETY: writers create the journal with `create_new` and only
+        // append, so bytes already written are never modified while mapped.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| {
+            Error::MarketData(format!("Failed to map journal {}: {}", path.display(), e))
+        })?;
+
+        if mmap.len() < FILE_HEADER_SIZE || mmap[0..8] != FILE_MAGIC {
+            return Err(corrupt("bad file header"));
+        }
+        if read_u32(&mmap, 8) != VERSION {
+            return Err(corrupt("unsupported version"));
+        }
+        if read_u32(&mmap, 12) as usize != MarketTick::SIZE {
+            return Err(corrupt("tick size mismatch"));
+        }
+        let created_ns = read_u64(&mmap, 24);
+
+        let mut index = Vec::new();
+        let mut offset = FILE_HEADER_SIZE;
+        let mut running_max_ts = 0;
+        let mut truncated = false;
+
+        while offset < mmap.len() {
+            if offset + BLOCK_HEADER_SIZE > mmap.len() {
+                truncated = true;
+                break;
+            }
+            if read_u32(&mmap, offset) != BLOCK_MAGIC {
+                // A zero-filled or partly written tail after a crash
+                if !index.is_empty() {
+                    truncated = true;
+                    break;
+                }
+                return Err(corrupt(&format!("bad block header at offset {}", offset)));
+            }
+
+            let count = read_u32(&mmap, offset + 4) as usize;
+            let data = offset + BLOCK_HEADER_SIZE;
+            let end = data.saturating_add(count.saturating_mul(ENTRY_SIZE));
+            if end > mmap.len() {
+                truncated = true;
+                break;
+            }
+
+            running_max_ts = running_max_ts.max(read_u64(&mmap, offset + 16));
+            index.push(BlockIndex {
+                offset: data,
+                count,
+                min_ts: read_u64(&mmap, offset + 8),
+                running_max_ts,
+                crc: read_u32(&mmap, offset + 24),
+            });
+            offset = end;
+        }
+
+        Ok(Self {
+            mmap,
+            index,
+            created_ns,
+            truncated,
+        })
+    }
+
+    /// Total number of ticks
+    pub fn len(&self) -> usize {
+        self.index.iter().map(|b| b.count).sum()
+    }
+
+    /// Check whether the journal holds no ticks
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of blocks
+    pub fn block_count(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Check whether an incomplete or unreadable tail was ignored
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Creation time of the journal (nanoseconds)
+    pub fn created_ns(&self) -> u64 {
+        self.created_ns
+    }
+
+    /// Lowest and highest tick timestamp, if any
+    pub fn time_range(&self) -> Option<(u64, u64)> {
+        let min = self.index.iter().map(|b| b.min_ts).min()?;
+        let max = self.index.last()?.running_max_ts;
+        Some((min, max))
+    }
+
+    /// Verify the checksums of all blocks
+    pub fn verify(&self) -> crate::Result<()> {
+        (0..self.index.len()).try_for_each(|block| self.check_block(block))
+    }
+
+    /// Iterate over all ticks in journal order
+    pub fn iter(&self) -> JournalIter<'_> {
+        JournalIter {
+            reader: self,
+            block: 0,
+            pos: 0,
+            checked: false,
+            from_ts: None,
+        }
+    }
+
+    /// Iterate from the first tick with timestamp >= `timestamp_ns`
+    ///
+    /// Blocks ending before `timestamp_ns` are skipped through the index;
+    /// after the first match, ticks are replayed in journal order.
+    pub fn iter_from(&self, timestamp_ns: u64) -> JournalIter<'_> {
+        let block = self
+            .index
+            .partition_point(|b| b.running_max_ts < timestamp_ns);
+
+        JournalIter {
+            reader: self,
+            block,
+            pos: 0,
+            checked: false,
+            from_ts: Some(timestamp_ns),
+        }
+    }
+
+    fn check_block(&self, block: usize) -> crate::Result<()> {
+        let b = &self.index[block];
+        let data = &self.mmap[b.offset..b.offset + b.count * ENTRY_SIZE];
+
+        if crc32fast::hash(data) != b.crc {
+            return Err(Error::MarketData(format!(
+                "Journal checksum mismatch in block {} (offset {})",
+                block, b.offset
+            )));
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn tick(&self, block: usize, pos: usize) -> MarketTick {
+        let start = self.index[block].offset + pos * MarketTick::SIZE;
+        let bytes = self.mmap[start..start + MarketTick::SIZE]
+            .try_into()
+            .unwrap();
+        MarketTick::from_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn receive_ns(&self, block: usize, pos: usize) -> u64 {
+        let b = &self.index[block];
+        read_u64(&self.mmap, b.offset + b.count * MarketTick::SIZE + pos * 8)
+    }
+}
+
+/// Iterator over journal ticks
+///
+/// Each block's checksum is verified before its first tick is returned; a
+/// corrupt block yields one error and is skipped.
+pub struct JournalIter<'a> {
+    reader: &'a TickJournalReader,
+    block: usize,
+    pos: usize,
+    checked: bool,
+    from_ts: Option<u64>,
+}
+
+impl<'a> JournalIter<'a> {
+    /// Iterate over ticks with their recorded receive times
+    pub fn received(self) -> JournalReceivedIter<'a> {
+        JournalReceivedIter(self)
+    }
+
+    fn next_received(&mut self) -> Option<crate::Result<ReceivedTick>> {
+        loop {
+            let count = self.reader.index.get(self.block)?.count;
+
+            if self.pos >= count {
+                self.block += 1;
+                self.pos = 0;
+                self.checked = false;
+                continue;
+            }
+
+            if !self.checked {
+                self.checked = true;
+                if let Err(e) = self.reader.check_block(self.block) {
+                    self.pos = count;
+                    return Some(Err(e));
+                }
+            }
+
+            let tick = self.reader.tick(self.block, self.pos);
+            let receive_ns = self.reader.receive_ns(self.block, self.pos);
+            self.pos += 1;
+
+            if let Some(from_ts) = self.from_ts {
+                if tick.timestamp_ns < from_ts {
+                    continue;
+                }
+                self.from_ts = None;
+            }
+
+            return Some(Ok(ReceivedTick::new(tick, receive_ns)));
+        }
+    }
+}
+
+impl Iterator for JournalIter<'_> {
+    type Item = crate::Result<MarketTick>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_received()
+            .map(|received| received.map(|received| received.tick))
+    }
+}
+
+/// Iterator over journal ticks with their receive times (0 = unknown)
+pub struct JournalReceivedIter<'a>(JournalIter<'a>);
+
+impl Iterator for JournalReceivedIter<'_> {
+    type Item = crate::Result<ReceivedTick>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_received()
+    }
+}
+
+#[inline(always)]
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[inline(always)]
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::Side;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "hedging-journal-{}-{}.bin",
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn ticks(n: u64) -> Vec<MarketTick> {
+        (0..n)
+            .map(|i| {
+                MarketTick::bid(1_000 + i * 10, 45.0 + i as f64 * 0.01, 100, 1)
+                    .with_sequence(1, i + 1)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = temp_path("round-trip");
+        let input = ticks(25);
+
+        let mut writer = TickJournalWriter::with_block_ticks(&path, 10).unwrap();
+        for tick in &input {
+            writer.append(tick).unwrap();
+        }
+        writer
+            .append(&MarketTick::trade(2_000, -1.5, 5, Side::Ask, 2))
+            .unwrap();
+        assert_eq!(writer.len(), 26);
+        drop(writer);
+
+        let reader = TickJournalReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 26);
+        assert_eq!(reader.block_count(), 3);
+        assert!(!reader.is_truncated());
+        assert_eq!(reader.time_range(), Some((1_000, 2_000)));
+        reader.verify().unwrap();
+
+        let output: Vec<MarketTick> = reader.iter().collect::<crate::Result<_>>().unwrap();
+        for (a, b) in output.iter().zip(&input) {
+            assert_eq!(a.as_bytes(), b.as_bytes());
+        }
+        assert!(output[25].is_trade());
+
+        // Ticks appended without a receive time read back as 0
+        let received: Vec<ReceivedTick> = reader.iter().received().map(|t| t.unwrap()).collect();
+        assert!(received.iter().all(|r| r.receive_ns == 0));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_background_writer() {
+        let path = temp_path("background");
+        let input = ticks(1_000);
+
+        let mut writer = TickJournalWriter::with_block_ticks(&path, 8)
+            .unwrap()
+            .in_background()
+            .unwrap();
+        for tick in &input[..500] {
+            writer.append(tick).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(TickJournalReader::open(&path).unwrap().len(), 500);
+
+        for tick in &input[500..] {
+            writer.append(tick).unwrap();
+        }
+        writer.sync().unwrap();
+        drop(writer);
+
+        let reader = TickJournalReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 1_000);
+        reader.verify().unwrap();
+        for (a, b) in reader.iter().zip(&input) {
+            assert_eq!(a.unwrap().as_bytes(), b.as_bytes());
+        }
+
+        // A mapped journal is never truncated by a new writer
+        assert!(TickJournalWriter::create(&path).is_err());
+        assert_eq!(TickJournalReader::open(&path).unwrap().len(), 1_000);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_receive_times() {
+        let path = temp_path("receive-times");
+        let mut writer = TickJournalWriter::with_block_ticks(&path, 4).unwrap();
+        for (i, tick) in ticks(10).into_iter().enumerate() {
+            let received = ReceivedTick::new(tick, tick.timestamp_ns + 500 + i as u64);
+            writer.append_received(&received).unwrap();
+        }
+        drop(writer);
+
+        let reader = TickJournalReader::open(&path).unwrap();
+        reader.verify().unwrap();
+        let received: Vec<ReceivedTick> = reader
+            .iter_from(1_050)
+            .received()
+            .map(|t| t.unwrap())
+            .collect();
+        assert_eq!(received.len(), 5);
+        assert_eq!(received[0].tick.timestamp_ns, 1_050);
+        assert_eq!(received[0].feed_latency_ns(), 505);
+        assert_eq!(received[4].feed_latency_ns(), 509);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_seek_by_time() {
+        let path = temp_path("seek");
+        let mut writer = TickJournalWriter::with_block_ticks(&path, 8).unwrap();
+        for tick in ticks(50) {
+            writer.append(&tick).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let reader = TickJournalReader::open(&path).unwrap();
+
+        let from: Vec<MarketTick> = reader.iter_from(1_205).map(|t| t.unwrap()).collect();
+        assert_eq!(from.len(), 29);
+        assert_eq!(from[0].timestamp_ns, 1_210);
+
+        assert_eq!(reader.iter_from(0).count(), 50);
+        assert_eq!(reader.iter_from(10_000).count(), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_corruption_and_truncation() {
+        let path = temp_path("corrupt");
+        let mut writer = TickJournalWriter::with_block_ticks(&path, 4).unwrap();
+        for tick in ticks(12) {
+            writer.append(&tick).unwrap();
+        }
+        drop(writer);
+
+        // Flip a byte in the second block and cut the last block short
+        let mut bytes = std::fs::read(&path).unwrap();
+        let block_len = BLOCK_HEADER_SIZE + 4 * ENTRY_SIZE;
+        bytes[FILE_HEADER_SIZE + block_len + BLOCK_HEADER_SIZE + 9] ^= 0xFF;
+        bytes.truncate(bytes.len() - 7);
+        std::fs::write(&path, &bytes).unwrap();
+
+        let reader = TickJournalReader::open(&path).unwrap();
+        assert!(reader.is_truncated());
+        assert_eq!(reader.block_count(), 2);
+        assert!(reader.verify().is_err());
+
+        let results: Vec<_> = reader.iter().collect();
+        assert_eq!(results.len(), 5);
+        assert!(results[..4].iter().all(|r| r.is_ok()));
+        assert!(results[4].is_err());
+
+        // A zero-filled tail after the first block, as left by a crash
+        bytes.truncate(FILE_HEADER_SIZE + block_len);
+        bytes.resize(bytes.len() + 4096, 0);
+        std::fs::write(&path, &bytes).unwrap();
+        let reader = TickJournalReader::open(&path).unwrap();
+        assert!(reader.is_truncated());
+        assert_eq!(reader.len(), 4);
+        reader.verify().unwrap();
+
+        // Without a valid block, a bad block header is corruption
+        bytes.truncate(FILE_HEADER_SIZE);
+        bytes.resize(bytes.len() + 64, 0);
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(TickJournalReader::open(&path).is_err());
+
+        std::fs::write(&path, b"not a journal").unwrap();
+        assert!(TickJournalReader::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
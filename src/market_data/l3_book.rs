@@ -0,0 +1,549 @@
+//! Order-by-order (L3) book reconstruction
+
+use crate::Error;
+use crate::market_data::{BookUpdate, OrderBook, Side};
+use ahash::AHashMap;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+/// Order-level market data message
+///
+/// Prices are fixed-point in the book's price scale.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OrderEvent {
+    /// A new order joined the back of its price level
+    Add {
+        order_id: u64,
+        side: Side,
+        price: i64,
+        size: u64,
+    },
+
+    /// An order's price or size changed
+    Modify {
+        order_id: u64,
+        price: i64,
+        size: u64,
+    },
+
+    /// An order was cancelled
+    Cancel { order_id: u64 },
+
+    /// An order was (partially) filled
+    Execute { order_id: u64, size: u64 },
+
+    /// All orders were removed
+    Clear,
+}
+
+/// Position of an order in its price level queue
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QueuePosition {
+    /// Side of the order
+    pub side: Side,
+
+    /// Price of the order (fixed-point)
+    pub price: i64,
+
+    /// Orders ahead in the queue
+    pub orders_ahead: usize,
+
+    /// Total size ahead in the queue
+    pub size_ahead: u64,
+
+    /// Remaining size of the order
+    pub size: u64,
+
+    /// Total size resting at the level
+    pub level_size: u64,
+}
+
+/// Resting order
+#[derive(Debug, Copy, Clone)]
+struct Order {
+    side: Side,
+    price: i64,
+    size: u64,
+}
+
+/// Orders resting at one price, in time priority
+#[derive(Debug, Default)]
+struct Level {
+    queue: VecDeque<u64>,
+    size: u64,
+}
+
+/// Order-by-order book
+///
+/// Keeps every resting order in time priority and maintains the aggregated
+/// level view of an `OrderBook`, so readers keep using `best_bid`,
+/// `best_ask`, snapshots and the engine unchanged. Levels beyond
+/// `OrderBook::MAX_LEVELS` are tracked here and promoted into the level
+/// view as better levels disappear.
+///
+/// Single writer: events are applied through `&mut self`.
+pub struct L3OrderBook {
+    /// Aggregated level view
+    orderbook: Arc<OrderBook>,
+
+    /// Resting orders by order ID
+    orders: AHashMap<u64, Order>,
+
+    /// Bid levels by price (best = highest)
+    bids: BTreeMap<i64, Level>,
+
+    /// Ask levels by price (best = lowest)
+    asks: BTreeMap<i64, Level>,
+}
+
+impl L3OrderBook {
+    /// Create an L3 book maintaining the given level view
+    ///
+    /// The level view is cleared so it only reflects orders seen here.
+    pub fn new(orderbook: Arc<OrderBook>) -> Self {
+        orderbook.clear(orderbook.last_update_ns());
+
+        Self {
+            orderbook,
+            orders: AHashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+
+    /// Apply an order event
+    ///
+    /// Size reductions keep queue priority; a price change or size
+    /// increase sends the order to the back of its (new) level.
+    /// Executions are also recorded as trades on the level view; a
+    /// zero-size execution is ignored. Adds must have a positive size.
+    pub fn apply(&mut self, event: OrderEvent, timestamp_ns: u64) -> crate::Result<()> {
+        match event {
+            OrderEvent::Add {
+                order_id,
+                side,
+                price,
+                size,
+            } => {
+                if self.orders.contains_key(&order_id) {
+                    return Err(Error::MarketData(format!(
+                        "Duplicate order ID {}",
+                        order_id
+                    )));
+                }
+                if size == 0 {
+                    return Err(Error::MarketData(format!(
+                        "Zero-size add for order ID {}",
+                        order_id
+                    )));
+                }
+
+                self.orders.insert(order_id, Order { side, price, size });
+                self.enqueue(order_id, side, price, size, timestamp_ns);
+            }
+
+            OrderEvent::Modify {
+                order_id,
+                price,
+                size,
+            } => {
+                let order = self.order(order_id)?;
+
+                if size == 0 {
+                    self.remove(order_id, timestamp_ns);
+                } else if price == order.price && size <= order.size {
+                    self.reduce(order_id, order.size - size, timestamp_ns);
+                } else {
+                    self.remove(order_id, timestamp_ns);
+                    self.orders.insert(
+                        order_id,
+                        Order {
+                            side: order.side,
+                            price,
+                            size,
+                        },
+                    );
+                    self.enqueue(order_id, order.side, price, size, timestamp_ns);
+                }
+            }
+
+            OrderEvent::Cancel { order_id } => {
+                self.order(order_id)?;
+                self.remove(order_id, timestamp_ns);
+            }
+
+            OrderEvent::Execute { order_id, size } => {
+                let order = self.order(order_id)?;
+                let filled = size.min(order.size);
+                if filled == 0 {
+                    return Ok(());
+                }
+
+                if filled == order.size {
+                    self.remove(order_id, timestamp_ns);
+                } else {
+                    self.reduce(order_id, filled, timestamp_ns);
+                }
+
+                self.orderbook
+                    .record_trade(order.price, filled, timestamp_ns);
+            }
+
+            OrderEvent::Clear => {
+                self.orders.clear();
+                self.bids.clear();
+                self.asks.clear();
+                self.orderbook.clear(timestamp_ns);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the queue position of an order
+    pub fn queue_position(&self, order_id: u64) -> Option<QueuePosition> {
+        let order = self.orders.get(&order_id)?;
+        let level = self.levels(order.side).get(&order.price)?;
+
+        let mut orders_ahead = 0;
+        let mut size_ahead = 0;
+        for &id in &level.queue {
+            if id == order_id {
+                break;
+            }
+            orders_ahead += 1;
+            size_ahead += self.orders[&id].size;
+        }
+
+        Some(QueuePosition {
+            side: order.side,
+            price: order.price,
+            orders_ahead,
+            size_ahead,
+            size: order.size,
+            level_size: level.size,
+        })
+    }
+
+    /// Check whether an order is resting
+    pub fn contains(&self, order_id: u64) -> bool {
+        self.orders.contains_key(&order_id)
+    }
+
+    /// Number of resting orders
+    pub fn order_count(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Number of price levels on one side (including those beyond the level view)
+    pub fn level_count(&self, side: Side) -> usize {
+        self.levels(side).len()
+    }
+
+    /// Get the best bid (price, size), see `OrderBook::best_bid`
+    #[inline(always)]
+    pub fn best_bid(&self) -> (f64, u64) {
+        self.orderbook.best_bid()
+    }
+
+    /// Get the best ask (price, size), see `OrderBook::best_ask`
+    #[inline(always)]
+    pub fn best_ask(&self) -> (f64, u64) {
+        self.orderbook.best_ask()
+    }
+
+    /// Get the aggregated level view
+    pub fn orderbook(&self) -> &Arc<OrderBook> {
+        &self.orderbook
+    }
+
+    fn order(&self, order_id: u64) -> crate::Result<Order> {
+        self.orders
+            .get(&order_id)
+            .copied()
+            .ok_or_else(|| Error::MarketData(format!("Unknown order ID {}", order_id)))
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<i64, Level> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<i64, Level> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    /// Append an order to the back of its level
+    fn enqueue(&mut self, order_id: u64, side: Side, price: i64, size: u64, timestamp_ns: u64) {
+        let level = self.levels_mut(side).entry(price).or_default();
+        level.queue.push_back(order_id);
+        level.size += size;
+        let level_size = level.size;
+
+        self.orderbook.apply(
+            BookUpdate::Change {
+                side,
+                price,
+                size: level_size,
+            },
+            timestamp_ns,
+        );
+    }
+
+    /// Reduce an order's size in place, keeping priority
+    fn reduce(&mut self, order_id: u64, by: u64, timestamp_ns: u64) {
+        let Some(order) = self.orders.get_mut(&order_id) else {
+            return;
+        };
+        order.size -= by;
+        let Order { side, price, .. } = *order;
+
+        let Some(level) = self.levels_mut(side).get_mut(&price) else {
+            return;
+        };
+        level.size -= by;
+        let level_size = level.size;
+
+        self.orderbook.apply(
+            BookUpdate::Change {
+                side,
+                price,
+                size: level_size,
+            },
+            timestamp_ns,
+        );
+    }
+
+    /// Remove an order, deleting its level if it empties
+    fn remove(&mut self, order_id: u64, timestamp_ns: u64) {
+        let Some(order) = self.orders.remove(&order_id) else {
+            return;
+        };
+
+        let levels = self.levels_mut(order.side);
+        let Some(level) = levels.get_mut(&order.price) else {
+            return;
+        };
+
+        if let Some(pos) = level.queue.iter().position(|&id| id == order_id) {
+            level.queue.remove(pos);
+        }
+        level.size -= order.size;
+
+        if level.queue.is_empty() {
+            levels.remove(&order.price);
+            self.orderbook.apply(
+                BookUpdate::Delete {
+                    side: order.side,
+                    price: order.price,
+                },
+                timestamp_ns,
+            );
+            self.promote(order.side, timestamp_ns);
+        } else {
+            let size = level.size;
+            self.orderbook.apply(
+                BookUpdate::Change {
+                    side: order.side,
+                    price: order.price,
+                    size,
+                },
+                timestamp_ns,
+            );
+        }
+    }
+
+    /// Refill the level view from deeper levels after a deletion
+    fn promote(&mut self, side: Side, timestamp_ns: u64) {
+        let depth = self.orderbook.depth(side);
+        if depth >= OrderBook::MAX_LEVELS {
+            return;
+        }
+
+        let next = match side {
+            Side::Bid => self.bids.iter().rev().nth(depth),
+            Side::Ask => self.asks.iter().nth(depth),
+        };
+
+        if let Some((&price, level)) = next {
+            self.orderbook.apply(
+                BookUpdate::New {
+                    side,
+                    price,
+                    size: level.size,
+                },
+                timestamp_ns,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(order_id: u64, side: Side, price: i64, size: u64) -> OrderEvent {
+        OrderEvent::Add {
+            order_id,
+            side,
+            price,
+            size,
+        }
+    }
+
+    #[test]
+    fn test_aggregates_into_levels() {
+        let mut book = L3OrderBook::new(Arc::new(OrderBook::new(2)));
+
+        book.apply(add(1, Side::Bid, 450000, 100), 1).unwrap();
+        book.apply(add(2, Side::Bid, 450000, 50), 2).unwrap();
+        book.apply(add(3, Side::Bid, 449000, 70), 3).unwrap();
+        book.apply(add(4, Side::Ask, 451000, 80), 4).unwrap();
+
+        assert_eq!(book.best_bid(), (45.0, 150));
+        assert_eq!(book.best_ask(), (45.1, 80));
+        assert_eq!(book.orderbook().get_bids(10), vec![(45.0, 150), (44.9, 70)]);
+
+        book.apply(OrderEvent::Cancel { order_id: 1 }, 5).unwrap();
+        assert_eq!(book.best_bid(), (45.0, 50));
+
+        book.apply(OrderEvent::Cancel { order_id: 2 }, 6).unwrap();
+        assert_eq!(book.best_bid(), (44.9, 70));
+        assert_eq!(book.order_count(), 2);
+
+        assert!(book.apply(OrderEvent::Cancel { order_id: 1 }, 7).is_err());
+        assert!(book.apply(add(3, Side::Ask, 1, 1), 8).is_err());
+    }
+
+    #[test]
+    fn test_queue_position() {
+        let mut book = L3OrderBook::new(Arc::new(OrderBook::new(2)));
+
+        book.apply(add(10, Side::Ask, 500000, 40), 1).unwrap();
+        book.apply(add(11, Side::Ask, 500000, 60), 2).unwrap();
+        // Our resting hedge order
+        book.apply(add(99, Side::Ask, 500000, 25), 3).unwrap();
+
+        let pos = book.queue_position(99).unwrap();
+        assert_eq!(pos.orders_ahead, 2);
+        assert_eq!(pos.size_ahead, 100);
+        assert_eq!(pos.level_size, 125);
+
+        // Partial fill of the head keeps the order; full fill removes it
+        book.apply(
+            OrderEvent::Execute {
+                order_id: 10,
+                size: 15,
+            },
+            4,
+        )
+        .unwrap();
+        assert_eq!(book.queue_position(99).unwrap().size_ahead, 85);
+        assert_eq!(book.orderbook().last_trade(), Some((50.0, 15)));
+
+        book.apply(
+            OrderEvent::Execute {
+                order_id: 10,
+                size: 25,
+            },
+            5,
+        )
+        .unwrap();
+        let pos = book.queue_position(99).unwrap();
+        assert_eq!(pos.orders_ahead, 1);
+        assert_eq!(pos.size_ahead, 60);
+        assert!(!book.contains(10));
+        assert_eq!(book.queue_position(10), None);
+    }
+
+    #[test]
+    fn test_modify_priority() {
+        let mut book = L3OrderBook::new(Arc::new(OrderBook::new(2)));
+
+        book.apply(add(1, Side::Bid, 450000, 100), 1).unwrap();
+        book.apply(add(2, Side::Bid, 450000, 100), 2).unwrap();
+
+        // Size reduction keeps priority
+        let reduce = OrderEvent::Modify {
+            order_id: 1,
+            price: 450000,
+            size: 60,
+        };
+        book.apply(reduce, 3).unwrap();
+        assert_eq!(book.queue_position(1).unwrap().orders_ahead, 0);
+        assert_eq!(book.best_bid(), (45.0, 160));
+
+        // Size increase loses priority
+        let increase = OrderEvent::Modify {
+            order_id: 1,
+            price: 450000,
+            size: 120,
+        };
+        book.apply(increase, 4).unwrap();
+        assert_eq!(book.queue_position(1).unwrap().orders_ahead, 1);
+        assert_eq!(book.best_bid(), (45.0, 220));
+
+        // Price change moves the order to a new level
+        let reprice = OrderEvent::Modify {
+            order_id: 2,
+            price: 451000,
+            size: 100,
+        };
+        book.apply(reprice, 5).unwrap();
+        assert_eq!(book.best_bid(), (45.1, 100));
+        assert_eq!(book.level_count(Side::Bid), 2);
+    }
+
+    #[test]
+    fn test_zero_sizes() {
+        let mut book = L3OrderBook::new(Arc::new(OrderBook::new(2)));
+
+        book.apply(add(1, Side::Ask, 500000, 40), 1).unwrap();
+        assert!(book.apply(add(2, Side::Ask, 501000, 0), 2).is_err());
+        assert!(!book.contains(2));
+        assert_eq!(book.level_count(Side::Ask), 1);
+
+        let execute = OrderEvent::Execute {
+            order_id: 1,
+            size: 0,
+        };
+        book.apply(execute, 3).unwrap();
+        assert_eq!(book.orderbook().last_trade(), None);
+        assert_eq!(book.queue_position(1).unwrap().size, 40);
+
+        // Unknown orders still fail
+        let execute = OrderEvent::Execute {
+            order_id: 7,
+            size: 0,
+        };
+        assert!(book.apply(execute, 4).is_err());
+    }
+
+    #[test]
+    fn test_deep_levels_promoted() {
+        let mut book = L3OrderBook::new(Arc::new(OrderBook::new(2)));
+
+        // 12 ask levels; only 10 fit the level view
+        for i in 0..12u64 {
+            let price = 500000 + i as i64 * 1000;
+            book.apply(add(i, Side::Ask, price, 10 + i), i).unwrap();
+        }
+        assert_eq!(book.level_count(Side::Ask), 12);
+        assert_eq!(book.orderbook().depth(Side::Ask), 10);
+
+        book.apply(OrderEvent::Cancel { order_id: 0 }, 20).unwrap();
+        let asks = book.orderbook().get_asks(10);
+        assert_eq!(asks.len(), 10);
+        assert_eq!(asks[0], (50.1, 11));
+        assert_eq!(asks[9], (51.0, 20));
+
+        book.apply(OrderEvent::Clear, 21).unwrap();
+        assert_eq!(book.order_count(), 0);
+        assert_eq!(book.orderbook().depth(Side::Ask), 0);
+    }
+}
@@ -1,7 +1,21 @@
 //! Market data structures and processing
 
+mod csv;
+mod health;
+mod instrument;
+mod journal;
+mod l3_book;
 mod orderbook;
+mod sequence;
+mod snapshot;
 mod tick;
 
-pub use orderbook::OrderBook;
-pub use tick::{MarketTick, Side};
+pub use csv::{CsvColumn, CsvFormat, HeaderMode, TickCsvReader, TickCsvWriter};
+pub use health::{BookHealthChecker, BookIssue, HealthLimits};
+pub use instrument::{Commodity, Instrument, InstrumentRegistry};
+pub use journal::{JournalIter, JournalReceivedIter, TickJournalReader, TickJournalWriter};
+pub use l3_book::{L3OrderBook, OrderEvent, QueuePosition};
+pub use orderbook::{BookUpdate, OrderBook, ReferencePrice};
+pub use sequence::{SequenceGap, SequenceReset, SequenceStatus, SequenceTracker};
+pub use snapshot::{BookSnapshot, FillEstimate};
+pub use tick::{MarketTick, PRICE_SCALE, ReceivedTick, Side, TickEvent, to_fixed_price};
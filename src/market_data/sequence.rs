@@ -0,0 +1,233 @@
+//! Per-channel feed sequence tracking and gap detection
+
+use crate::market_data::MarketTick;
+
+/// Range of messages missing on one feed channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceGap {
+    /// Feed channel
+    pub channel: u8,
+
+    /// First missing sequence number
+    pub first: u64,
+
+    /// Last missing sequence number (inclusive)
+    pub last: u64,
+}
+
+impl SequenceGap {
+    /// Number of missing messages
+    #[inline]
+    pub fn missing(&self) -> u64 {
+        self.last - self.first + 1
+    }
+}
+
+/// Restart of a feed channel's sequence numbers (e.g. a new session)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceReset {
+    /// Feed channel
+    pub channel: u8,
+
+    /// Sequence number that was expected
+    pub expected: u64,
+
+    /// Sequence number received
+    pub sequence: u64,
+}
+
+/// Outcome of checking a tick's sequence number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceStatus {
+    /// Tick carries no sequence number
+    Unsequenced,
+
+    /// Tick is the next expected message (or the first on its channel)
+    InOrder,
+
+    /// Messages were skipped before this tick
+    Gap(SequenceGap),
+
+    /// Tick was already seen (retransmission or replay)
+    Duplicate,
+
+    /// The channel restarted its sequence numbers; tracking continues
+    /// from this tick
+    Reset(SequenceReset),
+}
+
+/// Tracks the expected sequence number of every feed channel
+///
+/// Also records which symbols have been seen on each channel, so the
+/// books affected by a gap can be identified.
+#[derive(Debug, Clone)]
+pub struct SequenceTracker {
+    /// Next expected sequence per channel (0 = nothing seen yet)
+    expected: Vec<u64>,
+
+    /// Symbols seen per channel (256-bit set)
+    symbols: Vec<[u64; 4]>,
+}
+
+impl SequenceTracker {
+    /// Backwards jump treated as a reset rather than a duplicate
+    pub const RESET_DISTANCE: u64 = 1_000_000;
+
+    /// Create a tracker with no channel history
+    pub fn new() -> Self {
+        Self {
+            expected: vec![0; 256],
+            symbols: vec![[0; 4]; 256],
+        }
+    }
+
+    /// Check a tick against its channel and advance the expected sequence
+    ///
+    /// After a gap, tracking continues from the received tick.
+    /// Duplicates do not move the expected sequence. Sequence 1, or a jump
+    /// back by at least `RESET_DISTANCE`, is a reset: the channel restarted
+    /// (e.g. a new session) and tracking continues from the received tick.
+    #[inline]
+    pub fn check(&mut self, tick: &MarketTick) -> SequenceStatus {
+        if !tick.is_sequenced() {
+            return SequenceStatus::Unsequenced;
+        }
+
+        let channel = tick.channel as usize;
+        let symbol = tick.symbol_id as usize;
+        self.symbols[channel][symbol / 64] |= 1 << (symbol % 64);
+
+        let expected = self.expected[channel];
+        if expected != 0 && tick.sequence < expected {
+            if tick.sequence != 1 && expected - tick.sequence < Self::RESET_DISTANCE {
+                return SequenceStatus::Duplicate;
+            }
+
+            self.expected[channel] = tick.sequence + 1;
+            return SequenceStatus::Reset(SequenceReset {
+                channel: tick.channel,
+                expected,
+                sequence: tick.sequence,
+            });
+        }
+
+        self.expected[channel] = tick.sequence + 1;
+
+        if expected != 0 && tick.sequence > expected {
+            SequenceStatus::Gap(SequenceGap {
+                channel: tick.channel,
+                first: expected,
+                last: tick.sequence - 1,
+            })
+        } else {
+            SequenceStatus::InOrder
+        }
+    }
+
+    /// Next expected sequence on a channel (None if nothing seen yet)
+    pub fn expected(&self, channel: u8) -> Option<u64> {
+        let expected = self.expected[channel as usize];
+        (expected != 0).then_some(expected)
+    }
+
+    /// Symbols seen on a channel
+    pub fn symbols(&self, channel: u8) -> impl Iterator<Item = u8> + '_ {
+        let set = &self.symbols[channel as usize];
+        (0..=255u8).filter(move |&s| set[s as usize / 64] & (1 << (s % 64)) != 0)
+    }
+
+    /// Forget the expected sequence of a channel
+    ///
+    /// Call after resynchronizing (e.g., a snapshot reload); the next tick
+    /// on the channel is accepted as in order.
+    pub fn reset(&mut self, channel: u8) {
+        self.expected[channel as usize] = 0;
+    }
+}
+
+impl Default for SequenceTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(channel: u8, sequence: u64, symbol_id: u8) -> MarketTick {
+        MarketTick::bid(1000, 45.0, 100, symbol_id).with_sequence(channel, sequence)
+    }
+
+    #[test]
+    fn test_in_order_and_gap() {
+        let mut tracker = SequenceTracker::new();
+
+        assert_eq!(tracker.check(&tick(1, 10, 1)), SequenceStatus::InOrder);
+        assert_eq!(tracker.check(&tick(1, 11, 2)), SequenceStatus::InOrder);
+
+        let status = tracker.check(&tick(1, 15, 1));
+        let gap = SequenceGap {
+            channel: 1,
+            first: 12,
+            last: 14,
+        };
+        assert_eq!(status, SequenceStatus::Gap(gap));
+        assert_eq!(gap.missing(), 3);
+
+        assert_eq!(tracker.expected(1), Some(16));
+        assert_eq!(tracker.symbols(1).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_channels_are_independent() {
+        let mut tracker = SequenceTracker::new();
+
+        tracker.check(&tick(1, 100, 1));
+        assert_eq!(tracker.check(&tick(2, 5, 200)), SequenceStatus::InOrder);
+        assert_eq!(tracker.check(&tick(1, 101, 1)), SequenceStatus::InOrder);
+        assert_eq!(tracker.symbols(2).collect::<Vec<_>>(), vec![200]);
+        assert_eq!(tracker.expected(3), None);
+    }
+
+    #[test]
+    fn test_duplicates_unsequenced_and_reset() {
+        let mut tracker = SequenceTracker::new();
+
+        tracker.check(&tick(1, 10, 1));
+        assert_eq!(tracker.check(&tick(1, 10, 1)), SequenceStatus::Duplicate);
+        assert_eq!(tracker.expected(1), Some(11));
+
+        let plain = MarketTick::bid(1000, 45.0, 100, 1);
+        assert_eq!(tracker.check(&plain), SequenceStatus::Unsequenced);
+
+        tracker.reset(1);
+        assert_eq!(tracker.check(&tick(1, 3, 1)), SequenceStatus::InOrder);
+    }
+
+    #[test]
+    fn test_session_reset() {
+        let mut tracker = SequenceTracker::new();
+
+        tracker.check(&tick(1, 500, 1));
+        let reset = SequenceReset {
+            channel: 1,
+            expected: 501,
+            sequence: 1,
+        };
+        assert_eq!(tracker.check(&tick(1, 1, 1)), SequenceStatus::Reset(reset));
+        assert_eq!(tracker.check(&tick(1, 2, 1)), SequenceStatus::InOrder);
+
+        // Far behind: a restart that does not begin at 1
+        tracker.check(&tick(2, 5_000_000, 1));
+        assert_eq!(
+            tracker.check(&tick(2, 4_999_000, 1)),
+            SequenceStatus::Duplicate
+        );
+        assert!(matches!(
+            tracker.check(&tick(2, 100, 1)),
+            SequenceStatus::Reset(_)
+        ));
+        assert_eq!(tracker.expected(2), Some(101));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::market_data::{OrderBook, Side};
+
+/// Result of walking the book for a given quantity
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FillEstimate {
+    /// Quantity fillable from visible depth
+    pub filled: f64,
+
+    /// Quantity left once visible depth is exhausted
+    pub unfilled: f64,
+
+    /// Volume-weighted average price of the filled quantity
+    pub vwap: Option<f64>,
+
+    /// Price of the last level touched
+    pub worst_price: Option<f64>,
+
+    /// Number of levels consumed
+    pub levels: usize,
+}
+
+impl FillEstimate {
+    /// Fraction of the requested quantity covered by visible depth
+    #[inline]
+    pub fn coverage(&self) -> f64 {
+        let requested = self.filled + self.unfilled;
+        if requested > 0.0 {
+            self.filled / requested
+        } else {
+            1.0
+        }
+    }
+
+    /// Check whether visible depth covers the full quantity
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.unfilled <= 0.0
+    }
+}
+
+/// Consistent point-in-time view of an `OrderBook`
+///
+/// Produced by `OrderBook::snapshot`. All levels, the timestamp and the
+/// sequence number come from the same book state. Fixed-size storage keeps
+/// snapshots allocation-free on the hot path.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct BookSnapshot {
+    /// Symbol identifier
+    pub symbol_id: u8,
+
+    /// Number of completed book updates at snapshot time
+    pub sequence: u64,
+
+    /// Timestamp of the last update included (nanoseconds)
+    pub timestamp_ns: u64,
+
+    /// Bid levels (price, size), best first
+    pub(crate) bids: [(f64, u64); OrderBook::MAX_LEVELS],
+
+    /// Ask levels (price, size), best first
+    pub(crate) asks: [(f64, u64); OrderBook::MAX_LEVELS],
+
+    /// Number of valid bid levels
+    pub(crate) bid_levels: usize,
+
+    /// Number of valid ask levels
+    pub(crate) ask_levels: usize,
+}
+
+impl BookSnapshot {
+    /// Get bid levels, best first
+    #[inline(always)]
+    pub fn bids(&self) -> &[(f64, u64)] {
+        &self.bids[..self.bid_levels]
+    }
+
+    /// Get ask levels, best first
+    #[inline(always)]
+    pub fn asks(&self) -> &[(f64, u64)] {
+        &self.asks[..self.ask_levels]
+    }
+
+    /// Get the best bid, if any
+    #[inline(always)]
+    pub fn best_bid(&self) -> Option<(f64, u64)> {
+        self.bids().first().copied()
+    }
+
+    /// Get the best ask, if any
+    #[inline(always)]
+    pub fn best_ask(&self) -> Option<(f64, u64)> {
+        self.asks().first().copied()
+    }
+
+    /// Get mid price, if both sides are populated
+    #[inline(always)]
+    pub fn mid_price(&self) -> Option<f64> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        Some((bid + ask) / 2.0)
+    }
+
+    /// Walk one side of the book to fill `quantity`
+    ///
+    /// `side` is the side consumed: `Ask` to buy, `Bid` to sell. Quantity
+    /// is in book size units (lots).
+    pub fn estimate_fill(&self, side: Side, quantity: f64) -> FillEstimate {
+        let levels = match side {
+            Side::Bid => self.bids(),
+            Side::Ask => self.asks(),
+        };
+
+        let mut estimate = FillEstimate {
+            unfilled: quantity.max(0.0),
+            ..Default::default()
+        };
+        let mut notional = 0.0;
+
+        for &(price, size) in levels {
+            if estimate.unfilled <= 0.0 {
+                break;
+            }
+
+            let take = estimate.unfilled.min(size as f64);
+            notional += take * price;
+            estimate.filled += take;
+            estimate.unfilled -= take;
+            estimate.worst_price = Some(price);
+            estimate.levels += 1;
+        }
+
+        if estimate.filled > 0.0 {
+            estimate.vwap = Some(notional / estimate.filled);
+        }
+
+        estimate
+    }
+
+    /// Get spread in basis points, if both sides are populated
+    ///
+    /// Measured relative to the absolute mid, so negative prices are handled.
+    #[inline(always)]
+    pub fn spread_bps(&self) -> Option<f64> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        let mid = (bid + ask) / 2.0;
+
+        if mid != 0.0 {
+            Some(((ask - bid) / mid.abs()) * 10000.0)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::market_data::{OrderBook, Side};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_estimate_fill() {
+        let ob = OrderBook::new(2);
+        ob.update_ask(0, 500000, 120, 1);
+        ob.update_ask(1, 501000, 200, 2);
+        ob.update_ask(2, 503000, 100, 3);
+        ob.update_bid(0, 499000, 50, 4);
+
+        let snap = ob.snapshot(10);
+
+        // Inside level 0
+        let fill = snap.estimate_fill(Side::Ask, 100.0);
+        assert_eq!(fill.vwap, Some(50.0));
+        assert_eq!(fill.worst_price, Some(50.0));
+        assert_eq!(fill.levels, 1);
+        assert!(fill.is_complete());
+
+        // Across two levels
+        let fill = snap.estimate_fill(Side::Ask, 220.0);
+        let expected = (120.0 * 50.0 + 100.0 * 50.1) / 220.0;
+        assert!((fill.vwap.unwrap() - expected).abs() < 1e-9);
+        assert_eq!(fill.worst_price, Some(50.1));
+        assert_eq!(fill.levels, 2);
+
+        // More than visible depth
+        let fill = snap.estimate_fill(Side::Ask, 1_000.0);
+        assert_eq!(fill.filled, 420.0);
+        assert_eq!(fill.unfilled, 580.0);
+        assert_eq!(fill.worst_price, Some(50.3));
+        assert!((fill.coverage() - 0.42).abs() < 1e-12);
+
+        // Empty side
+        let fill = OrderBook::new(1)
+            .snapshot(10)
+            .estimate_fill(Side::Bid, 10.0);
+        assert_eq!(fill.vwap, None);
+        assert_eq!(fill.coverage(), 0.0);
+    }
+
+    #[test]
+    fn test_snapshot_basic() {
+        let ob = OrderBook::new(2);
+
+        ob.update_bid(0, 450000, 100, 1000);
+        ob.update_bid(1, 449000, 200, 1001);
+        ob.update_ask(0, 451000, 110, 1002);
+
+        let snap = ob.snapshot(10);
+        assert_eq!(snap.symbol_id, 2);
+        assert_eq!(snap.sequence, 3);
+        assert_eq!(snap.timestamp_ns, 1002);
+        assert_eq!(snap.bids(), &[(45.0, 100), (44.9, 200)]);
+        assert_eq!(snap.asks(), &[(45.1, 110)]);
+        assert_eq!(snap.mid_price(), Some(45.05));
+
+        let top = ob.snapshot(1);
+        assert_eq!(top.bids().len(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_empty_book() {
+        let ob = OrderBook::new(1);
+
+        let snap = ob.try_snapshot(10).unwrap();
+        assert_eq!(snap.best_bid(), None);
+        assert_eq!(snap.mid_price(), None);
+        assert_eq!(snap.spread_bps(), None);
+    }
+
+    #[test]
+    fn test_snapshot_never_torn() {
+        let ob = Arc::new(OrderBook::new(1));
+        let done = Arc::new(AtomicBool::new(false));
+
+        // Writer keeps bid and ask sizes equal to the price offset, so a torn
+        // read shows up as a mismatch between levels or sides.
+        let writer = {
+            let ob = Arc::clone(&ob);
+            let done = Arc::clone(&done);
+            std::thread::spawn(move || {
+                for i in 1..20_000u64 {
+                    ob.insert_level(Side::Bid, 450000 - i as i64, i, i);
+                    ob.insert_level(Side::Ask, 460000 + i as i64, i, i);
+                    ob.clear_side(Side::Ask, i);
+                    ob.clear_side(Side::Bid, i);
+                }
+                done.store(true, Ordering::Release);
+            })
+        };
+
+        while !done.load(Ordering::Acquire) {
+            let snap = ob.snapshot(10);
+            assert!(snap.bids().len() <= 1);
+            assert!(snap.asks().len() <= snap.bids().len());
+
+            if let (Some((bid, bid_size)), Some((ask, ask_size))) =
+                (snap.best_bid(), snap.best_ask())
+            {
+                assert_eq!(bid_size, ask_size);
+                assert!((bid - (45.0 - bid_size as f64 / 10000.0)).abs() < 1e-9);
+                assert!((ask - (46.0 + ask_size as f64 / 10000.0)).abs() < 1e-9);
+                assert_eq!(snap.timestamp_ns, bid_size);
+            }
+        }
+
+        writer.join().unwrap();
+    }
+}
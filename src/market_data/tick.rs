@@ -9,61 +9,292 @@ pub enum Side {
     Ask = 1,
 }
 
+impl Side {
+    /// The other side of the book
+    #[inline(always)]
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        }
+    }
+}
+
+/// Market data event carried by a tick
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum TickEvent {
+    /// Top-of-book quote update (bid or ask)
+    Quote = 0,
+    /// Last-trade print
+    Trade = 1,
+    /// Official settlement price
+    Settlement = 2,
+    /// All resting orders removed from the book
+    BookClear = 3,
+    /// Trading halted; the book is cleared until trading resumes
+    Halt = 4,
+}
+
+impl TickEvent {
+    /// Decode an event byte
+    #[inline(always)]
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(TickEvent::Quote),
+            1 => Some(TickEvent::Trade),
+            2 => Some(TickEvent::Settlement),
+            3 => Some(TickEvent::BookClear),
+            4 => Some(TickEvent::Halt),
+            _ => None,
+        }
+    }
+}
+
+/// Fixed-point price scale (4 decimal places)
+pub const PRICE_SCALE: f64 = 10000.0;
+
+/// Convert a price to fixed-point
+///
+/// Rounds to the nearest tick instead of truncating, so negative prices
+/// and values like 45.5555 (not exact in binary) convert symmetrically.
+#[inline(always)]
+pub fn to_fixed_price(price: f64) -> i64 {
+    (price * PRICE_SCALE).round() as i64
+}
+
 /// Compact market data tick (32 bytes)
 ///
-/// Optimized for cache efficiency and minimal memory footprint.
+/// Optimized for cache efficiency and minimal memory footprint. The first
+/// 24 bytes hold the market event and feed channel; the last 8 carry the
+/// per-channel sequence number. The local receive time is not part of the
+/// wire format (see `ReceivedTick`).
 #[repr(C)]
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct MarketTick {
-    /// Timestamp in nanoseconds (epoch)
+    /// Exchange timestamp in nanoseconds (epoch)
     pub timestamp_ns: u64,
 
+    /// Price (fixed-point: price * scale, `PRICE_SCALE` unless the
+    /// instrument sets its own; may be zero or negative)
     pub price: i64,
 
     /// Quantity in lots
     pub quantity: u32,
 
     /// Side (0=bid, 1=ask)
+    /// For trades: side of the resting order that was filled
     pub side: u8,
 
     /// Symbol identifier
     pub symbol_id: u8,
 
-    /// Padding to align to 32 bytes
-    _padding: [u8; 6],
+    /// Event type (see `TickEvent`, 0=quote)
+    pub event: u8,
+
+    /// Feed channel the sequence number belongs to
+    pub channel: u8,
+
+    /// Per-channel feed sequence number (0 if unsequenced)
+    pub sequence: u64,
 }
 
 impl MarketTick {
+    /// Size of a tick on the wire and in memory
+    pub const SIZE: usize = std::mem::size_of::<MarketTick>();
+
     /// Create a BID tick
+    ///
+    /// `price` is converted with the default `PRICE_SCALE`. For instruments
+    /// registered with another scale use `Instrument::bid` or `bid_raw`.
     #[inline]
     pub fn bid(timestamp_ns: u64, price: f64, quantity: u32, symbol_id: u8) -> Self {
-        Self {
+        Self::bid_raw(timestamp_ns, to_fixed_price(price), quantity, symbol_id)
+    }
+
+    /// Create an ASK tick
+    ///
+    /// `price` is converted with the default `PRICE_SCALE`. For instruments
+    /// registered with another scale use `Instrument::ask` or `ask_raw`.
+    #[inline]
+    pub fn ask(timestamp_ns: u64, price: f64, quantity: u32, symbol_id: u8) -> Self {
+        Self::ask_raw(timestamp_ns, to_fixed_price(price), quantity, symbol_id)
+    }
+
+    /// Create a last-trade print
+    ///
+    /// `price` is converted with the default `PRICE_SCALE`. For instruments
+    /// registered with another scale use `Instrument::trade` or `trade_raw`.
+    #[inline]
+    pub fn trade(timestamp_ns: u64, price: f64, quantity: u32, side: Side, symbol_id: u8) -> Self {
+        Self::trade_raw(
             timestamp_ns,
-            price: (price * 10000.0) as i64,
+            to_fixed_price(price),
             quantity,
-            side: Side::Bid as u8,
+            side,
             symbol_id,
-            _padding: [0; 6],
-        }
+        )
     }
 
-    /// Create an ASK tick
+    /// Create a settlement price event
+    ///
+    /// `price` is converted with the default `PRICE_SCALE`. For instruments
+    /// registered with another scale use `Instrument::settlement` or
+    /// `settlement_raw`.
     #[inline]
-    pub fn ask(timestamp_ns: u64, price: f64, quantity: u32, symbol_id: u8) -> Self {
+    pub fn settlement(timestamp_ns: u64, price: f64, symbol_id: u8) -> Self {
+        Self::settlement_raw(timestamp_ns, to_fixed_price(price), symbol_id)
+    }
+
+    /// Create a BID tick from a fixed-point price
+    #[inline]
+    pub fn bid_raw(timestamp_ns: u64, price: i64, quantity: u32, symbol_id: u8) -> Self {
+        Self::build(
+            timestamp_ns,
+            price,
+            quantity,
+            Side::Bid,
+            symbol_id,
+            TickEvent::Quote,
+        )
+    }
+
+    /// Create an ASK tick from a fixed-point price
+    #[inline]
+    pub fn ask_raw(timestamp_ns: u64, price: i64, quantity: u32, symbol_id: u8) -> Self {
+        Self::build(
+            timestamp_ns,
+            price,
+            quantity,
+            Side::Ask,
+            symbol_id,
+            TickEvent::Quote,
+        )
+    }
+
+    /// Create a last-trade print from a fixed-point price
+    #[inline]
+    pub fn trade_raw(
+        timestamp_ns: u64,
+        price: i64,
+        quantity: u32,
+        side: Side,
+        symbol_id: u8,
+    ) -> Self {
+        Self::build(
+            timestamp_ns,
+            price,
+            quantity,
+            side,
+            symbol_id,
+            TickEvent::Trade,
+        )
+    }
+
+    /// Create a settlement price event from a fixed-point price
+    #[inline]
+    pub fn settlement_raw(timestamp_ns: u64, price: i64, symbol_id: u8) -> Self {
+        Self::build(
+            timestamp_ns,
+            price,
+            0,
+            Side::Bid,
+            symbol_id,
+            TickEvent::Settlement,
+        )
+    }
+
+    /// Create a book-cleared event
+    #[inline]
+    pub fn book_clear(timestamp_ns: u64, symbol_id: u8) -> Self {
+        Self::status(timestamp_ns, symbol_id, TickEvent::BookClear)
+    }
+
+    /// Create a trading-halted event
+    #[inline]
+    pub fn halt(timestamp_ns: u64, symbol_id: u8) -> Self {
+        Self::status(timestamp_ns, symbol_id, TickEvent::Halt)
+    }
+
+    #[inline]
+    fn status(timestamp_ns: u64, symbol_id: u8, event: TickEvent) -> Self {
+        Self::build(timestamp_ns, 0, 0, Side::Bid, symbol_id, event)
+    }
+
+    #[inline]
+    fn build(
+        timestamp_ns: u64,
+        price: i64,
+        quantity: u32,
+        side: Side,
+        symbol_id: u8,
+        event: TickEvent,
+    ) -> Self {
         Self {
             timestamp_ns,
-            price: (price * 10000.0) as i64,
+            price,
             quantity,
-            side: Side::Ask as u8,
+            side: side as u8,
             symbol_id,
-            _padding: [0; 6],
+            event: event as u8,
+            channel: 0,
+            sequence: 0,
         }
     }
 
-    /// Convert fixed-point price to f64
+    /// Set the feed channel and sequence number
+    #[inline]
+    pub fn with_sequence(mut self, channel: u8, sequence: u64) -> Self {
+        self.channel = channel;
+        self.sequence = sequence;
+        self
+    }
+
+    /// Check if the tick carries a feed sequence number
+    #[inline(always)]
+    pub fn is_sequenced(&self) -> bool {
+        self.sequence != 0
+    }
+
+    /// View the tick as raw bytes (wire format)
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8; Self::SIZE] {
+        // SAFETY: repr(C) with no implicit padding, every byte is initialized
+        unsafe { &*(self as *const Self as *const [u8; Self::SIZE]) }
+    }
+
+    /// Decode a tick from raw bytes (wire format)
+    #[inline(always)]
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+        // SAFETY: every bit pattern is a valid MarketTick
+        unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const Self) }
+    }
+
+    /// Decode the event type (None for unknown event bytes)
+    #[inline(always)]
+    pub fn event(&self) -> Option<TickEvent> {
+        TickEvent::from_u8(self.event)
+    }
+
+    /// Check if this is a quote update
+    #[inline(always)]
+    pub fn is_quote(&self) -> bool {
+        self.event == TickEvent::Quote as u8
+    }
+
+    /// Check if this is a trade print
+    #[inline(always)]
+    pub fn is_trade(&self) -> bool {
+        self.event == TickEvent::Trade as u8
+    }
+
+    /// Convert fixed-point price to f64 using the default `PRICE_SCALE`
+    ///
+    /// For instruments registered with another scale use
+    /// `Instrument::price_f64(tick.price)`.
     #[inline(always)]
     pub fn price_f64(&self) -> f64 {
-        (self.price as f64) / 10000.0
+        (self.price as f64) / PRICE_SCALE
     }
 
     /// Check if this is a BID
@@ -87,10 +318,20 @@ impl MarketTick {
 
 impl fmt::Display for MarketTick {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.event() {
+            Some(TickEvent::Quote) if self.is_bid() => "BID",
+            Some(TickEvent::Quote) => "ASK",
+            Some(TickEvent::Trade) => "TRADE",
+            Some(TickEvent::Settlement) => "SETTLE",
+            Some(TickEvent::BookClear) => return write!(f, "CLEAR ({})", self.timestamp_ns),
+            Some(TickEvent::Halt) => return write!(f, "HALT ({})", self.timestamp_ns),
+            None => "UNKNOWN",
+        };
+
         write!(
             f,
             "{} {:>8.2} @ {:>6} ({})",
-            if self.is_bid() { "BID" } else { "ASK" },
+            label,
             self.price_f64(),
             self.quantity,
             self.timestamp_ns
@@ -106,13 +347,40 @@ impl fmt::Debug for MarketTick {
             .field("quantity", &self.quantity)
             .field("side", if self.is_bid() { &"BID" } else { &"ASK" })
             .field("symbol_id", &self.symbol_id)
+            .field("event", &self.event())
+            .field("channel", &self.channel)
+            .field("sequence", &self.sequence)
             .finish()
     }
 }
 
-// Ensure the size is exactly 32 bytes
+// Ensure the size is exactly 32 bytes (no implicit padding)
 static_assertions::const_assert_eq!(std::mem::size_of::<MarketTick>(), 32);
 
+/// Tick stamped with its local receive time by the feed
+#[derive(Debug, Copy, Clone)]
+pub struct ReceivedTick {
+    /// Tick as decoded from the wire
+    pub tick: MarketTick,
+
+    /// Local receive timestamp in nanoseconds (epoch)
+    pub receive_ns: u64,
+}
+
+impl ReceivedTick {
+    /// Stamp a tick with its receive time
+    #[inline(always)]
+    pub fn new(tick: MarketTick, receive_ns: u64) -> Self {
+        Self { tick, receive_ns }
+    }
+
+    /// Exchange-to-receive latency in nanoseconds
+    #[inline]
+    pub fn feed_latency_ns(&self) -> u64 {
+        self.receive_ns.saturating_sub(self.tick.timestamp_ns)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,6 +399,7 @@ mod tests {
     #[test]
     fn test_tick_size() {
         assert_eq!(std::mem::size_of::<MarketTick>(), 32);
+        assert_eq!(MarketTick::SIZE, 32);
     }
 
     #[test]
@@ -145,5 +414,71 @@ mod tests {
     fn test_fixed_point_conversion() {
         let tick = MarketTick::bid(1000000, 45.5555, 100, 1);
         assert!((tick.price_f64() - 45.5555).abs() < 0.0001);
+        assert_eq!(tick.price, 455555);
+    }
+
+    #[test]
+    fn test_event_types() {
+        let quote = MarketTick::ask(1000, 45.0, 100, 1);
+        assert_eq!(quote.event(), Some(TickEvent::Quote));
+        assert!(quote.is_quote());
+
+        let trade = MarketTick::trade(1000, 45.25, 30, Side::Ask, 1);
+        assert!(trade.is_trade());
+        assert!(trade.is_ask());
+        assert_eq!(trade.price_f64(), 45.25);
+        assert_eq!(format!("{}", trade), "TRADE    45.25 @     30 (1000)");
+
+        let settle = MarketTick::settlement(1000, 44.8, 2);
+        assert_eq!(settle.event(), Some(TickEvent::Settlement));
+
+        assert_eq!(
+            MarketTick::book_clear(1000, 2).event(),
+            Some(TickEvent::BookClear)
+        );
+        assert_eq!(MarketTick::halt(1000, 2).event(), Some(TickEvent::Halt));
+    }
+
+    #[test]
+    fn test_event_byte_layout() {
+        let tick = MarketTick::halt(1000, 7);
+        let bytes = tick.as_bytes();
+
+        // side, symbol_id, event follow the 20 bytes of timestamp/price/quantity
+        assert_eq!(bytes[21], 7);
+        assert_eq!(bytes[22], TickEvent::Halt as u8);
+        assert_eq!(TickEvent::from_u8(bytes[22]), Some(TickEvent::Halt));
+        assert_eq!(TickEvent::from_u8(200), None);
+    }
+
+    #[test]
+    fn test_negative_and_zero_prices() {
+        let tick = MarketTick::ask(1000000, -12.3456, 100, 1);
+        assert_eq!(tick.price, -123456);
+        assert_eq!(tick.price_f64(), -12.3456);
+
+        let tick = MarketTick::bid(1000000, -0.00004, 100, 1);
+        assert_eq!(tick.price, 0);
+
+        assert_eq!(to_fixed_price(-45.5555), -to_fixed_price(45.5555));
+    }
+
+    #[test]
+    fn test_feed_metadata() {
+        let tick = MarketTick::bid(1_000_000, 45.0, 100, 1).with_sequence(3, 42);
+
+        assert!(tick.is_sequenced());
+        assert!(!MarketTick::bid(1, 45.0, 100, 1).is_sequenced());
+
+        let decoded = MarketTick::from_bytes(tick.as_bytes());
+        assert_eq!(decoded.channel, 3);
+        assert_eq!(decoded.sequence, 42);
+        assert_eq!(decoded.price, tick.price);
+        // Channel and sequence sit in the former padding bytes
+        assert_eq!(tick.as_bytes()[23], 3);
+        assert_eq!(tick.as_bytes()[24..], 42u64.to_ne_bytes());
+
+        let received = ReceivedTick::new(tick, 1_025_000);
+        assert_eq!(received.feed_latency_ns(), 25_000);
     }
 }
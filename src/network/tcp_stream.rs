@@ -1,15 +1,36 @@
 //! Standard TCP/IP networking for market data and orders
 
 use crate::Error;
-use crate::market_data::MarketTick;
+use crate::market_data::{
+    MarketTick, OrderBook, ReceivedTick, SequenceGap, SequenceReset, SequenceStatus,
+    SequenceTracker,
+};
 use std::io::{self, Read, Write};
 use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// TCP-based market data feed
+///
+/// Stamps each tick with its local receive time and checks per-channel
+/// sequence numbers. On a gap or a sequence reset, the attached orderbooks
+/// of every symbol seen on the channel are marked stale until
+/// `recover_channel`.
 pub struct TcpMarketDataFeed {
     stream: TcpStream,
     buffer: Vec<u8>,
+
+    /// Per-channel sequence tracking
+    sequences: SequenceTracker,
+
+    /// Orderbooks to mark stale on gaps, indexed by symbol ID
+    orderbooks: Vec<Option<Arc<OrderBook>>>,
+
+    /// Gaps detected since the last `take_gaps`
+    gaps: Vec<SequenceGap>,
+
+    /// Sequence resets detected since the last `take_resets`
+    resets: Vec<SequenceReset>,
 }
 
 impl TcpMarketDataFeed {
@@ -30,26 +51,116 @@ impl TcpMarketDataFeed {
         Ok(Self {
             stream,
             buffer: vec![0u8; 8192],
+            sequences: SequenceTracker::new(),
+            orderbooks: vec![None; 256],
+            gaps: Vec::new(),
+            resets: Vec::new(),
         })
     }
 
-    /// Read next tick from stream
-    pub fn read_tick(&mut self) -> Result<Option<MarketTick>, Error> {
-        // Read exactly 32 bytes (size of MarketTick)
-        match self.stream.read_exact(&mut self.buffer[..32]) {
-            Ok(_) => {
-                // Parse binary tick data
-                let tick = unsafe { std::ptr::read(self.buffer.as_ptr() as *const MarketTick) };
-                Ok(Some(tick))
+    /// Attach an orderbook to be marked stale when its channel has a gap
+    pub fn attach_orderbook(&mut self, orderbook: Arc<OrderBook>) {
+        let symbol_id = orderbook.symbol_id() as usize;
+        self.orderbooks[symbol_id] = Some(orderbook);
+    }
+
+    /// Read next tick from stream, stamped with its receive time
+    ///
+    /// Duplicate (already seen) sequence numbers are dropped; ticks after
+    /// a sequence reset are kept.
+    pub fn read_tick(&mut self) -> Result<Option<ReceivedTick>, Error> {
+        loop {
+            // Read exactly one MarketTick
+            match self.stream.read_exact(&mut self.buffer[..MarketTick::SIZE]) {
+                Ok(_) => {
+                    // Parse binary tick data
+                    let bytes = self.buffer[..MarketTick::SIZE].try_into().unwrap();
+                    let tick = MarketTick::from_bytes(bytes);
+                    let receive_ns = crate::utils::get_timestamp_ns();
+
+                    match self.sequences.check(&tick) {
+                        SequenceStatus::Duplicate => continue,
+                        SequenceStatus::Gap(gap) => self.on_gap(gap),
+                        SequenceStatus::Reset(reset) => self.on_reset(reset),
+                        SequenceStatus::InOrder | SequenceStatus::Unsequenced => {}
+                    }
+
+                    return Ok(Some(ReceivedTick::new(tick, receive_ns)));
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) if e.kind() == io::ErrorKind::TimedOut => return Ok(None),
+                Err(e) => return Err(Error::MarketData(format!("Read error: {}", e))),
+            }
+        }
+    }
+
+    /// Record a gap and mark the channel's orderbooks stale
+    fn on_gap(&mut self, gap: SequenceGap) {
+        log::warn!(
+            "Sequence gap on channel {}: missing {}..={} ({} messages)",
+            gap.channel,
+            gap.first,
+            gap.last,
+            gap.missing()
+        );
+
+        self.mark_stale(gap.channel);
+        self.gaps.push(gap);
+    }
+
+    /// Record a sequence reset and mark the channel's orderbooks stale
+    fn on_reset(&mut self, reset: SequenceReset) {
+        log::warn!(
+            "Sequence reset on channel {}: expected {}, received {}",
+            reset.channel,
+            reset.expected,
+            reset.sequence
+        );
+
+        self.mark_stale(reset.channel);
+        self.resets.push(reset);
+    }
+
+    fn mark_stale(&self, channel: u8) {
+        for symbol_id in self.sequences.symbols(channel) {
+            if let Some(ref orderbook) = self.orderbooks[symbol_id as usize] {
+                orderbook.set_stale(true);
             }
-            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
-            Err(e) if e.kind() == io::ErrorKind::TimedOut => Ok(None),
-            Err(e) => Err(Error::MarketData(format!("Read error: {}", e))),
         }
     }
 
+    /// Take the gaps detected since the last call
+    pub fn take_gaps(&mut self) -> Vec<SequenceGap> {
+        std::mem::take(&mut self.gaps)
+    }
+
+    /// Take the sequence resets detected since the last call
+    pub fn take_resets(&mut self) -> Vec<SequenceReset> {
+        std::mem::take(&mut self.resets)
+    }
+
+    /// Mark a channel as recovered
+    ///
+    /// Call once the channel's books have been resynchronized (e.g., from
+    /// a snapshot). Clears the stale flag of its orderbooks and restarts
+    /// sequence tracking from the next tick.
+    pub fn recover_channel(&mut self, channel: u8) {
+        for symbol_id in self.sequences.symbols(channel) {
+            if let Some(ref orderbook) = self.orderbooks[symbol_id as usize] {
+                orderbook.set_stale(false);
+            }
+        }
+
+        self.sequences.reset(channel);
+    }
+
+    /// Next expected sequence number on a channel
+    pub fn expected_sequence(&self, channel: u8) -> Option<u64> {
+        self.sequences.expected(channel)
+    }
+
     /// Read multiple ticks in batch
-    pub fn read_batch(&mut self, max_count: usize) -> Result<Vec<MarketTick>, Error> {
+    pub fn read_batch(&mut self, max_count: usize) -> Result<Vec<ReceivedTick>, Error> {
         let mut ticks = Vec::with_capacity(max_count);
 
         while ticks.len() < max_count {
@@ -94,3 +205,104 @@ impl TcpOrderSubmitter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_gap_detection_marks_books_stale() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            for seq in [1, 2, 5, 5, 6] {
+                let tick = MarketTick::bid(1000, 45.0, 100, 1).with_sequence(7, seq);
+                stream.write_all(tick.as_bytes()).unwrap();
+            }
+            let tick = MarketTick::ask(1000, 50.0, 100, 2).with_sequence(8, 1);
+            stream.write_all(tick.as_bytes()).unwrap();
+
+            // Keep the connection open until the client has read everything
+            done_rx.recv().unwrap();
+        });
+
+        let mut feed = TcpMarketDataFeed::connect(addr).unwrap();
+        let spot = Arc::new(OrderBook::new(1));
+        let futures = Arc::new(OrderBook::new(2));
+        feed.attach_orderbook(Arc::clone(&spot));
+        feed.attach_orderbook(Arc::clone(&futures));
+
+        let ticks = feed.read_batch(10).unwrap();
+        done_tx.send(()).unwrap();
+        server.join().unwrap();
+
+        // Duplicate of sequence 5 is dropped
+        let sequences: Vec<u64> = ticks.iter().map(|t| t.tick.sequence).collect();
+        assert_eq!(sequences, vec![1, 2, 5, 6, 1]);
+        assert!(ticks.iter().all(|t| t.receive_ns != 0));
+
+        let gaps = feed.take_gaps();
+        assert_eq!(
+            gaps,
+            vec![SequenceGap {
+                channel: 7,
+                first: 3,
+                last: 4
+            }]
+        );
+        assert!(feed.take_gaps().is_empty());
+
+        assert!(spot.is_stale());
+        assert!(!futures.is_stale());
+
+        feed.recover_channel(7);
+        assert!(!spot.is_stale());
+        assert_eq!(feed.expected_sequence(7), None);
+        assert_eq!(feed.expected_sequence(8), Some(2));
+    }
+
+    #[test]
+    fn test_sequence_reset_keeps_ticks() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // New session restarts at 1
+            for seq in [41, 42, 1, 2, 3] {
+                let tick = MarketTick::bid(1000, 45.0, 100, 1).with_sequence(7, seq);
+                stream.write_all(tick.as_bytes()).unwrap();
+            }
+            done_rx.recv().unwrap();
+        });
+
+        let mut feed = TcpMarketDataFeed::connect(addr).unwrap();
+        let spot = Arc::new(OrderBook::new(1));
+        feed.attach_orderbook(Arc::clone(&spot));
+
+        let ticks = feed.read_batch(10).unwrap();
+        done_tx.send(()).unwrap();
+        server.join().unwrap();
+
+        let sequences: Vec<u64> = ticks.iter().map(|t| t.tick.sequence).collect();
+        assert_eq!(sequences, vec![41, 42, 1, 2, 3]);
+        assert_eq!(
+            feed.take_resets(),
+            vec![SequenceReset {
+                channel: 7,
+                expected: 43,
+                sequence: 1
+            }]
+        );
+        assert!(feed.take_gaps().is_empty());
+        assert!(spot.is_stale());
+        assert_eq!(feed.expected_sequence(7), Some(4));
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::hedging::HedgeRecommendation;
 use crate::market_data::OrderBook;
+use crate::utils::{SharedClock, SystemClock};
 
 /// Trait for hedging strategies
 ///
@@ -20,7 +21,9 @@ pub trait HedgingStrategy: Send + Sync {
 
     /// Update strategy parameters (cold path)
     ///
-    /// Called periodically in the background thread
+    /// Not called by the engine itself: schedule it with
+    /// `HedgeEngine::schedule_recalibration` to run it periodically on the
+    /// recalibration thread.
     fn update_parameters(&mut self) {}
 
     /// Get a strategy name
@@ -36,6 +39,7 @@ pub trait HedgingStrategy: Send + Sync {
 pub struct StrategyBuilder {
     strategies: Vec<Box<dyn HedgingStrategy>>,
     weights: Vec<f64>,
+    clock: SharedClock,
 }
 
 impl StrategyBuilder {
@@ -44,9 +48,16 @@ impl StrategyBuilder {
         Self {
             strategies: Vec::new(),
             weights: Vec::new(),
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Use a custom time source for composite recommendations
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Add a strategy with weight
     pub fn add_strategy(mut self, strategy: Box<dyn HedgingStrategy>, weight: f64) -> Self {
         self.strategies.push(strategy);
@@ -59,6 +70,7 @@ impl StrategyBuilder {
         CompositeStrategy {
             strategies: self.strategies,
             weights: self.weights,
+            clock: self.clock,
         }
     }
 }
@@ -70,10 +82,10 @@ impl Default for StrategyBuilder {
 }
 
 /// Composite strategy that combines multiple strategies
-#[derive(Default)]
 pub struct CompositeStrategy {
     strategies: Vec<Box<dyn HedgingStrategy>>,
     weights: Vec<f64>,
+    clock: SharedClock,
 }
 
 impl CompositeStrategy {
@@ -83,6 +95,12 @@ impl CompositeStrategy {
     }
 }
 
+impl Default for CompositeStrategy {
+    fn default() -> Self {
+        StrategyBuilder::new().build()
+    }
+}
+
 impl HedgingStrategy for CompositeStrategy {
     fn calculate_hedge(
         &self,
@@ -122,7 +140,7 @@ impl HedgingStrategy for CompositeStrategy {
             crate::market_data::Side::Ask,
             crate::hedging::Urgency::Normal,
             format!("Composite strategy ({} strategies)", self.strategies.len()),
-            crate::utils::get_timestamp_ns(),
+            self.clock.now_ns(),
         ))
     }
 
@@ -20,11 +20,32 @@ pub struct Metrics {
     /// Maximum tick latency
     max_tick_latency_ns: u64,
 
+    /// Ticks with a feed receive time
+    feed_ticks: usize,
+
+    /// Sum of exchange-to-receive latencies (for average)
+    total_feed_latency_ns: u64,
+
+    /// Maximum exchange-to-receive latency
+    max_feed_latency_ns: u64,
+
     /// Total hedge volume (MWh)
     total_hedge_volume: f64,
 
     /// Latency histogram (nanoseconds)
     latency_histogram: LatencyHistogram,
+
+    /// Recalibrations run (successful or not)
+    recalibrations: usize,
+
+    /// Recalibrations that failed
+    recalibration_failures: usize,
+
+    /// Sum of recalibration durations (for average)
+    total_recalibration_ns: u64,
+
+    /// Longest recalibration
+    max_recalibration_ns: u64,
 }
 
 impl Metrics {
@@ -36,8 +57,15 @@ impl Metrics {
             total_tick_latency_ns: 0,
             min_tick_latency_ns: u64::MAX,
             max_tick_latency_ns: 0,
+            feed_ticks: 0,
+            total_feed_latency_ns: 0,
+            max_feed_latency_ns: 0,
             total_hedge_volume: 0.0,
             latency_histogram: LatencyHistogram::new(),
+            recalibrations: 0,
+            recalibration_failures: 0,
+            total_recalibration_ns: 0,
+            max_recalibration_ns: 0,
         }
     }
 
@@ -50,12 +78,29 @@ impl Metrics {
         self.latency_histogram.record(latency_ns);
     }
 
+    /// Record exchange-to-receive feed latency
+    pub fn record_feed_latency(&mut self, latency_ns: u64) {
+        self.feed_ticks += 1;
+        self.total_feed_latency_ns = self.total_feed_latency_ns.saturating_add(latency_ns);
+        self.max_feed_latency_ns = self.max_feed_latency_ns.max(latency_ns);
+    }
+
     /// Record hedge execution
     pub fn record_hedge_execution(&mut self, volume: f64) {
         self.hedges_executed += 1;
         self.total_hedge_volume += volume.abs();
     }
 
+    /// Record a recalibration run (cold path)
+    pub fn record_recalibration(&mut self, duration_ns: u64, succeeded: bool) {
+        self.recalibrations += 1;
+        if !succeeded {
+            self.recalibration_failures += 1;
+        }
+        self.total_recalibration_ns += duration_ns;
+        self.max_recalibration_ns = self.max_recalibration_ns.max(duration_ns);
+    }
+
     /// Get average tick latency (nanoseconds)
     pub fn avg_tick_latency_ns(&self) -> u64 {
         if self.ticks_processed == 0 {
@@ -80,6 +125,20 @@ impl Metrics {
         self.max_tick_latency_ns
     }
 
+    /// Get average feed latency (nanoseconds)
+    pub fn avg_feed_latency_ns(&self) -> u64 {
+        if self.feed_ticks == 0 {
+            0
+        } else {
+            self.total_feed_latency_ns / self.feed_ticks as u64
+        }
+    }
+
+    /// Get maximum feed latency (nanoseconds)
+    pub fn max_feed_latency_ns(&self) -> u64 {
+        self.max_feed_latency_ns
+    }
+
     /// Get total ticks processed
     pub fn ticks_processed(&self) -> usize {
         self.ticks_processed
@@ -95,6 +154,30 @@ impl Metrics {
         self.total_hedge_volume
     }
 
+    /// Get total recalibrations run
+    pub fn recalibrations(&self) -> usize {
+        self.recalibrations
+    }
+
+    /// Get total failed recalibrations
+    pub fn recalibration_failures(&self) -> usize {
+        self.recalibration_failures
+    }
+
+    /// Get average recalibration duration (nanoseconds)
+    pub fn avg_recalibration_ns(&self) -> u64 {
+        if self.recalibrations == 0 {
+            0
+        } else {
+            self.total_recalibration_ns / self.recalibrations as u64
+        }
+    }
+
+    /// Get longest recalibration duration (nanoseconds)
+    pub fn max_recalibration_ns(&self) -> u64 {
+        self.max_recalibration_ns
+    }
+
     /// Get latency percentile
     pub fn latency_percentile(&self, percentile: f64) -> u64 {
         self.latency_histogram.percentile(percentile)
@@ -116,7 +199,11 @@ impl Metrics {
             p50_latency_ns: self.latency_percentile(0.50),
             p95_latency_ns: self.latency_percentile(0.95),
             p99_latency_ns: self.latency_percentile(0.99),
+            avg_feed_latency_ns: self.avg_feed_latency_ns(),
+            max_feed_latency_ns: self.max_feed_latency_ns,
             total_hedge_volume: self.total_hedge_volume,
+            recalibrations: self.recalibrations,
+            recalibration_failures: self.recalibration_failures,
         }
     }
 }
@@ -138,7 +225,11 @@ pub struct MetricsSummary {
     pub p50_latency_ns: u64,
     pub p95_latency_ns: u64,
     pub p99_latency_ns: u64,
+    pub avg_feed_latency_ns: u64,
+    pub max_feed_latency_ns: u64,
     pub total_hedge_volume: f64,
+    pub recalibrations: usize,
+    pub recalibration_failures: usize,
 }
 
 impl std::fmt::Display for MetricsSummary {
@@ -151,6 +242,11 @@ impl std::fmt::Display for MetricsSummary {
             "  Total Hedge Volume: {:.0} MWh",
             self.total_hedge_volume
         )?;
+        writeln!(
+            f,
+            "  Recalibrations:     {} ({} failed)",
+            self.recalibrations, self.recalibration_failures
+        )?;
         writeln!(f, "\nLatency Statistics:")?;
         writeln!(
             f,
@@ -188,6 +284,11 @@ impl std::fmt::Display for MetricsSummary {
             self.max_latency_ns,
             self.max_latency_ns as f64 / 1000.0
         )?;
+        writeln!(
+            f,
+            "  Feed:     {} ns avg, {} ns max (exchange to receive)",
+            self.avg_feed_latency_ns, self.max_feed_latency_ns
+        )?;
         Ok(())
     }
 }
@@ -280,6 +381,19 @@ mod tests {
         assert_eq!(metrics.max_tick_latency_ns(), 200);
     }
 
+    #[test]
+    fn test_metrics_feed_latency() {
+        let mut metrics = Metrics::new();
+        assert_eq!(metrics.avg_feed_latency_ns(), 0);
+
+        metrics.record_feed_latency(20_000);
+        metrics.record_feed_latency(40_000);
+
+        assert_eq!(metrics.avg_feed_latency_ns(), 30_000);
+        assert_eq!(metrics.max_feed_latency_ns(), 40_000);
+        assert_eq!(metrics.ticks_processed(), 0);
+    }
+
     #[test]
     fn test_metrics_hedge() {
         let mut metrics = Metrics::new();
@@ -291,6 +405,19 @@ mod tests {
         assert_eq!(metrics.total_hedge_volume(), 300.0);
     }
 
+    #[test]
+    fn test_metrics_recalibration() {
+        let mut metrics = Metrics::new();
+
+        metrics.record_recalibration(1_000, true);
+        metrics.record_recalibration(3_000, false);
+
+        assert_eq!(metrics.recalibrations(), 2);
+        assert_eq!(metrics.recalibration_failures(), 1);
+        assert_eq!(metrics.avg_recalibration_ns(), 2_000);
+        assert_eq!(metrics.max_recalibration_ns(), 3_000);
+    }
+
     #[test]
     fn test_histogram_percentile() {
         let mut metrics: Metrics = Metrics::new();
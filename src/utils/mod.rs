@@ -1,9 +1,11 @@
 //! Utility functions and helpers
 
+mod clock;
 mod lockfree_queue;
 mod metrics;
 mod timestamp;
 
+pub use clock::{Clock, SharedClock, SimulatedClock, SystemClock};
 pub use lockfree_queue::{LockFreeQueue, MPSCQueue};
 pub use metrics::{Metrics, MetricsSummary};
-pub use timestamp::get_timestamp_ns;
+pub use timestamp::{TscClock, cycles_to_ns, get_timestamp_ns, has_invariant_tsc, read_cycles};
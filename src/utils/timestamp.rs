@@ -1,30 +1,257 @@
 //! High-resolution timestamp utilities
+//!
+//! `get_timestamp_ns` returns wall-clock (Unix epoch) nanoseconds, the same
+//! timebase as `MarketTick::timestamp_ns`. On x86_64 it is derived from the
+//! TSC, calibrated against the system clock at startup and re-anchored
+//! periodically. For latency measurements use `read_cycles` and
+//! `cycles_to_ns`, which never step.
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering, fence};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// Get the current timestamp in nanoseconds
+/// Time spent measuring the TSC frequency at startup
+const CALIBRATION_WINDOW: Duration = Duration::from_millis(10);
+
+/// Wall-clock time between re-anchors (nanoseconds)
+const REANCHOR_INTERVAL_NS: u64 = 1_000_000_000;
+
+/// Fixed-point shift of the ns-per-cycle multiplier
+const MULT_SHIFT: u32 = 32;
+
+static GLOBAL: OnceLock<TscClock> = OnceLock::new();
+
+/// Get the current wall-clock timestamp in nanoseconds since the Unix epoch
 ///
-/// Uses RDTSC on x86_64 for the lowest overhead (~5ns)
-/// Falls back to SystemTime on other architectures (~50-100ns)
+/// Uses the calibrated TSC on x86_64 (~10-20ns); falls back to SystemTime
+/// when the TSC is not invariant or on other architectures (~50-100ns).
+/// The first call calibrates the TSC and takes ~10ms.
 #[inline(always)]
 pub fn get_timestamp_ns() -> u64 {
+    TscClock::global().now_ns()
+}
+
+/// Read the raw cycle counter
+///
+/// RDTSC on x86_64 (~5ns); monotonic nanoseconds elsewhere. Only
+/// differences are meaningful: convert them with `cycles_to_ns`.
+#[inline(always)]
+pub fn read_cycles() -> u64 {
     #[cfg(target_arch = "x86_64")]
     {
-        // RDTSC: Read Time-Stamp Counter. The fastest way to get a timestamp on x86_64
-        // ~5-10 nanoseconds overhead
+        // SAFETY: RDTSC is available on every x86_64 CPU
         unsafe { std::arch::x86_64::_rdtsc() }
     }
 
     #[cfg(not(target_arch = "x86_64"))]
     {
-        // Fallback for non-x86_64 architectures
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
+        static START: OnceLock<std::time::Instant> = OnceLock::new();
+        START
+            .get_or_init(std::time::Instant::now)
+            .elapsed()
             .as_nanos() as u64
     }
 }
 
+/// Convert a cycle count (a difference of `read_cycles`) to nanoseconds
+#[inline(always)]
+pub fn cycles_to_ns(cycles: u64) -> u64 {
+    TscClock::global().cycles_to_ns(cycles)
+}
+
+/// Check whether the CPU advertises an invariant TSC
+///
+/// An invariant TSC ticks at a constant rate regardless of frequency
+/// scaling and C-states, and is synchronized across cores.
+pub fn has_invariant_tsc() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::__cpuid;
+
+        // CPUID.80000007H:EDX[8]
+        let max_extended = __cpuid(0x8000_0000).eax;
+        max_extended >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+#[inline(always)]
+fn system_time_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
+}
+
+/// Cycle counter and wall-clock time read back to back
+fn sample() -> (u64, u64) {
+    let before = read_cycles();
+    let wall_ns = system_time_ns();
+    let after = read_cycles();
+    (before + (after - before) / 2, wall_ns)
+}
+
+/// ns-per-cycle multiplier (fixed-point) between two samples
+fn multiplier(from: (u64, u64), to: (u64, u64)) -> Option<u64> {
+    let cycles = to.0.checked_sub(from.0).filter(|&c| c > 0)?;
+    let ns = to.1.checked_sub(from.1)?;
+    Some((((ns as u128) << MULT_SHIFT) / cycles as u128) as u64)
+}
+
+/// Cycle counter calibrated to wall-clock nanoseconds
+///
+/// Epoch time is extrapolated from an anchor (cycles, wall ns) with the
+/// measured frequency. Once per second the anchor is moved to a fresh
+/// system clock sample and the frequency is refined over the whole
+/// baseline since calibration, so the error stays bounded by the drift
+/// accumulated over one interval.
+pub struct TscClock {
+    /// Cycle counter is usable for wall-clock time
+    reliable: bool,
+
+    /// Calibration start sample
+    base: (u64, u64),
+
+    /// ns per cycle (fixed-point: << MULT_SHIFT)
+    mult: AtomicU64,
+
+    /// Anchor seqlock (odd = update in progress)
+    seq: AtomicU64,
+
+    /// Anchor cycle count
+    anchor_cycles: AtomicU64,
+
+    /// Anchor wall-clock time (nanoseconds)
+    anchor_ns: AtomicU64,
+
+    /// Set while a thread re-anchors
+    reanchoring: AtomicBool,
+}
+
+impl TscClock {
+    /// Calibrate a new clock over the default window (~10ms)
+    pub fn calibrate() -> Self {
+        Self::calibrate_over(CALIBRATION_WINDOW)
+    }
+
+    /// Calibrate a new clock, measuring the frequency over `window`
+    ///
+    /// On x86_64 without an invariant TSC the clock falls back to the
+    /// system clock for wall-clock time.
+    pub fn calibrate_over(window: Duration) -> Self {
+        let start = sample();
+        std::thread::sleep(window);
+        let end = sample();
+
+        let mult = multiplier(start, end);
+        let reliable = mult.is_some() && (has_invariant_tsc() || cfg!(not(target_arch = "x86_64")));
+
+        Self {
+            reliable,
+            base: start,
+            mult: AtomicU64::new(mult.unwrap_or(1 << MULT_SHIFT)),
+            seq: AtomicU64::new(0),
+            anchor_cycles: AtomicU64::new(end.0),
+            anchor_ns: AtomicU64::new(end.1),
+            reanchoring: AtomicBool::new(false),
+        }
+    }
+
+    /// Process-wide clock, calibrated on first use
+    pub fn global() -> &'static TscClock {
+        GLOBAL.get_or_init(Self::calibrate)
+    }
+
+    /// Check whether wall-clock time comes from the cycle counter
+    pub fn is_calibrated(&self) -> bool {
+        self.reliable
+    }
+
+    /// Measured cycle counter frequency (Hz)
+    pub fn frequency_hz(&self) -> f64 {
+        let mult = self.mult.load(Ordering::Relaxed) as f64;
+        1e9 * (1u64 << MULT_SHIFT) as f64 / mult
+    }
+
+    /// Convert a cycle count to nanoseconds
+    #[inline(always)]
+    pub fn cycles_to_ns(&self, cycles: u64) -> u64 {
+        Self::scale(cycles, self.mult.load(Ordering::Relaxed))
+    }
+
+    /// Current wall-clock time (nanoseconds since the Unix epoch)
+    #[inline(always)]
+    pub fn now_ns(&self) -> u64 {
+        if !self.reliable {
+            return system_time_ns();
+        }
+
+        let cycles = read_cycles();
+        let (anchor_cycles, anchor_ns, mult) = self.anchor();
+        let elapsed_ns = Self::scale(cycles.saturating_sub(anchor_cycles), mult);
+
+        if elapsed_ns > REANCHOR_INTERVAL_NS {
+            self.reanchor();
+        }
+
+        anchor_ns + elapsed_ns
+    }
+
+    /// Move the anchor to a fresh system clock sample
+    ///
+    /// Called automatically once per interval; at most one thread
+    /// re-anchors at a time.
+    pub fn reanchor(&self) {
+        if !self.reliable || self.reanchoring.swap(true, Ordering::Acquire) {
+            return;
+        }
+
+        let now = sample();
+        let mult = multiplier(self.base, now).unwrap_or_else(|| self.mult.load(Ordering::Relaxed));
+
+        // Same protocol as `OrderBook::write`: the fence keeps the stores
+        // below from becoming visible before the odd sequence number
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.mult.store(mult, Ordering::Relaxed);
+        self.anchor_cycles.store(now.0, Ordering::Relaxed);
+        self.anchor_ns.store(now.1, Ordering::Relaxed);
+        self.seq.fetch_add(1, Ordering::Release);
+
+        self.reanchoring.store(false, Ordering::Release);
+    }
+
+    /// Consistent (anchor cycles, anchor ns, multiplier)
+    #[inline(always)]
+    fn anchor(&self) -> (u64, u64, u64) {
+        loop {
+            let seq1 = self.seq.load(Ordering::Acquire);
+            if seq1 & 1 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+
+            let cycles = self.anchor_cycles.load(Ordering::Relaxed);
+            let ns = self.anchor_ns.load(Ordering::Relaxed);
+            let mult = self.mult.load(Ordering::Relaxed);
+
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq1 {
+                return (cycles, ns, mult);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn scale(cycles: u64, mult: u64) -> u64 {
+        ((cycles as u128 * mult as u128) >> MULT_SHIFT) as u64
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,6 +269,7 @@ mod tests {
     #[test]
     fn test_timestamp_overhead() {
         let iterations = 10000;
+        let _ = get_timestamp_ns(); // Calibrate outside the measurement
         let start = std::time::Instant::now();
 
         for _ in 0..iterations {
@@ -55,4 +283,41 @@ mod tests {
         println!("Average timestamp overhead: {}ns", avg_ns);
         assert!(avg_ns < 100);
     }
+
+    #[test]
+    fn test_timestamp_is_wall_clock() {
+        let ts = get_timestamp_ns();
+        let wall = system_time_ns();
+
+        assert!(
+            ts.abs_diff(wall) < 1_000_000,
+            "off by {}ns",
+            ts.abs_diff(wall)
+        );
+    }
+
+    #[test]
+    fn test_cycles_to_ns() {
+        let clock = TscClock::calibrate_over(Duration::from_millis(5));
+        assert!(clock.frequency_hz() > 1e8);
+
+        let start = read_cycles();
+        let instant = std::time::Instant::now();
+        std::thread::sleep(Duration::from_millis(20));
+        let elapsed_ns = clock.cycles_to_ns(read_cycles() - start);
+        let expected_ns = instant.elapsed().as_nanos() as u64;
+
+        // Within 2% of the OS monotonic clock
+        assert!(elapsed_ns.abs_diff(expected_ns) < expected_ns / 50);
+    }
+
+    #[test]
+    fn test_reanchor_keeps_wall_clock() {
+        let clock = TscClock::calibrate_over(Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(10));
+        clock.reanchor();
+
+        let ts = clock.now_ns();
+        assert!(ts.abs_diff(system_time_ns()) < 1_000_000);
+    }
 }
@@ -1,5 +1,6 @@
 //! Integration tests
 
+use hedging_engine::hedging::SamplingConfig;
 use hedging_engine::utils::MetricsSummary;
 use hedging_engine::*;
 use std::thread::JoinHandle;
@@ -121,6 +122,7 @@ fn test_mean_reversion_strategy() {
         enable_mvhr: false,
         enable_mean_reversion: true,
         statistics_window_hours: 100, // Small window for testing
+        sampling: SamplingConfig::every_tick(),
         ..Default::default()
     };
 
@@ -133,7 +135,7 @@ fn test_mean_reversion_strategy() {
         43.5, 46.5, 43.0, 47.0, 42.5, 45.0, 44.5, 45.5, 44.0, 46.0, 43.5, 46.5, 43.0, 47.0, 42.5,
     ];
 
-    for (_i, &price) in prices.iter().enumerate() {
+    for &price in prices.iter() {
         let ts: u64 = get_timestamp_ns();
         engine.on_tick(MarketTick::bid(ts, price, 100, 1));
     }